@@ -2,13 +2,15 @@ use std::fs::File;
 use std::io::Write;
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::ArgMatches;
 use colored::Colorize;
 use log::{debug, info};
 
 use rustfst::prelude::*;
 
+use crate::fst_io::{write_fst, CliSemiring, FstInput, FstType, STDIO_PATH};
+
 fn duration_to_seconds(duration: &Duration) -> f64 {
     duration.as_secs() as f64 + duration.subsec_nanos() as f64 * 1.0e-9
 }
@@ -28,43 +30,82 @@ pub trait BinaryFstAlgorithm {
     fn get_path_out(&self) -> &str;
     fn get_algorithm_name(&self) -> String;
 
-    fn read(&self) -> Result<(VectorFst<TropicalWeight>, VectorFst<TropicalWeight>)> {
-        Ok((
-            VectorFst::<TropicalWeight>::read(self.get_path_in_1())?,
-            VectorFst::<TropicalWeight>::read(self.get_path_in_2())?,
-        ))
+    fn read<W: CliSemiring>(
+        &self,
+        inputs: &(FstInput, FstInput),
+    ) -> Result<(VectorFst<W>, VectorFst<W>)> {
+        Ok((inputs.0.read()?, inputs.1.read()?))
     }
 
-    fn run_algorithm(
+    fn run_algorithm<W: CliSemiring>(
         &self,
-        fst_1: VectorFst<TropicalWeight>,
-        fst_2: VectorFst<TropicalWeight>,
-    ) -> Result<VectorFst<TropicalWeight>>;
+        fst_1: VectorFst<W>,
+        fst_2: VectorFst<W>,
+    ) -> Result<VectorFst<W>>;
 
-    fn write(&self, fst: &VectorFst<TropicalWeight>) -> Result<()> {
-        fst.write(self.get_path_out())
+    fn write<W: CliSemiring>(&self, fst: &VectorFst<W>, fst_type: FstType) -> Result<()> {
+        write_fst(fst, self.get_path_out(), fst_type)
     }
 
     fn run_cli_or_bench(&self, m: &ArgMatches) -> Result<()> {
+        if self.get_path_in_1() == STDIO_PATH && self.get_path_in_2() == STDIO_PATH {
+            bail!("Only one of the input FSTs can be read from stdin");
+        }
+        let inputs = (
+            FstInput::new(self.get_path_in_1())?,
+            FstInput::new(self.get_path_in_2())?,
+        );
+        let arc_type_1 = inputs.0.arc_type(m.value_of("arc_type"))?;
+        let arc_type_2 = inputs.1.arc_type(m.value_of("arc_type"))?;
+        if arc_type_1 != arc_type_2 {
+            bail!(
+                "Both FSTs must have the same arc type. Found {} and {}",
+                arc_type_1,
+                arc_type_2
+            );
+        }
+        match arc_type_1.as_str() {
+            "standard" | "tropical" => {
+                self.run_cli_or_bench_with_weight::<TropicalWeight>(m, &inputs)
+            }
+            "log" => self.run_cli_or_bench_with_weight::<LogWeight>(m, &inputs),
+            "tropical64" => self.run_cli_or_bench_with_weight::<Tropical64Weight>(m, &inputs),
+            "log64" => self.run_cli_or_bench_with_weight::<Log64Weight>(m, &inputs),
+            _ => bail!("Unsupported arc_type : {}", arc_type_1),
+        }
+    }
+
+    fn run_cli_or_bench_with_weight<W: CliSemiring>(
+        &self,
+        m: &ArgMatches,
+        inputs: &(FstInput, FstInput),
+    ) -> Result<()> {
+        let fst_type = m.value_of("fst_type").unwrap().parse()?;
         if m.is_present("bench") {
             // Run bench
-            self.run_bench(
+            self.run_bench::<W>(
                 m.value_of("n_warm_ups").unwrap().parse().unwrap(),
                 m.value_of("n_iters").unwrap().parse().unwrap(),
                 m.value_of("export-markdown"),
+                inputs,
+                fst_type,
             )
         } else {
             // Run cli
-            self.run_cli()
+            self.run_cli::<W>(inputs, fst_type)
         }
     }
 
-    fn run_cli(&self) -> Result<()> {
+    fn run_cli<W: CliSemiring>(
+        &self,
+        inputs: &(FstInput, FstInput),
+        fst_type: FstType,
+    ) -> Result<()> {
         info!("Running {} algorithm", self.get_algorithm_name().blue());
         // Parsing
         debug!("Parsing...");
         let parsing_start = Instant::now();
-        let (fst_1, fst_2) = self.read()?;
+        let (fst_1, fst_2) = self.read::<W>(inputs)?;
         let duration_parsing = parsing_start.elapsed();
         debug!("Duration parsing : {:?}", &duration_parsing);
 
@@ -78,20 +119,22 @@ pub trait BinaryFstAlgorithm {
         // Serialization
         debug!("Serialization...");
         let serialization_start = Instant::now();
-        self.write(&fst_out)?;
+        self.write(&fst_out, fst_type)?;
         let duration_serialization = serialization_start.elapsed();
         debug!("Duration serialization : {:?}", &duration_serialization);
 
         Ok(())
     }
 
-    fn run_bench(
+    fn run_bench<W: CliSemiring>(
         &self,
         n_warm_ups: usize,
         n_iters: usize,
         path_markdown_report: Option<&str>,
+        inputs: &(FstInput, FstInput),
+        fst_type: FstType,
     ) -> Result<()> {
-        println!(
+        eprintln!(
             "Running benchmark for algorithm {}",
             self.get_algorithm_name().blue()
         );
@@ -107,7 +150,7 @@ pub trait BinaryFstAlgorithm {
         for i in 0..(n_warm_ups + n_iters) {
             // Parsing
             let parsing_start = Instant::now();
-            let (fst_1, fst_2) = self.read()?;
+            let (fst_1, fst_2) = self.read::<W>(inputs)?;
             let duration_parsing = parsing_start.elapsed();
 
             // Algorithm
@@ -117,11 +160,11 @@ pub trait BinaryFstAlgorithm {
 
             // Serialization
             let serialization_start = Instant::now();
-            self.write(&fst_out)?;
+            self.write(&fst_out, fst_type)?;
             let duration_serialization = serialization_start.elapsed();
 
             if i >= n_warm_ups {
-                println!(
+                eprintln!(
                     "Run #{}/{}: \t{} \t{} \t{}",
                     format!("{}", i + 1 - n_warm_ups).yellow(),
                     format!("{}", n_iters).yellow(),
@@ -145,7 +188,7 @@ pub trait BinaryFstAlgorithm {
                         + duration_to_seconds(&duration_serialization),
                 )
             } else {
-                println!(
+                eprintln!(
                     "Warmup #{}/{}: \t{} \t{} \t{}",
                     format!("{}", i + 1).yellow(),
                     format!("{}", n_warm_ups).yellow(),
@@ -164,27 +207,27 @@ pub trait BinaryFstAlgorithm {
             "Bench results (Warmups = {}, Iterations = {}):",
             n_warm_ups, n_iters
         );
-        println!("{}", s.bold().underline());
+        eprintln!("{}", s.bold().underline());
 
         let s = format!(
             "\t Mean {} : \t\t{}",
             "parsing time".blue(),
             format!("{:.6}s", duration_to_seconds(&avg_parsing_time)).blue()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
         let s = format!(
             "\t Mean {} : \t\t{}",
             "algorithm time".magenta(),
             format!("{:.6}s", duration_to_seconds(&avg_algo_time)).magenta()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
 
         let s = format!(
             "\t Mean {} : \t{}",
             "serialization time".cyan(),
             format!("{:.6}s", duration_to_seconds(&avg_serialization_time)).cyan()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
 
         let mean_total_time = avg_parsing_time + avg_algo_time + avg_serialization_time;
         let s = format!(
@@ -192,7 +235,7 @@ pub trait BinaryFstAlgorithm {
             "CLI time".red(),
             format!("{:.6}s", duration_to_seconds(&mean_total_time)).red()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
 
         if let Some(_path) = path_markdown_report {
             let mut file = File::create(_path)?;
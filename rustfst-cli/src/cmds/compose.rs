@@ -1,12 +1,14 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use clap::ArgMatches;
 
 use rustfst::algorithms::compose::{
-    compose, ComposeFst, ComposeFstOpOptions, LabelReachableData, MatcherFst,
+    check_compose_symbols, compose_with_config, ComposeConfig, ComposeFilterEnum, ComposeFst,
+    ComposeFstOpOptions, LabelReachableData, MatcherEnum, MatcherFst,
 };
 use rustfst::fst_impls::VectorFst;
-use rustfst::semirings::TropicalWeight;
 
 use crate::binary_fst_algorithm::BinaryFstAlgorithm;
+use crate::fst_io::CliSemiring;
 use rustfst::algorithms::compose::compose_filters::{
     AltSequenceComposeFilterBuilder, ComposeFilterBuilder,
 };
@@ -17,7 +19,9 @@ use rustfst::algorithms::compose::lookahead_filters::{
 use rustfst::algorithms::compose::lookahead_matchers::{
     LabelLookAheadMatcher, LookaheadMatcher, MatcherFlagsTrait,
 };
-use rustfst::algorithms::compose::matchers::{MatchType, Matcher, MatcherFlags, SortedMatcher};
+use rustfst::algorithms::compose::matchers::{
+    MatchType, Matcher, MatcherFlags, MatcherRewriteMode, MultiEpsMatcherFlags, SortedMatcher,
+};
 use rustfst::algorithms::lazy::SimpleHashMapCache;
 use rustfst::algorithms::tr_compares::ILabelCompare;
 use rustfst::algorithms::tr_sort;
@@ -33,6 +37,7 @@ pub struct ComposeAlgorithm {
     path_in_2: String,
     path_out: String,
     compose_type: ComposeType,
+    config: ComposeConfig,
 }
 
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
@@ -65,16 +70,25 @@ impl BinaryFstAlgorithm for ComposeAlgorithm {
         "compose".to_string()
     }
 
-    fn run_algorithm(
+    fn run_algorithm<W: CliSemiring>(
         &self,
-        fst_1: VectorFst<TropicalWeight>,
-        mut fst_2: VectorFst<TropicalWeight>,
-    ) -> Result<VectorFst<TropicalWeight>> {
+        fst_1: VectorFst<W>,
+        mut fst_2: VectorFst<W>,
+    ) -> Result<VectorFst<W>> {
         match self.compose_type {
-            ComposeType::Default => {
-                compose::<TropicalWeight, VectorFst<_>, VectorFst<_>, _, _, _>(&fst_1, &fst_2)
-            }
+            ComposeType::Default => Ok(compose_with_config::<
+                W,
+                VectorFst<_>,
+                VectorFst<_>,
+                _,
+                _,
+                _,
+            >(&fst_1, &fst_2, self.config.clone())?),
             ComposeType::LookAhead => {
+                if self.config.compat_symbols {
+                    check_compose_symbols(&fst_1, &fst_2)?;
+                }
+
                 type TLaFst<'a, S, F> = MatcherFst<
                     S,
                     F,
@@ -177,24 +191,118 @@ impl BinaryFstAlgorithm for ComposeAlgorithm {
                         compose_options,
                     )?;
 
-                dyn_fst.compute()
+                Ok(dyn_fst.compute()?)
             }
         }
     }
 }
 
+/// Options of the default composition that the lookahead composition doesn't support.
+const LOOKAHEAD_IGNORED_ARGS: &[&str] = &[
+    "compose_filter",
+    "match_type",
+    "matcher1",
+    "matcher2",
+    "multi_eps_labels",
+    "sigma_label",
+    "rho_label",
+    "phi_label",
+    "phi_loop",
+    "rewrite_mode",
+];
+
 impl ComposeAlgorithm {
-    pub fn new(path_in_1: &str, path_in_2: &str, path_out: &str, compose_type: &str) -> Self {
-        let compose_type = match compose_type {
+    pub fn new(path_in_1: &str, path_in_2: &str, path_out: &str, m: &ArgMatches) -> Result<Self> {
+        let compose_type = match m.value_of("compose_type").unwrap() {
             "default" => ComposeType::Default,
             "lookahead" => ComposeType::LookAhead,
-            _ => panic!("Unexpected compose_type : {}", compose_type),
+            s => bail!("Unexpected compose_type : {}", s),
         };
-        Self {
+        if let ComposeType::LookAhead = compose_type {
+            // The lookahead composition uses its own matchers and filters.
+            for arg in LOOKAHEAD_IGNORED_ARGS {
+                if m.occurrences_of(arg) > 0 {
+                    bail!("--{} can't be used with --compose_type lookahead", arg);
+                }
+            }
+        }
+        Ok(Self {
             path_in_1: path_in_1.to_string(),
             path_in_2: path_in_2.to_string(),
             path_out: path_out.to_string(),
             compose_type,
-        }
+            config: parse_compose_config(m)?,
+        })
     }
 }
+
+fn parse_compose_config(m: &ArgMatches) -> Result<ComposeConfig> {
+    let compose_filter = match m.value_of("compose_filter").unwrap() {
+        "auto" => ComposeFilterEnum::AutoFilter,
+        "null" => ComposeFilterEnum::NullFilter,
+        "trivial" => ComposeFilterEnum::TrivialFilter,
+        "sequence" => ComposeFilterEnum::SequenceFilter,
+        "alt_sequence" => ComposeFilterEnum::AltSequenceFilter,
+        "match" => ComposeFilterEnum::MatchFilter,
+        "no_match" => ComposeFilterEnum::NoMatchFilter,
+        s => bail!("Unexpected compose_filter : {}", s),
+    };
+    let match_type = match m.value_of("match_type").unwrap() {
+        "both" => MatchType::MatchBoth,
+        "input" => MatchType::MatchInput,
+        "output" => MatchType::MatchOutput,
+        s => bail!("Unexpected match_type : {}", s),
+    };
+    Ok(ComposeConfig {
+        compose_filter,
+        connect: true,
+        compat_symbols: m.is_present("compat_symbols"),
+        matcher1: parse_matcher(m, m.value_of("matcher1").unwrap())?,
+        matcher2: parse_matcher(m, m.value_of("matcher2").unwrap())?,
+        match_type,
+    })
+}
+
+fn parse_matcher(m: &ArgMatches, matcher: &str) -> Result<MatcherEnum> {
+    let parse_label = |name: &str| -> Result<usize> {
+        match m.value_of(name) {
+            Some(label) => Ok(label.parse()?),
+            None => bail!("--{} is required by the {} matcher", name, matcher),
+        }
+    };
+    let rewrite_mode = match m.value_of("rewrite_mode").unwrap() {
+        "auto" => MatcherRewriteMode::Auto,
+        "always" => MatcherRewriteMode::Always,
+        "never" => MatcherRewriteMode::Never,
+        s => bail!("Unexpected rewrite_mode : {}", s),
+    };
+    let matcher = match matcher {
+        "sorted" => MatcherEnum::SortedMatcher,
+        "generic" => MatcherEnum::GenericMatcher,
+        "multi_eps" => MatcherEnum::MultiEpsMatcher {
+            multi_eps_labels: m
+                .value_of("multi_eps_labels")
+                .unwrap_or("")
+                .split(',')
+                .filter(|l| !l.is_empty())
+                .map(|l| l.trim().parse())
+                .collect::<std::result::Result<_, _>>()?,
+            flags: MultiEpsMatcherFlags::MULTI_EPS_LOOP | MultiEpsMatcherFlags::MULTI_EPS_LIST,
+        },
+        "sigma" => MatcherEnum::SigmaMatcher {
+            sigma_label: parse_label("sigma_label")?,
+            rewrite_mode,
+        },
+        "rho" => MatcherEnum::RhoMatcher {
+            rho_label: parse_label("rho_label")?,
+            rewrite_mode,
+        },
+        "phi" => MatcherEnum::PhiMatcher {
+            phi_label: parse_label("phi_label")?,
+            rewrite_mode,
+            phi_loop: m.value_of("phi_loop").unwrap() == "true",
+        },
+        s => bail!("Unexpected matcher : {}", s),
+    };
+    Ok(matcher)
+}
@@ -0,0 +1,50 @@
+use anyhow::{bail, Result};
+
+use rustfst::algorithms::determinize::{
+    determinize_with_config, DeterminizeConfig, DeterminizeType,
+};
+use rustfst::prelude::*;
+
+use crate::fst_io::CliSemiring;
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+pub struct DeterminizeAlgorithm {
+    path_in: String,
+    det_type: DeterminizeType,
+    path_out: String,
+}
+
+impl UnaryFstAlgorithm for DeterminizeAlgorithm {
+    fn get_path_in(&self) -> &str {
+        self.path_in.as_str()
+    }
+
+    fn get_path_out(&self) -> &str {
+        self.path_out.as_str()
+    }
+
+    fn get_algorithm_name(&self) -> String {
+        "determinize".to_string()
+    }
+
+    fn run_algorithm<W: CliSemiring>(&self, fst: VectorFst<W>) -> Result<VectorFst<W>> {
+        let config = DeterminizeConfig::default().with_det_type(self.det_type);
+        Ok(determinize_with_config(&fst, config)?)
+    }
+}
+
+impl DeterminizeAlgorithm {
+    pub fn new(path_in: &str, det_type: &str, path_out: &str) -> Result<Self> {
+        let det_type = match det_type {
+            "functional" => DeterminizeType::DeterminizeFunctional,
+            "nonfunctional" => DeterminizeType::DeterminizeNonFunctional,
+            "disambiguate" => DeterminizeType::DeterminizeDisambiguate,
+            _ => bail!("Unknown det_type : {}", det_type),
+        };
+        Ok(Self {
+            path_in: path_in.to_string(),
+            det_type,
+            path_out: path_out.to_string(),
+        })
+    }
+}
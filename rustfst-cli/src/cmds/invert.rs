@@ -2,6 +2,7 @@ use anyhow::Result;
 
 use rustfst::prelude::*;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub struct InvertAlgorithm {
@@ -22,10 +23,7 @@ impl UnaryFstAlgorithm for InvertAlgorithm {
         "invert".to_string()
     }
 
-    fn run_algorithm(
-        &self,
-        mut fst: VectorFst<TropicalWeight>,
-    ) -> Result<VectorFst<TropicalWeight>> {
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
         invert(&mut fst);
         Ok(fst)
     }
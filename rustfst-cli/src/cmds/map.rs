@@ -1,14 +1,14 @@
-use anyhow::{bail, Result};
-use unsafe_unwrap::UnsafeUnwrap;
+use anyhow::{bail, format_err, Result};
 
 use rustfst::prelude::*;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub struct MapAlgorithm {
     path_in: String,
     map_type: String,
-    weight: Option<f32>,
+    weight: Option<String>,
     path_out: String,
 }
 
@@ -25,10 +25,7 @@ impl UnaryFstAlgorithm for MapAlgorithm {
         format!("map {}", self.map_type)
     }
 
-    fn run_algorithm(
-        &self,
-        mut fst: VectorFst<TropicalWeight>,
-    ) -> Result<VectorFst<TropicalWeight>> {
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
         match self.map_type.as_str() {
             "tr_sum" | "arc_sum" => {
                 tr_sum(&mut fst);
@@ -59,8 +56,7 @@ impl UnaryFstAlgorithm for MapAlgorithm {
                 Ok(fst)
             }
             "plus" => {
-                // Safe because there is a check at parsing time.
-                let mapper = tr_mappers::PlusMapper::new(unsafe { self.weight.unsafe_unwrap() });
+                let mapper = tr_mappers::PlusMapper::from_weight(self.parse_weight()?);
                 tr_map(&mut fst, &mapper)?;
                 Ok(fst)
             }
@@ -76,8 +72,7 @@ impl UnaryFstAlgorithm for MapAlgorithm {
                 Ok(fst)
             }
             "times" => {
-                // Safe because there is a check at parsing time.
-                let mapper = tr_mappers::TimesMapper::new(unsafe { self.weight.unsafe_unwrap() });
+                let mapper = tr_mappers::TimesMapper::from_weight(self.parse_weight()?);
                 tr_map(&mut fst, &mapper)?;
                 Ok(fst)
             }
@@ -94,8 +89,16 @@ impl MapAlgorithm {
         Self {
             path_in: path_in.to_string(),
             map_type: map_type.to_string(),
-            weight: weight.map(|f| f.parse().unwrap()),
+            weight: weight.map(|f| f.to_string()),
             path_out: path_out.to_string(),
         }
     }
+
+    fn parse_weight<W: CliSemiring>(&self) -> Result<W> {
+        // The weight is required at parsing time for the map types using it.
+        let weight = self.weight.as_ref().unwrap();
+        let (_, weight) = W::parse_text(weight.as_str())
+            .map_err(|e| format_err!("Can't parse weight {} : {:?}", weight, e))?;
+        Ok(weight)
+    }
 }
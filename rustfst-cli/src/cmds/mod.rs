@@ -1,11 +1,15 @@
 pub mod compose;
 pub mod connect;
+pub mod determinize;
 pub mod invert;
 pub mod map;
 pub mod minimize;
+pub mod optimize;
+pub mod pipeline;
 pub mod project;
 pub mod push;
 pub mod reverse;
+pub mod rm_epsilon;
 pub mod rm_final_epsilon;
 pub mod shortest_path;
 pub mod topsort;
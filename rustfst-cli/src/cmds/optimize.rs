@@ -0,0 +1,90 @@
+use anyhow::{bail, Result};
+use log::info;
+
+use rustfst::algorithms::determinize::DeterminizeType;
+use rustfst::algorithms::encode::EncodeType;
+use rustfst::prelude::*;
+
+use crate::fst_io::CliSemiring;
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+pub struct OptimizeAlgorithm {
+    path_in: String,
+    config: OptimizeConfig,
+    path_out: String,
+}
+
+impl UnaryFstAlgorithm for OptimizeAlgorithm {
+    fn get_path_in(&self) -> &str {
+        self.path_in.as_str()
+    }
+
+    fn get_path_out(&self) -> &str {
+        self.path_out.as_str()
+    }
+
+    fn get_algorithm_name(&self) -> String {
+        "optimize".to_string()
+    }
+
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
+        let sizes = optimize_with_config(&mut fst, self.config)?;
+        for size in sizes {
+            info!(
+                "After {:?} : {} states, {} trs",
+                size.stage, size.num_states, size.num_trs
+            );
+        }
+        Ok(fst)
+    }
+}
+
+impl OptimizeAlgorithm {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        path_in: &str,
+        rm_epsilon: bool,
+        tr_sum: bool,
+        encode: &str,
+        det_type: &str,
+        minimize: bool,
+        push_weights: &str,
+        safe: bool,
+        path_out: &str,
+    ) -> Result<Self> {
+        let encode_type = match encode {
+            "auto" => OptimizeEncodeType::EncodeAuto,
+            "none" => OptimizeEncodeType::EncodeNone,
+            "labels" => OptimizeEncodeType::Encode(EncodeType::EncodeLabels),
+            "weights" => OptimizeEncodeType::Encode(EncodeType::EncodeWeights),
+            "weights_and_labels" => OptimizeEncodeType::Encode(EncodeType::EncodeWeightsAndLabels),
+            _ => bail!("Unknown encode : {}", encode),
+        };
+        let det_type = match det_type {
+            "functional" => Some(DeterminizeType::DeterminizeFunctional),
+            "nonfunctional" => Some(DeterminizeType::DeterminizeNonFunctional),
+            "disambiguate" => Some(DeterminizeType::DeterminizeDisambiguate),
+            "none" => None,
+            _ => bail!("Unknown det_type : {}", det_type),
+        };
+        let push_weights = match push_weights {
+            "none" => None,
+            "to_initial" => Some(ReweightType::ReweightToInitial),
+            "to_final" => Some(ReweightType::ReweightToFinal),
+            _ => bail!("Unknown push_weights : {}", push_weights),
+        };
+        let config = OptimizeConfig::default()
+            .with_rm_epsilon(rm_epsilon)
+            .with_tr_sum(tr_sum)
+            .with_encode_type(encode_type)
+            .with_det_type(det_type)
+            .with_minimize(minimize)
+            .with_push_weights(push_weights)
+            .with_safe(safe);
+        Ok(Self {
+            path_in: path_in.to_string(),
+            config,
+            path_out: path_out.to_string(),
+        })
+    }
+}
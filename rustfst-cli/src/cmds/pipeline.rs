@@ -0,0 +1,201 @@
+use anyhow::{bail, format_err, Result};
+use clap::{App, ArgMatches};
+
+use rustfst::prelude::*;
+
+use crate::cmds::connect::ConnectAlgorithm;
+use crate::cmds::determinize::DeterminizeAlgorithm;
+use crate::cmds::invert::InvertAlgorithm;
+use crate::cmds::map::MapAlgorithm;
+use crate::cmds::minimize::MinimizeAlgorithm;
+use crate::cmds::optimize::OptimizeAlgorithm;
+use crate::cmds::project::ProjectFstAlgorithm;
+use crate::cmds::push::PushAlgorithm;
+use crate::cmds::reverse::ReverseAlgorithm;
+use crate::cmds::rm_epsilon::RmEpsilonAlgorithm;
+use crate::cmds::rm_final_epsilon::RmFinalEpsilonAlgorithm;
+use crate::cmds::shortest_path::ShortestPathAlgorithm;
+use crate::cmds::topsort::TopsortAlgorithm;
+use crate::cmds::tr_sort::TrsortAlgorithm;
+use crate::fst_io::CliSemiring;
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+macro_rules! unary_cmd {
+    ($($variant: ident($algo: ty)),*) => {
+        /// Any of the algorithms running on a single FST.
+        pub enum UnaryCmd {
+            $($variant($algo)),*
+        }
+
+        impl UnaryFstAlgorithm for UnaryCmd {
+            fn get_path_in(&self) -> &str {
+                match self {
+                    $(UnaryCmd::$variant(algo) => algo.get_path_in()),*
+                }
+            }
+
+            fn get_path_out(&self) -> &str {
+                match self {
+                    $(UnaryCmd::$variant(algo) => algo.get_path_out()),*
+                }
+            }
+
+            fn get_algorithm_name(&self) -> String {
+                match self {
+                    $(UnaryCmd::$variant(algo) => algo.get_algorithm_name()),*
+                }
+            }
+
+            fn run_algorithm<W: CliSemiring>(&self, fst: VectorFst<W>) -> Result<VectorFst<W>> {
+                match self {
+                    $(UnaryCmd::$variant(algo) => algo.run_algorithm(fst)),*
+                }
+            }
+        }
+    };
+}
+
+unary_cmd!(
+    Minimize(MinimizeAlgorithm),
+    Connect(ConnectAlgorithm),
+    TrSort(TrsortAlgorithm),
+    Project(ProjectFstAlgorithm),
+    Invert(InvertAlgorithm),
+    Topsort(TopsortAlgorithm),
+    Reverse(ReverseAlgorithm),
+    Map(MapAlgorithm),
+    ShortestPath(ShortestPathAlgorithm),
+    RmFinalEpsilon(RmFinalEpsilonAlgorithm),
+    RmEpsilon(RmEpsilonAlgorithm),
+    Determinize(DeterminizeAlgorithm),
+    Push(PushAlgorithm),
+    Optimize(OptimizeAlgorithm)
+);
+
+impl UnaryCmd {
+    /// Builds the algorithm corresponding to the subcommand `name` parsed into `m`.
+    pub fn new(name: &str, m: &ArgMatches, path_in: &str, path_out: &str) -> Result<Self> {
+        let cmd = match name {
+            "minimize" => UnaryCmd::Minimize(MinimizeAlgorithm::new(
+                path_in,
+                m.is_present("allow_nondet"),
+                path_out,
+            )),
+            "connect" => UnaryCmd::Connect(ConnectAlgorithm::new(path_in, path_out)),
+            "tr_sort" => UnaryCmd::TrSort(TrsortAlgorithm::new(
+                path_in,
+                m.value_of("sort_type").unwrap(),
+                path_out,
+            )),
+            "project" => UnaryCmd::Project(ProjectFstAlgorithm::new(
+                path_in,
+                m.is_present("project_output"),
+                path_out,
+            )),
+            "invert" => UnaryCmd::Invert(InvertAlgorithm::new(path_in, path_out)),
+            "topsort" => UnaryCmd::Topsort(TopsortAlgorithm::new(path_in, path_out)),
+            "reverse" => UnaryCmd::Reverse(ReverseAlgorithm::new(path_in, path_out)),
+            "map" => UnaryCmd::Map(MapAlgorithm::new(
+                path_in,
+                m.value_of("map_type").unwrap(),
+                m.value_of("weight"),
+                path_out,
+            )),
+            "shortestpath" => UnaryCmd::ShortestPath(ShortestPathAlgorithm::new(
+                path_in,
+                m.is_present("unique"),
+                m.value_of("nshortest").unwrap().parse()?,
+                path_out,
+            )),
+            "rmfinalepsilon" => {
+                UnaryCmd::RmFinalEpsilon(RmFinalEpsilonAlgorithm::new(path_in, path_out))
+            }
+            "rmepsilon" => UnaryCmd::RmEpsilon(RmEpsilonAlgorithm::new(path_in, path_out)),
+            "determinize" => UnaryCmd::Determinize(DeterminizeAlgorithm::new(
+                path_in,
+                m.value_of("det_type").unwrap(),
+                path_out,
+            )?),
+            "push" => UnaryCmd::Push(PushAlgorithm::new(
+                path_in,
+                path_out,
+                m.is_present("to_final"),
+                m.is_present("push_weights"),
+                m.is_present("push_labels"),
+                m.is_present("remove_total_weight"),
+                m.is_present("remove_common_affix"),
+            )),
+            "optimize" => UnaryCmd::Optimize(OptimizeAlgorithm::new(
+                path_in,
+                !m.is_present("no_rm_epsilon"),
+                !m.is_present("no_tr_sum"),
+                m.value_of("encode").unwrap(),
+                m.value_of("det_type").unwrap(),
+                !m.is_present("no_minimize"),
+                m.value_of("push_weights").unwrap(),
+                m.is_present("safe"),
+                path_out,
+            )?),
+            _ => bail!("Unknown subcommand {}.", name),
+        };
+        Ok(cmd)
+    }
+}
+
+/// Runs a sequence of unary algorithms. The FST is only parsed before the first step and
+/// serialized after the last one.
+pub struct PipelineAlgorithm {
+    path_in: String,
+    path_out: String,
+    steps: Vec<UnaryCmd>,
+}
+
+impl UnaryFstAlgorithm for PipelineAlgorithm {
+    fn get_path_in(&self) -> &str {
+        self.path_in.as_str()
+    }
+
+    fn get_path_out(&self) -> &str {
+        self.path_out.as_str()
+    }
+
+    fn get_algorithm_name(&self) -> String {
+        let names: Vec<_> = self.steps.iter().map(|s| s.get_algorithm_name()).collect();
+        format!("pipeline ({})", names.join(" | "))
+    }
+
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
+        for step in &self.steps {
+            fst = step.run_algorithm(fst)?;
+        }
+        Ok(fst)
+    }
+}
+
+impl PipelineAlgorithm {
+    /// Parses `operations`, a list of subcommands separated by `|`, with `step_parser`.
+    pub fn new(path_in: &str, operations: &str, path_out: &str, step_parser: App) -> Result<Self> {
+        let steps = operations
+            .split('|')
+            .map(|operation| {
+                let args: Vec<_> = operation.split_whitespace().collect();
+                if args.is_empty() {
+                    bail!("Empty operation in pipeline : {}", operations);
+                }
+                let matches = step_parser
+                    .clone()
+                    .get_matches_from_safe(args)
+                    .map_err(|e| format_err!("Can't parse operation {} : {}", operation, e))?;
+                match matches.subcommand() {
+                    (name, Some(m)) => UnaryCmd::new(name, m, path_in, path_out),
+                    (name, None) => bail!("Unknown subcommand {}.", name),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            path_in: path_in.to_string(),
+            path_out: path_out.to_string(),
+            steps,
+        })
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 
 use rustfst::prelude::*;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub struct ProjectFstAlgorithm {
@@ -23,10 +24,7 @@ impl UnaryFstAlgorithm for ProjectFstAlgorithm {
         "project".into()
     }
 
-    fn run_algorithm(
-        &self,
-        mut fst: VectorFst<TropicalWeight>,
-    ) -> Result<VectorFst<TropicalWeight>> {
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
         project(&mut fst, self.project_type);
         Ok(fst)
     }
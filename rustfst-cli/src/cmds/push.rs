@@ -2,6 +2,7 @@ use anyhow::Result;
 
 use rustfst::prelude::*;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub struct PushAlgorithm {
@@ -24,8 +25,8 @@ impl UnaryFstAlgorithm for PushAlgorithm {
         "push".to_string()
     }
 
-    fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>> {
-        push(&fst, self.reweight_type, self.push_type)
+    fn run_algorithm<W: CliSemiring>(&self, fst: VectorFst<W>) -> Result<VectorFst<W>> {
+        Ok(push(&fst, self.reweight_type, self.push_type)?)
     }
 }
 
@@ -2,6 +2,7 @@ use rustfst::prelude::*;
 
 use anyhow::Result;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub struct ReverseAlgorithm {
@@ -22,8 +23,8 @@ impl UnaryFstAlgorithm for ReverseAlgorithm {
         "reverse".to_string()
     }
 
-    fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>> {
-        reverse(&fst)
+    fn run_algorithm<W: CliSemiring>(&self, fst: VectorFst<W>) -> Result<VectorFst<W>> {
+        Ok(reverse(&fst)?)
     }
 }
 
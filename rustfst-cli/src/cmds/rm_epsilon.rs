@@ -0,0 +1,40 @@
+use anyhow::Result;
+
+use rustfst::algorithms::rm_epsilon::rm_epsilon;
+use rustfst::prelude::*;
+
+use crate::fst_io::CliSemiring;
+use crate::unary_fst_algorithm::UnaryFstAlgorithm;
+
+pub struct RmEpsilonAlgorithm {
+    path_in: String,
+    path_out: String,
+}
+
+impl UnaryFstAlgorithm for RmEpsilonAlgorithm {
+    fn get_path_in(&self) -> &str {
+        self.path_in.as_str()
+    }
+
+    fn get_path_out(&self) -> &str {
+        self.path_out.as_str()
+    }
+
+    fn get_algorithm_name(&self) -> String {
+        "rmepsilon".to_string()
+    }
+
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
+        rm_epsilon(&mut fst)?;
+        Ok(fst)
+    }
+}
+
+impl RmEpsilonAlgorithm {
+    pub fn new(path_in: &str, path_out: &str) -> Self {
+        Self {
+            path_in: path_in.to_string(),
+            path_out: path_out.to_string(),
+        }
+    }
+}
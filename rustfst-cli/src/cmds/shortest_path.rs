@@ -1,5 +1,6 @@
 use rustfst::prelude::*;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 use anyhow::Result;
 
@@ -23,11 +24,11 @@ impl UnaryFstAlgorithm for ShortestPathAlgorithm {
         "shortest path".to_string()
     }
 
-    fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>> {
+    fn run_algorithm<W: CliSemiring>(&self, fst: VectorFst<W>) -> Result<VectorFst<W>> {
         let config = ShortestPathConfig::default()
             .with_nshortest(self.nshortest)
             .with_unique(self.unique);
-        shortest_path_with_config(&fst, config)
+        Ok(shortest_path_with_config(&fst, config)?)
     }
 }
 
@@ -2,6 +2,7 @@ use rustfst::prelude::*;
 
 use anyhow::Result;
 
+use crate::fst_io::CliSemiring;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub struct TopsortAlgorithm {
@@ -22,10 +23,7 @@ impl UnaryFstAlgorithm for TopsortAlgorithm {
         "topsort".to_string()
     }
 
-    fn run_algorithm(
-        &self,
-        mut fst: VectorFst<TropicalWeight>,
-    ) -> Result<VectorFst<TropicalWeight>> {
+    fn run_algorithm<W: CliSemiring>(&self, mut fst: VectorFst<W>) -> Result<VectorFst<W>> {
         top_sort(&mut fst)?;
         Ok(fst)
     }
@@ -0,0 +1,143 @@
+use std::fs::read;
+use std::io::{stdin, stdout, BufWriter, Read};
+use std::str::FromStr;
+
+use anyhow::{bail, format_err, Context, Result};
+
+use rustfst::prelude::*;
+use rustfst::BinaryFstTypes;
+
+/// Bounds required on the weights by all the commands of the CLI.
+///
+/// All the semirings supported by the CLI are their own reverse.
+pub trait CliSemiring:
+    SerializableSemiring + WeaklyDivisibleSemiring + WeightQuantize + Semiring<ReverseWeight = Self>
+{
+}
+
+impl<W> CliSemiring for W where
+    W: SerializableSemiring
+        + WeaklyDivisibleSemiring
+        + WeightQuantize
+        + Semiring<ReverseWeight = Self>
+{
+}
+
+/// Arc types supported by the CLI.
+pub static ARC_TYPES: &[&str] = &["standard", "log", "tropical64", "log64"];
+
+/// Type of the FST written by the CLI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FstType {
+    Vector,
+    Const,
+}
+
+impl FromStr for FstType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "vector" => Ok(FstType::Vector),
+            "const" => Ok(FstType::Const),
+            _ => bail!("Unknown fst_type : {}", s),
+        }
+    }
+}
+
+/// Path used to read an FST from stdin or to write an FST to stdout.
+pub static STDIO_PATH: &str = "-";
+
+/// Binary content of an input FST.
+///
+/// The file is read again every time the FST is loaded. Stdin can only be read once, so its
+/// content is kept in memory.
+pub struct FstInput {
+    path: String,
+    stdin_data: Option<Vec<u8>>,
+}
+
+impl FstInput {
+    pub fn new(path: &str) -> Result<Self> {
+        let stdin_data = if path == STDIO_PATH {
+            let mut data = vec![];
+            stdin()
+                .lock()
+                .read_to_end(&mut data)
+                .with_context(|| "Can't read FST from stdin")?;
+            Some(data)
+        } else {
+            None
+        };
+        Ok(Self {
+            path: path.to_string(),
+            stdin_data,
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    /// Types stored in the header of the FST.
+    pub fn types(&self) -> Result<BinaryFstTypes> {
+        match &self.stdin_data {
+            Some(data) => Ok(BinaryFstTypes::parse(data)?),
+            None => Ok(BinaryFstTypes::read(&self.path)?),
+        }
+    }
+
+    /// Returns the arc type to use to load the FST. The one stored in the header of the FST is
+    /// used except if `arc_type` is set.
+    pub fn arc_type(&self, arc_type: Option<&str>) -> Result<String> {
+        if let Some(arc_type) = arc_type {
+            return Ok(arc_type.to_string());
+        }
+        Ok(self.types()?.tr_type)
+    }
+
+    /// Loads the FST, whatever the type of FST stored.
+    pub fn read<W: CliSemiring>(&self) -> Result<VectorFst<W>> {
+        match &self.stdin_data {
+            Some(data) => load_fst(data, self.path()),
+            None => {
+                let data = read(&self.path)
+                    .with_context(|| format!("Can't open binary FST file : {}", self.path))?;
+                load_fst(&data, self.path())
+            }
+        }
+    }
+}
+
+fn load_fst<W: CliSemiring>(data: &[u8], path: &str) -> Result<VectorFst<W>> {
+    let fst_types = BinaryFstTypes::parse(data)?;
+    if fst_types.fst_type == VectorFst::<W>::fst_type() {
+        Ok(VectorFst::<W>::load(data)?)
+    } else if fst_types.fst_type == ConstFst::<W>::fst_type() {
+        let fst = ConstFst::<W>::load(data)?;
+        Ok(fst_convert(fst))
+    } else {
+        Err(format_err!(
+            "Unsupported fst_type {} for file {}",
+            fst_types.fst_type,
+            path
+        ))
+    }
+}
+
+/// Writes a binary FST with the requested FST type. Writes to stdout if `path` is `-`.
+pub fn write_fst<W: CliSemiring>(fst: &VectorFst<W>, path: &str, fst_type: FstType) -> Result<()> {
+    if path == STDIO_PATH {
+        let stdout = stdout();
+        let output = BufWriter::new(stdout.lock());
+        match fst_type {
+            FstType::Vector => fst.store(output),
+            FstType::Const => ConstFst::from(fst.clone()).store(output),
+        }
+    } else {
+        match fst_type {
+            FstType::Vector => fst.write(path),
+            FstType::Const => ConstFst::from(fst.clone()).write(path),
+        }
+    }
+}
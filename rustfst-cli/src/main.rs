@@ -1,34 +1,156 @@
 use std::process;
 
 use anyhow::{format_err, Result};
-use clap::{App, Arg, SubCommand};
+use clap::{App, AppSettings, Arg, SubCommand};
 use log::error;
 
 use crate::binary_fst_algorithm::BinaryFstAlgorithm;
 use crate::cmds::compose::ComposeAlgorithm;
-use crate::cmds::connect::ConnectAlgorithm;
-use crate::cmds::invert::InvertAlgorithm;
-use crate::cmds::map::MapAlgorithm;
-use crate::cmds::minimize::MinimizeAlgorithm;
-use crate::cmds::project::ProjectFstAlgorithm;
-use crate::cmds::push::PushAlgorithm;
-use crate::cmds::reverse::ReverseAlgorithm;
-use crate::cmds::rm_final_epsilon::RmFinalEpsilonAlgorithm;
-use crate::cmds::shortest_path::ShortestPathAlgorithm;
-use crate::cmds::topsort::TopsortAlgorithm;
-use crate::cmds::tr_sort::TrsortAlgorithm;
+use crate::cmds::pipeline::{PipelineAlgorithm, UnaryCmd};
+use crate::fst_io::ARC_TYPES;
 use crate::unary_fst_algorithm::UnaryFstAlgorithm;
 
 pub mod binary_fst_algorithm;
 pub mod cmds;
+pub mod fst_io;
 pub mod unary_fst_algorithm;
 
+const MATCHERS: &[&str] = &["sorted", "generic", "multi_eps", "sigma", "rho", "phi"];
+
 fn main() {
     let mut app = App::new("rustfst")
         .version("1.0")
         .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
         .about("Rustfst CLI");
 
+    for cmd in unary_subcommands() {
+        app = app.subcommand(one_in_one_out_options(cmd));
+    }
+
+    // Compose
+    let compose_cmd = SubCommand::with_name("compose")
+        .about("Compose algorithm")
+        .arg(
+            Arg::with_name("compose_type")
+                .long("compose_type")
+                .possible_values(&["default", "lookahead"])
+                .takes_value(true)
+                .default_value("default"),
+        )
+        .arg(
+            Arg::with_name("compat_symbols")
+                .help("Fails if the output symbols of in_1.fst don't match the input symbols of in_2.fst.")
+                .long("compat_symbols"),
+        )
+        .arg(
+            Arg::with_name("compose_filter")
+                .help("Composition filter used by the default composition.")
+                .long("compose_filter")
+                .possible_values(&[
+                    "auto",
+                    "null",
+                    "trivial",
+                    "sequence",
+                    "alt_sequence",
+                    "match",
+                    "no_match",
+                ])
+                .takes_value(true)
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::with_name("match_type")
+                .help("Side on which the matching is performed.")
+                .long("match_type")
+                .possible_values(&["both", "input", "output"])
+                .takes_value(true)
+                .default_value("both"),
+        )
+        .arg(
+            Arg::with_name("matcher1")
+                .help("Matcher used on the output labels of in_1.fst.")
+                .long("matcher1")
+                .possible_values(MATCHERS)
+                .takes_value(true)
+                .default_value("sorted"),
+        )
+        .arg(
+            Arg::with_name("matcher2")
+                .help("Matcher used on the input labels of in_2.fst.")
+                .long("matcher2")
+                .possible_values(MATCHERS)
+                .takes_value(true)
+                .default_value("sorted"),
+        )
+        .arg(
+            Arg::with_name("multi_eps_labels")
+                .help("Comma separated labels treated as epsilons by the multi_eps matcher.")
+                .long("multi_eps_labels")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("sigma_label")
+                .help("Label matching any non-epsilon label with the sigma matcher.")
+                .long("sigma_label")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("rho_label")
+                .help("Label matching any label not explicitly matched with the rho matcher.")
+                .long("rho_label")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("phi_label")
+                .help("Failure label of the phi matcher.")
+                .long("phi_label")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("phi_loop")
+                .help("Whether a phi self-loop matches any label with the phi matcher.")
+                .long("phi_loop")
+                .possible_values(&["true", "false"])
+                .takes_value(true)
+                .default_value("true"),
+        )
+        .arg(
+            Arg::with_name("rewrite_mode")
+                .help("Rewriting of the special labels of the sigma, rho and phi matchers.")
+                .long("rewrite_mode")
+                .possible_values(&["auto", "always", "never"])
+                .takes_value(true)
+                .default_value("auto"),
+        );
+    app = app.subcommand(two_in_one_out_options(compose_cmd));
+
+    // Pipeline
+    let pipeline_cmd = SubCommand::with_name("pipeline")
+        .about("Runs a sequence of unary operations without serializing the intermediate FSTs.")
+        .arg(
+            Arg::with_name("operations")
+                .help("Operations separated by `|`. For instance \"rmepsilon | determinize | minimize\".")
+                .required(true),
+        );
+    app = app.subcommand(one_in_one_out_options(pipeline_cmd));
+
+    let matches = app.get_matches();
+
+    let env = env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "debug");
+
+    env_logger::Builder::from_env(env)
+        .default_format_timestamp_nanos(true)
+        .init();
+
+    if let Err(e) = handle(matches) {
+        error!("{:?}", e);
+        process::exit(exitcode::OK)
+    }
+}
+
+/// Subcommands running an algorithm on a single FST. Their input and output arguments are added
+/// separately so that they can also be used as the steps of a pipeline.
+fn unary_subcommands<'a, 'b>() -> Vec<App<'a, 'b>> {
     // Minimization
     let minimize_cmd = SubCommand::with_name("minimize")
         .about("Minimization algorithm.")
@@ -37,11 +159,9 @@ fn main() {
                 .help("Minimize non-deterministic FSTs ?")
                 .long("allow_nondet"),
         );
-    app = app.subcommand(one_in_one_out_options(minimize_cmd));
 
     // Connect
     let connect_cmd = SubCommand::with_name("connect").about("Connect algorithm.");
-    app = app.subcommand(one_in_one_out_options(connect_cmd));
 
     // Trsort
     let tr_sort_cmd = SubCommand::with_name("tr_sort")
@@ -55,7 +175,6 @@ fn main() {
                 .possible_values(&["ilabel", "olabel"])
                 .default_value("ilabel"),
         );
-    app = app.subcommand(one_in_one_out_options(tr_sort_cmd));
 
     // Project
     let project_cmd = SubCommand::with_name("project")
@@ -65,19 +184,15 @@ fn main() {
                 .help("Project output (vs. input)")
                 .long("project_output"),
         );
-    app = app.subcommand(one_in_one_out_options(project_cmd));
 
     // Invert
     let invert_cmd = SubCommand::with_name("invert").about("Invert algorithm.");
-    app = app.subcommand(one_in_one_out_options(invert_cmd));
 
     // Topsort
     let topsort_cmd = SubCommand::with_name("topsort").about("Topsort algorithm.");
-    app = app.subcommand(one_in_one_out_options(topsort_cmd));
 
     // Reverse
     let reverse_cmd = SubCommand::with_name("reverse").about("Reverse algorithm.");
-    app = app.subcommand(one_in_one_out_options(reverse_cmd));
 
     // Map
     let map_cmd = SubCommand::with_name("map")
@@ -109,7 +224,6 @@ fn main() {
                 .takes_value(true)
                 .required_ifs(&[("map_type", "plus"), ("map_type", "times")]),
         );
-    app = app.subcommand(one_in_one_out_options(map_cmd));
 
     // Shortest Path
     let shortest_path_cmd = SubCommand::with_name("shortestpath")
@@ -126,12 +240,25 @@ fn main() {
                 .long("unique")
                 .help("Return unique strings"),
         );
-    app = app.subcommand(one_in_one_out_options(shortest_path_cmd));
 
     // Rm Final Epsilon
     let rm_final_epsilon_cmd =
         SubCommand::with_name("rmfinalepsilon").about("RmFinalEpsilon algorithm.");
-    app = app.subcommand(one_in_one_out_options(rm_final_epsilon_cmd));
+
+    // Rm Epsilon
+    let rm_epsilon_cmd = SubCommand::with_name("rmepsilon").about("RmEpsilon algorithm.");
+
+    // Determinize
+    let determinize_cmd = SubCommand::with_name("determinize")
+        .about("Determinization algorithm.")
+        .arg(
+            Arg::with_name("det_type")
+                .long("det_type")
+                .takes_value(true)
+                .possible_values(&["functional", "nonfunctional", "disambiguate"])
+                .default_value("functional")
+                .help("Type of determinization."),
+        );
 
     // Push
     let push_cmd = SubCommand::with_name("push")
@@ -141,127 +268,121 @@ fn main() {
         .arg(Arg::with_name("push_labels").long("push_labels"))
         .arg(Arg::with_name("remove_total_weight").long("remove_total_weight"))
         .arg(Arg::with_name("remove_common_affix").long("remove_common_affix"));
-    app = app.subcommand(one_in_one_out_options(push_cmd));
 
-    // Compose
-    let compose_cmd = SubCommand::with_name("compose")
-        .about("Compose algorithm")
+    // Optimize
+    let optimize_cmd = SubCommand::with_name("optimize")
+        .about("Optimization algorithm: epsilon removal, determinization and minimization.")
         .arg(
-            Arg::with_name("compose_type")
-                .long("compose_type")
-                .possible_values(&["default", "lookahead"])
+            Arg::with_name("no_rm_epsilon")
+                .help("Don't remove the epsilon transitions.")
+                .long("no_rm_epsilon"),
+        )
+        .arg(
+            Arg::with_name("no_tr_sum")
+                .help("Don't sum the transitions with the same labels and destination.")
+                .long("no_tr_sum"),
+        )
+        .arg(
+            Arg::with_name("encode")
+                .help("Encoding applied before the determinization and the minimization.")
+                .long("encode")
                 .takes_value(true)
-                .default_value("default"),
+                .possible_values(&["auto", "none", "labels", "weights", "weights_and_labels"])
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::with_name("det_type")
+                .help("Type of determinization.")
+                .long("det_type")
+                .takes_value(true)
+                .possible_values(&["functional", "nonfunctional", "disambiguate", "none"])
+                .default_value("functional"),
+        )
+        .arg(
+            Arg::with_name("no_minimize")
+                .help("Don't minimize.")
+                .long("no_minimize"),
+        )
+        .arg(
+            Arg::with_name("push_weights")
+                .help("Pushes the weights at the end.")
+                .long("push_weights")
+                .takes_value(true)
+                .possible_values(&["none", "to_initial", "to_final"])
+                .default_value("none"),
+        )
+        .arg(
+            Arg::with_name("safe")
+                .help("Skip the determinization of cyclic FSTs that might not be functional.")
+                .long("safe"),
         );
-    app = app.subcommand(two_in_one_out_options(compose_cmd));
-
-    let matches = app.get_matches();
-
-    let env = env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "debug");
 
-    env_logger::Builder::from_env(env)
-        .default_format_timestamp_nanos(true)
-        .init();
+    vec![
+        minimize_cmd,
+        connect_cmd,
+        tr_sort_cmd,
+        project_cmd,
+        invert_cmd,
+        topsort_cmd,
+        reverse_cmd,
+        map_cmd,
+        shortest_path_cmd,
+        rm_final_epsilon_cmd,
+        rm_epsilon_cmd,
+        determinize_cmd,
+        push_cmd,
+        optimize_cmd,
+    ]
+}
 
-    if let Err(e) = handle(matches) {
-        error!("{:?}", e);
-        process::exit(exitcode::OK)
-    }
+/// Parser for the steps of a pipeline.
+fn pipeline_step_parser<'a, 'b>() -> App<'a, 'b> {
+    App::new("pipeline step")
+        .setting(AppSettings::NoBinaryName)
+        .setting(AppSettings::SubcommandRequired)
+        .subcommands(unary_subcommands())
 }
 
 /// Handles the command-line input.
 fn handle(matches: clap::ArgMatches) -> Result<()> {
     match matches.subcommand() {
-        ("minimize", Some(m)) => MinimizeAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.is_present("allow_nondet"),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("connect", Some(m)) => ConnectAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("tr_sort", Some(m)) => TrsortAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("sort_type").unwrap(),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("project", Some(m)) => ProjectFstAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.is_present("project_output"),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("invert", Some(m)) => InvertAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("topsort", Some(m)) => TopsortAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("reverse", Some(m)) => ReverseAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("out.fst").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        ("map", Some(m)) => MapAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("map_type").unwrap(),
-            m.value_of("weight"),
+        ("compose", Some(m)) => ComposeAlgorithm::new(
+            m.value_of("in_1.fst").unwrap(),
+            m.value_of("in_2.fst").unwrap(),
             m.value_of("out.fst").unwrap(),
-        )
+            m,
+        )?
         .run_cli_or_bench(m),
-        ("shortestpath", Some(m)) => ShortestPathAlgorithm::new(
+        ("pipeline", Some(m)) => PipelineAlgorithm::new(
             m.value_of("in.fst").unwrap(),
-            m.is_present("unique"),
-            m.value_of("nshortest").unwrap().parse().unwrap(),
+            m.value_of("operations").unwrap(),
             m.value_of("out.fst").unwrap(),
-        )
+            pipeline_step_parser(),
+        )?
         .run_cli_or_bench(m),
-        ("rmfinalepsilon", Some(m)) => RmFinalEpsilonAlgorithm::new(
+        (s, Some(m)) => UnaryCmd::new(
+            s,
+            m,
             m.value_of("in.fst").unwrap(),
             m.value_of("out.fst").unwrap(),
-        )
+        )?
         .run_cli_or_bench(m),
-        ("push", Some(m)) => PushAlgorithm::new(
-            m.value_of("in.fst").unwrap(),
-            m.value_of("out.fst").unwrap(),
-            m.is_present("to_final"),
-            m.is_present("push_weights"),
-            m.is_present("push_labels"),
-            m.is_present("remove_total_weight"),
-            m.is_present("remove_common_affix"),
-        )
-        .run_cli_or_bench(m),
-        ("compose", Some(m)) => ComposeAlgorithm::new(
-            m.value_of("in_1.fst").unwrap(),
-            m.value_of("in_2.fst").unwrap(),
-            m.value_of("out.fst").unwrap(),
-            m.value_of("compose_type").unwrap(),
-        )
-        .run_cli_or_bench(m),
-        (s, _) => Err(format_err!("Unknown subcommand {}.", s)),
+        (s, None) => Err(format_err!("Unknown subcommand {}.", s)),
     }
 }
 
 fn one_in_one_out_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
-    command
+    let command = command
         .version("1.0")
         .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
         .arg(
             Arg::with_name("in.fst")
-                .help("Path to input fst file.")
+                .help("Path to input fst file. Use `-` to read from stdin.")
                 .required(true),
         )
         .arg(
             Arg::with_name("out.fst")
-                .help("Path to output fst file.")
+                .help("Path to output fst file. Use `-` to write to stdout.")
                 .required(true),
         ).arg(
             Arg::with_name("bench")
@@ -281,26 +402,27 @@ fn one_in_one_out_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, '
         Arg::with_name("export-markdown")
             .long("export-markdown")
             .takes_value(true)
-    )
+    );
+    arc_type_and_fst_type_options(command)
 }
 
 fn two_in_one_out_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
-    command
+    let command = command
         .version("1.0")
         .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
         .arg(
             Arg::with_name("in_1.fst")
-                .help("Path to the first input fst file.")
+                .help("Path to the first input fst file. Use `-` to read from stdin.")
                 .required(true),
         )
         .arg(
             Arg::with_name("in_2.fst")
-                .help("Path to the second input fst file.")
+                .help("Path to the second input fst file. Use `-` to read from stdin.")
                 .required(true),
         )
         .arg(
             Arg::with_name("out.fst")
-                .help("Path to output fst file.")
+                .help("Path to output fst file. Use `-` to write to stdout.")
                 .required(true),
         ).arg(
         Arg::with_name("bench")
@@ -320,5 +442,25 @@ fn two_in_one_out_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, '
         Arg::with_name("export-markdown")
             .long("export-markdown")
             .takes_value(true)
-    )
+    );
+    arc_type_and_fst_type_options(command)
+}
+
+fn arc_type_and_fst_type_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
+    command
+        .arg(
+            Arg::with_name("arc_type")
+                .long("arc_type")
+                .takes_value(true)
+                .possible_values(ARC_TYPES)
+                .help("Arc type used to load the input FSTs. By default, read from their header."),
+        )
+        .arg(
+            Arg::with_name("fst_type")
+                .long("fst_type")
+                .takes_value(true)
+                .possible_values(&["vector", "const"])
+                .default_value("vector")
+                .help("Type of the output FST."),
+        )
 }
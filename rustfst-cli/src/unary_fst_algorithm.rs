@@ -2,13 +2,15 @@ use std::fs::File;
 use std::io::Write;
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::ArgMatches;
 use colored::Colorize;
 use log::{debug, info};
 
 use rustfst::prelude::*;
 
+use crate::fst_io::{write_fst, CliSemiring, FstInput, FstType};
+
 fn duration_to_seconds(duration: &Duration) -> f64 {
     duration.as_secs() as f64 + duration.subsec_nanos() as f64 * 1.0e-9
 }
@@ -27,36 +29,57 @@ pub trait UnaryFstAlgorithm {
     fn get_path_out(&self) -> &str;
     fn get_algorithm_name(&self) -> String;
 
-    fn read(&self) -> Result<VectorFst<TropicalWeight>> {
-        VectorFst::<TropicalWeight>::read(self.get_path_in())
+    fn read<W: CliSemiring>(&self, input: &FstInput) -> Result<VectorFst<W>> {
+        input.read()
     }
 
-    fn run_algorithm(&self, fst: VectorFst<TropicalWeight>) -> Result<VectorFst<TropicalWeight>>;
+    fn run_algorithm<W: CliSemiring>(&self, fst: VectorFst<W>) -> Result<VectorFst<W>>;
 
-    fn write(&self, fst: &VectorFst<TropicalWeight>) -> Result<()> {
-        fst.write(self.get_path_out())
+    fn write<W: CliSemiring>(&self, fst: &VectorFst<W>, fst_type: FstType) -> Result<()> {
+        write_fst(fst, self.get_path_out(), fst_type)
     }
 
     fn run_cli_or_bench(&self, m: &ArgMatches) -> Result<()> {
+        let input = FstInput::new(self.get_path_in())?;
+        let arc_type = input.arc_type(m.value_of("arc_type"))?;
+        match arc_type.as_str() {
+            "standard" | "tropical" => {
+                self.run_cli_or_bench_with_weight::<TropicalWeight>(m, &input)
+            }
+            "log" => self.run_cli_or_bench_with_weight::<LogWeight>(m, &input),
+            "tropical64" => self.run_cli_or_bench_with_weight::<Tropical64Weight>(m, &input),
+            "log64" => self.run_cli_or_bench_with_weight::<Log64Weight>(m, &input),
+            _ => bail!("Unsupported arc_type : {}", arc_type),
+        }
+    }
+
+    fn run_cli_or_bench_with_weight<W: CliSemiring>(
+        &self,
+        m: &ArgMatches,
+        input: &FstInput,
+    ) -> Result<()> {
+        let fst_type = m.value_of("fst_type").unwrap().parse()?;
         if m.is_present("bench") {
             // Run bench
-            self.run_bench(
+            self.run_bench::<W>(
                 m.value_of("n_warm_ups").unwrap().parse().unwrap(),
                 m.value_of("n_iters").unwrap().parse().unwrap(),
                 m.value_of("export-markdown"),
+                input,
+                fst_type,
             )
         } else {
             // Run cli
-            self.run_cli()
+            self.run_cli::<W>(input, fst_type)
         }
     }
 
-    fn run_cli(&self) -> Result<()> {
+    fn run_cli<W: CliSemiring>(&self, input: &FstInput, fst_type: FstType) -> Result<()> {
         info!("Running {} algorithm", self.get_algorithm_name().blue());
         // Parsing
         debug!("Parsing...");
         let parsing_start = Instant::now();
-        let mut fst = self.read()?;
+        let mut fst = self.read::<W>(input)?;
         let duration_parsing = parsing_start.elapsed();
         debug!("Duration parsing : {:?}", &duration_parsing);
 
@@ -70,20 +93,22 @@ pub trait UnaryFstAlgorithm {
         // Serialization
         debug!("Serialization...");
         let serialization_start = Instant::now();
-        self.write(&fst)?;
+        self.write(&fst, fst_type)?;
         let duration_serialization = serialization_start.elapsed();
         debug!("Duration serialization : {:?}", &duration_serialization);
 
         Ok(())
     }
 
-    fn run_bench(
+    fn run_bench<W: CliSemiring>(
         &self,
         n_warm_ups: usize,
         n_iters: usize,
         path_markdown_report: Option<&str>,
+        input: &FstInput,
+        fst_type: FstType,
     ) -> Result<()> {
-        println!(
+        eprintln!(
             "Running benchmark for algorithm {}",
             self.get_algorithm_name().blue()
         );
@@ -99,7 +124,7 @@ pub trait UnaryFstAlgorithm {
         for i in 0..(n_warm_ups + n_iters) {
             // Parsing
             let parsing_start = Instant::now();
-            let mut fst = self.read()?;
+            let mut fst = self.read::<W>(input)?;
             let duration_parsing = parsing_start.elapsed();
 
             // Algorithm
@@ -109,11 +134,11 @@ pub trait UnaryFstAlgorithm {
 
             // Serialization
             let serialization_start = Instant::now();
-            self.write(&fst)?;
+            self.write(&fst, fst_type)?;
             let duration_serialization = serialization_start.elapsed();
 
             if i >= n_warm_ups {
-                println!(
+                eprintln!(
                     "Run #{}/{}: \t{} \t{} \t{}",
                     format!("{}", i + 1 - n_warm_ups).yellow(),
                     format!("{}", n_iters).yellow(),
@@ -137,7 +162,7 @@ pub trait UnaryFstAlgorithm {
                         + duration_to_seconds(&duration_serialization),
                 )
             } else {
-                println!(
+                eprintln!(
                     "Warmup #{}/{}: \t{} \t{} \t{}",
                     format!("{}", i + 1).yellow(),
                     format!("{}", n_warm_ups).yellow(),
@@ -156,27 +181,27 @@ pub trait UnaryFstAlgorithm {
             "Bench results (Warmups = {}, Iterations = {}):",
             n_warm_ups, n_iters
         );
-        println!("{}", s.bold().underline());
+        eprintln!("{}", s.bold().underline());
 
         let s = format!(
             "\t Mean {} : \t\t{}",
             "parsing time".blue(),
             format!("{:.6}s", duration_to_seconds(&avg_parsing_time)).blue()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
         let s = format!(
             "\t Mean {} : \t\t{}",
             "algorithm time".magenta(),
             format!("{:.6}s", duration_to_seconds(&avg_algo_time)).magenta()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
 
         let s = format!(
             "\t Mean {} : \t{}",
             "serialization time".cyan(),
             format!("{:.6}s", duration_to_seconds(&avg_serialization_time)).cyan()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
 
         let mean_total_time = avg_parsing_time + avg_algo_time + avg_serialization_time;
         let s = format!(
@@ -184,7 +209,7 @@ pub trait UnaryFstAlgorithm {
             "CLI time".red(),
             format!("{:.6}s", duration_to_seconds(&mean_total_time)).red()
         );
-        println!("{}", s.bold());
+        eprintln!("{}", s.bold());
 
         if let Some(_path) = path_markdown_report {
             let mut file = File::create(_path)?;
@@ -52,6 +52,15 @@ string dump_fst(const F& a, const string& dir_path) {
     return name;
 }
 
+// Same output as `fstencode --save_encoder`.
+template<class M>
+string dump_encoder(const M& mapper, const string& dir_path) {
+    auto name = "res_" + std::to_string(ID_FST_NUM) + ".enc";
+    mapper.Write(dir_path + name);
+    ID_FST_NUM++;
+    return name;
+}
+
 template<class F>
 string fst_to_string(const F& a) {
     using Arc = typename F::Arc;
@@ -272,6 +281,7 @@ void compute_fst_encode(const F& raw_fst, json& j, const string& dir_path) {
             j2["encode_labels"] = encode_labels;
             j2["encode_weights"] = encode_weights;
             j2["result_path"] = dump_fst(fst_out, dir_path);
+            j2["encoder_path"] = dump_encoder(mapper, dir_path);
             j["encode"].push_back(j2);
         }
     }
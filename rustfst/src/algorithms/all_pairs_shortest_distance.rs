@@ -1,9 +1,7 @@
-use anyhow::Result;
-
 use crate::fst_traits::ExpandedFst;
 use crate::fst_traits::Fst;
 use crate::semirings::StarSemiring;
-use crate::Trs;
+use crate::{Error, Trs};
 
 /// This operation computes the shortest distance from each state to every other states.
 /// The shortest distance from `p` to `q` is the ⊕-sum of the weights
@@ -38,7 +36,7 @@ use crate::Trs;
 /// # Ok(())
 /// # }
 /// ```
-pub fn all_pairs_shortest_distance<W, F>(fst: &F) -> Result<Vec<Vec<W>>>
+pub fn all_pairs_shortest_distance<W, F>(fst: &F) -> Result<Vec<Vec<W>>, Error>
 where
     F: Fst<W> + ExpandedFst<W>,
     W: StarSemiring,
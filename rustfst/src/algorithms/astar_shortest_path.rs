@@ -0,0 +1,370 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use binary_heap_plus::{BinaryHeap, FnComparator};
+
+use crate::algorithms::preconditions::check_semiring_properties;
+use crate::algorithms::queues::natural_less;
+use crate::algorithms::shortest_distance;
+use crate::fst_path::FstPath;
+use crate::fst_traits::{CoreFst, ExpandedFst, Fst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{Error, StateId, Tr, Trs};
+
+/// Configuration of the A* search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AStarConfig<W: Semiring> {
+    /// The states whose estimated weight is worse than the estimated weight of the last
+    /// expanded state times `beam` are not enqueued.
+    pub beam: Option<W>,
+    /// Maximum number of states in the queue. The queue is pruned in batches : when it reaches
+    /// twice this size, only the `max_queue_size` best states are kept.
+    pub max_queue_size: Option<usize>,
+}
+
+impl<W: Semiring> Default for AStarConfig<W> {
+    fn default() -> Self {
+        Self {
+            beam: None,
+            max_queue_size: None,
+        }
+    }
+}
+
+impl<W: Semiring> AStarConfig<W> {
+    pub fn with_beam(self, beam: Option<W>) -> Self {
+        Self { beam, ..self }
+    }
+
+    pub fn with_max_queue_size(self, max_queue_size: Option<usize>) -> Self {
+        Self {
+            max_queue_size,
+            ..self
+        }
+    }
+}
+
+/// Returns the exact heuristic of an FST : the shortest distance from each state to the final
+/// states.
+pub fn reverse_distance_heuristic<W, F>(fst: &F) -> Result<impl Fn(StateId) -> W, Error>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+{
+    let distance = shortest_distance(fst, true)?;
+    Ok(move |s: StateId| distance.get(s).cloned().unwrap_or_else(W::zero))
+}
+
+#[derive(Debug, Clone)]
+struct QueueEntry<W> {
+    // `None` once the final weight has been read.
+    state: Option<StateId>,
+    weight: W,
+    estimate: W,
+    id: usize,
+}
+
+fn compare_entries<W: Semiring>(e1: &QueueEntry<W>, e2: &QueueEntry<W>) -> Ordering {
+    // The heap pops the greatest entry : the best estimate must compare as the greatest.
+    if natural_less(&e1.estimate, &e2.estimate).unwrap() {
+        Ordering::Greater
+    } else if natural_less(&e2.estimate, &e1.estimate).unwrap() {
+        Ordering::Less
+    } else {
+        e2.id.cmp(&e1.id)
+    }
+}
+
+type EntryHeap<W> =
+    BinaryHeap<QueueEntry<W>, FnComparator<fn(&QueueEntry<W>, &QueueEntry<W>) -> Ordering>>;
+
+fn new_heap<W: Semiring>() -> EntryHeap<W> {
+    BinaryHeap::new_by(compare_entries)
+}
+
+struct SearchState<W> {
+    weight: W,
+    // State and transition leading to this state.
+    parent: Option<(StateId, usize)>,
+    expanded: bool,
+}
+
+/// Returns the transitions of the best path and its final weight.
+fn astar_search<W, F, H>(
+    fst: &F,
+    heuristic: H,
+    config: &AStarConfig<W>,
+) -> Result<Option<(Vec<Tr<W>>, W)>>
+where
+    W: Semiring,
+    F: Fst<W>,
+    H: Fn(StateId) -> W,
+{
+    check_semiring_properties::<W>("astar_shortest_path", SemiringProperties::PATH)?;
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+
+    let mut queue = new_heap();
+    let mut search_states: HashMap<StateId, SearchState<W>> = HashMap::new();
+    let mut final_parent: Option<(StateId, W)> = None;
+    let mut next_id = 0;
+    let mut frontier: Option<W> = None;
+
+    let mut push = |queue: &mut EntryHeap<W>,
+                    frontier: &Option<W>,
+                    state: Option<StateId>,
+                    weight: W,
+                    estimate: W|
+     -> Result<()> {
+        if estimate.is_zero() {
+            // No final state can be reached.
+            return Ok(());
+        }
+        if let (Some(beam), Some(frontier)) = (&config.beam, frontier) {
+            if natural_less(&frontier.times(beam)?, &estimate)? {
+                return Ok(());
+            }
+        }
+        queue.push(QueueEntry {
+            state,
+            weight,
+            estimate,
+            id: next_id,
+        });
+        next_id += 1;
+        if let Some(max_queue_size) = config.max_queue_size {
+            // Pruning in batches keeps the amortized cost of a push logarithmic.
+            if queue.len() >= 2 * max_queue_size.max(1) {
+                let entries = std::mem::replace(queue, new_heap()).into_sorted_vec();
+                for entry in entries.into_iter().rev().take(max_queue_size) {
+                    queue.push(entry);
+                }
+            }
+        }
+        Ok(())
+    };
+
+    search_states.insert(
+        start,
+        SearchState {
+            weight: W::one(),
+            parent: None,
+            expanded: false,
+        },
+    );
+    push(
+        &mut queue,
+        &frontier,
+        Some(start),
+        W::one(),
+        heuristic(start),
+    )?;
+
+    while let Some(entry) = queue.pop() {
+        let state = match entry.state {
+            Some(s) => s,
+            None => break,
+        };
+        {
+            let search_state = search_states.get_mut(&state).unwrap();
+            if search_state.expanded || natural_less(&search_state.weight, &entry.weight)? {
+                // Outdated entry.
+                continue;
+            }
+            search_state.expanded = true;
+        }
+        frontier = Some(entry.estimate.clone());
+
+        if let Some(final_weight) = fst.final_weight(state)? {
+            let weight = entry.weight.times(&final_weight)?;
+            let is_better = match &final_parent {
+                Some((_, best)) => natural_less(&weight, best)?,
+                None => true,
+            };
+            if is_better {
+                final_parent = Some((state, weight.clone()));
+                push(&mut queue, &frontier, None, weight.clone(), weight)?;
+            }
+        }
+        for (tr_idx, tr) in fst.get_trs(state)?.trs().iter().enumerate() {
+            let weight = entry.weight.times(&tr.weight)?;
+            let improved = match search_states.entry(tr.nextstate) {
+                Entry::Vacant(e) => {
+                    e.insert(SearchState {
+                        weight: weight.clone(),
+                        parent: Some((state, tr_idx)),
+                        expanded: false,
+                    });
+                    true
+                }
+                Entry::Occupied(mut e) => {
+                    let search_state = e.get_mut();
+                    if natural_less(&weight, &search_state.weight)? {
+                        search_state.weight = weight.clone();
+                        search_state.parent = Some((state, tr_idx));
+                        // The state is reopened if it has been reached by a better path.
+                        search_state.expanded = false;
+                        true
+                    } else {
+                        false
+                    }
+                }
+            };
+            if improved {
+                let estimate = weight.times(heuristic(tr.nextstate))?;
+                push(&mut queue, &frontier, Some(tr.nextstate), weight, estimate)?;
+            }
+        }
+    }
+
+    let (final_state, _) = match final_parent {
+        Some(f) => f,
+        None => return Ok(None),
+    };
+    let final_weight = fst.final_weight(final_state)?.unwrap();
+    let mut trs = vec![];
+    let mut state = final_state;
+    while let Some((prev, tr_idx)) = search_states[&state].parent {
+        trs.push(fst.get_trs(prev)?.trs()[tr_idx].clone());
+        state = prev;
+    }
+    trs.reverse();
+    Ok(Some((trs, final_weight)))
+}
+
+/// Computes the shortest path of an FST with an A* search. The states are expanded by
+/// increasing weight from the start state times `heuristic(state)`, which must not be worse than
+/// the shortest distance from `state` to the final states for the result to be exact (see
+/// `reverse_distance_heuristic`). The FST can be lazy, only the states visited by the search are
+/// computed.
+///
+/// The result is a linear FST, empty if there is no successful path. The semiring must have the
+/// path property.
+pub fn astar_shortest_path<W, F1, F2, H>(
+    fst: &F1,
+    heuristic: H,
+    config: AStarConfig<W>,
+) -> Result<F2, Error>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: MutableFst<W>,
+    H: Fn(StateId) -> W,
+{
+    let mut ofst = F2::new();
+    ofst.set_symts_from_fst(fst);
+    let (trs, final_weight) = match astar_search(fst, heuristic, &config)? {
+        Some(path) => path,
+        None => return Ok(ofst),
+    };
+    let mut state = ofst.add_state();
+    ofst.set_start(state)?;
+    for tr in trs {
+        let nextstate = ofst.add_state();
+        ofst.add_tr(state, Tr::new(tr.ilabel, tr.olabel, tr.weight, nextstate))?;
+        state = nextstate;
+    }
+    ofst.set_final(state, final_weight)?;
+    Ok(ofst)
+}
+
+/// Same as `astar_shortest_path` but returns the shortest path as an `FstPath`, `None` if there
+/// is no successful path.
+pub fn astar_shortest_fst_path<W, F, H>(
+    fst: &F,
+    heuristic: H,
+    config: AStarConfig<W>,
+) -> Result<Option<FstPath<W>>, Error>
+where
+    W: Semiring,
+    F: Fst<W>,
+    H: Fn(StateId) -> W,
+{
+    let (trs, final_weight) = match astar_search(fst, heuristic, &config)? {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let mut path = FstPath::default();
+    for tr in trs.iter() {
+        path.add_to_path(tr.ilabel, tr.olabel, &tr.weight)?;
+    }
+    path.add_weight(&final_weight)?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::shortest_path;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::TropicalWeight;
+    use crate::EPS_LABEL;
+
+    fn fst() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s3, TropicalWeight::new(1.0))?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(1.0), s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, TropicalWeight::new(3.0), s2))?;
+        fst.add_tr(s1, Tr::new(3, EPS_LABEL, TropicalWeight::new(5.0), s3))?;
+        fst.add_tr(s2, Tr::new(4, 4, TropicalWeight::new(1.0), s3))?;
+        fst.add_tr(s1, Tr::new(5, 5, TropicalWeight::new(1.0), s2))?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_astar_shortest_path_exact_heuristic() -> Result<()> {
+        let fst = fst()?;
+        let heuristic = reverse_distance_heuristic(&fst)?;
+        let res: VectorFst<TropicalWeight> =
+            astar_shortest_path(&fst, heuristic, AStarConfig::default())?;
+        let reference: VectorFst<TropicalWeight> = shortest_path(&fst)?;
+        let paths: Vec<_> = res.paths_iter().collect();
+        let ref_paths: Vec<_> = reference.paths_iter().collect();
+        assert_eq!(paths, ref_paths);
+        assert_eq!(paths[0].ilabels, vec![1, 5, 4]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(4.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_astar_shortest_path_zero_heuristic() -> Result<()> {
+        // Without heuristic, A* is a Dijkstra search.
+        let fst = fst()?;
+        let path =
+            astar_shortest_fst_path(&fst, |_| TropicalWeight::one(), AStarConfig::default())?;
+        assert_eq!(
+            path,
+            Some(FstPath::new(
+                vec![1, 5, 4],
+                vec![1, 5, 4],
+                TropicalWeight::new(4.0)
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_astar_shortest_path_beam() -> Result<()> {
+        let fst = fst()?;
+        // Keeping a single state in the queue is enough to follow the best path here.
+        let config = AStarConfig::default().with_max_queue_size(Some(1));
+        let path = astar_shortest_fst_path(&fst, |_| TropicalWeight::one(), config)?;
+        assert_eq!(path.unwrap().ilabels, vec![1, 5, 4]);
+
+        // All the transitions leaving the start state are out of a tight beam.
+        let config = AStarConfig::default().with_beam(Some(TropicalWeight::new(0.5)));
+        let path = astar_shortest_fst_path(&fst, |_| TropicalWeight::one(), config)?;
+        assert!(path.is_none());
+        Ok(())
+    }
+}
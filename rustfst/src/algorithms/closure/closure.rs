@@ -69,3 +69,24 @@ where
         FstProperties::all_properties(),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_closure_properties_proptest(fst in any_fst_with_properties::<TropicalWeight>()) {
+            for closure_type in &[ClosureType::ClosurePlus, ClosureType::ClosureStar] {
+                let mut fst = fst.clone();
+                closure(&mut fst, *closure_type);
+                prop_assert!(properties_compatible(&fst));
+            }
+        }
+    }
+}
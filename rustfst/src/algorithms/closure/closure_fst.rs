@@ -1,14 +1,12 @@
 use std::sync::Arc;
 
-use anyhow::Result;
-
 use crate::algorithms::closure::ClosureType;
 use crate::algorithms::replace::ReplaceFst;
 use crate::fst_properties::mutable_properties::closure_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
 use crate::semirings::Semiring;
-use crate::{SymbolTable, Tr, TrsVec, EPS_LABEL};
+use crate::{Error, SymbolTable, Tr, TrsVec, EPS_LABEL};
 
 /// Computes the concatenative closure. This version is a delayed FST. If an FST
 /// transduces string x to y with weight a, then its closure transduces x to y
@@ -25,7 +23,7 @@ where
 {
     //TODO: Use a borrow and not a move
     //TODO: Allow fsts of different types
-    pub fn new(fst: F, closure_type: ClosureType) -> Result<Self> {
+    pub fn new(fst: F, closure_type: ClosureType) -> Result<Self, Error> {
         let props = fst.properties();
         let mut rfst = F::new();
         if let Some(isymt) = fst.input_symbols() {
@@ -64,7 +62,7 @@ where
     }
 
     /// Turns the Lazy FST into a static one.
-    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
         self.0.compute()
     }
 }
@@ -79,7 +77,7 @@ where
         self.0.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.0.final_weight(state_id)
     }
 
@@ -87,7 +85,7 @@ where
         self.0.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.0.num_trs(s)
     }
 
@@ -95,7 +93,7 @@ where
         self.0.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.0.get_trs(state_id)
     }
 
@@ -107,11 +105,11 @@ where
         self.1
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_output_epsilons(state)
     }
 }
@@ -1,12 +1,10 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use anyhow::Result;
-
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, ExpandedFst, Fst, FstIntoIterator, FstIterator, StateIterator};
 use crate::semirings::Semiring;
-use crate::SymbolTable;
+use crate::{Error, SymbolTable};
 
 /// Adds an object of type T to an FST.
 /// The resulting type is a new FST implementation.
@@ -41,7 +39,7 @@ impl<W: Semiring, F: CoreFst<W>, T> CoreFst<W> for FstAddOn<F, T> {
         self.fst.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.fst.final_weight(state_id)
     }
 
@@ -49,7 +47,7 @@ impl<W: Semiring, F: CoreFst<W>, T> CoreFst<W> for FstAddOn<F, T> {
         self.fst.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.fst.num_trs(s)
     }
 
@@ -57,7 +55,7 @@ impl<W: Semiring, F: CoreFst<W>, T> CoreFst<W> for FstAddOn<F, T> {
         self.fst.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.fst.get_trs(state_id)
     }
 
@@ -69,11 +67,11 @@ impl<W: Semiring, F: CoreFst<W>, T> CoreFst<W> for FstAddOn<F, T> {
         self.fst.properties()
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.fst.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.fst.num_output_epsilons(state)
     }
 }
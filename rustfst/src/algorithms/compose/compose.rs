@@ -4,13 +4,21 @@ use std::fmt::Debug;
 use anyhow::Result;
 
 use crate::algorithms::compose::compose_filters::{
-    AltSequenceComposeFilterBuilder, MatchComposeFilterBuilder, NoMatchComposeFilterBuilder,
-    NullComposeFilterBuilder, SequenceComposeFilterBuilder, TrivialComposeFilterBuilder,
+    AltSequenceComposeFilterBuilder, ComposeFilterBuilder, MatchComposeFilterBuilder,
+    NoMatchComposeFilterBuilder, NullComposeFilterBuilder, SequenceComposeFilterBuilder,
+    TrivialComposeFilterBuilder,
 };
-use crate::algorithms::compose::matchers::SortedMatcher;
-use crate::algorithms::compose::ComposeFst;
-use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::algorithms::compose::matchers::{
+    IterItemMatcher, MatchType, Matcher, MatcherFlags, MatcherRewriteMode, MultiEpsMatcher,
+    MultiEpsMatcherFlags, PhiMatcher, RhoMatcher, SigmaMatcher, SortedMatcher,
+};
+use crate::algorithms::compose::{ComposeFst, ComposeFstOpOptions};
+use crate::algorithms::preconditions::check_fst_properties;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
+use crate::fst_traits::{AllocableFst, ExpandedFst, Fst, MutableFst};
 use crate::semirings::Semiring;
+use crate::symbol_table::compat_symbols;
+use crate::{Error, Label, StateId};
 
 #[derive(PartialOrd, PartialEq, Debug, Clone, Copy)]
 pub enum ComposeFilterEnum {
@@ -23,10 +31,55 @@ pub enum ComposeFilterEnum {
     NoMatchFilter,
 }
 
-#[derive(PartialOrd, PartialEq, Debug, Clone, Copy)]
+/// Matcher used on one side of the composition.
+#[derive(PartialOrd, PartialEq, Debug, Clone)]
+pub enum MatcherEnum {
+    SortedMatcher,
+    GenericMatcher,
+    /// `SortedMatcher` treating the `multi_eps_labels` as epsilons.
+    MultiEpsMatcher {
+        multi_eps_labels: Vec<Label>,
+        flags: MultiEpsMatcherFlags,
+    },
+    /// `SigmaMatcher` where `sigma_label` matches any non-epsilon label.
+    SigmaMatcher {
+        sigma_label: Label,
+        rewrite_mode: MatcherRewriteMode,
+    },
+    /// `RhoMatcher` where `rho_label` matches any label not explicitly matched.
+    RhoMatcher {
+        rho_label: Label,
+        rewrite_mode: MatcherRewriteMode,
+    },
+    /// `PhiMatcher` where `phi_label` is a failure transition.
+    PhiMatcher {
+        phi_label: Label,
+        rewrite_mode: MatcherRewriteMode,
+        phi_loop: bool,
+    },
+}
+
+impl Default for MatcherEnum {
+    fn default() -> Self {
+        MatcherEnum::SortedMatcher
+    }
+}
+
+#[derive(PartialOrd, PartialEq, Debug, Clone)]
 pub struct ComposeConfig {
     pub compose_filter: ComposeFilterEnum,
     pub connect: bool,
+    /// If true, an error is returned when the output symbols of the first FST are not compatible
+    /// with the input symbols of the second one.
+    pub compat_symbols: bool,
+    /// Matcher used on the output labels of the first FST.
+    pub matcher1: MatcherEnum,
+    /// Matcher used on the input labels of the second FST.
+    pub matcher2: MatcherEnum,
+    /// Side on which the matching is performed. `MatchBoth` lets the composition choose
+    /// the side at each state, `MatchOutput` only uses `matcher1` and `MatchInput` only
+    /// uses `matcher2`.
+    pub match_type: MatchType,
 }
 
 impl Default for ComposeConfig {
@@ -34,10 +87,248 @@ impl Default for ComposeConfig {
         Self {
             compose_filter: ComposeFilterEnum::AutoFilter,
             connect: true,
+            compat_symbols: false,
+            matcher1: MatcherEnum::default(),
+            matcher2: MatcherEnum::default(),
+            match_type: MatchType::MatchBoth,
         }
     }
 }
 
+/// Matcher built at runtime from a `MatcherEnum`.
+#[derive(Debug)]
+struct ConfiguredMatcher<W: Semiring, F: Fst<W>, B: Borrow<F> + Debug> {
+    matcher: ConfiguredMatcherKind<W, F, B>,
+    match_type: MatchType,
+    // Sort properties of the FST, computed by `compose_with_config` if they were not known.
+    props: FstProperties,
+}
+
+#[derive(Debug)]
+enum ConfiguredMatcherKind<W: Semiring, F: Fst<W>, B: Borrow<F> + Debug> {
+    Sorted(SortedMatcher<W, F, B>),
+    MultiEps(MultiEpsMatcher<W, F, B, SortedMatcher<W, F, B>>),
+    Sigma(SigmaMatcher<W, F, B, SortedMatcher<W, F, B>>),
+    Rho(RhoMatcher<W, F, B, SortedMatcher<W, F, B>>),
+    Phi(PhiMatcher<W, F, B, SortedMatcher<W, F, B>>),
+}
+
+enum ConfiguredMatcherIter<W: Semiring, F: Fst<W>, B: Borrow<F> + Debug> {
+    Sorted(<SortedMatcher<W, F, B> as Matcher<W, F, B>>::Iter),
+    MultiEps(<MultiEpsMatcher<W, F, B, SortedMatcher<W, F, B>> as Matcher<W, F, B>>::Iter),
+    Special(std::vec::IntoIter<IterItemMatcher<W>>),
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F> + Debug> Iterator for ConfiguredMatcherIter<W, F, B> {
+    type Item = IterItemMatcher<W>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ConfiguredMatcherIter::Sorted(it) => it.next(),
+            ConfiguredMatcherIter::MultiEps(it) => it.next(),
+            ConfiguredMatcherIter::Special(it) => it.next(),
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F> + Debug> ConfiguredMatcher<W, F, B> {
+    fn from_config(
+        fst: B,
+        match_type: MatchType,
+        config: &MatcherEnum,
+        props: FstProperties,
+    ) -> Result<Self> {
+        let matcher = match config {
+            MatcherEnum::SortedMatcher | MatcherEnum::GenericMatcher => {
+                ConfiguredMatcherKind::Sorted(SortedMatcher::new(fst, match_type)?)
+            }
+            MatcherEnum::MultiEpsMatcher {
+                multi_eps_labels,
+                flags,
+            } => {
+                let mut matcher = MultiEpsMatcher::new_with_opts(fst, match_type, *flags, None)?;
+                for label in multi_eps_labels {
+                    matcher.add_multi_eps_label(*label)?;
+                }
+                ConfiguredMatcherKind::MultiEps(matcher)
+            }
+            MatcherEnum::SigmaMatcher {
+                sigma_label,
+                rewrite_mode,
+            } => ConfiguredMatcherKind::Sigma(SigmaMatcher::new_with_opts(
+                fst,
+                match_type,
+                *sigma_label,
+                *rewrite_mode,
+            )?),
+            MatcherEnum::RhoMatcher {
+                rho_label,
+                rewrite_mode,
+            } => ConfiguredMatcherKind::Rho(RhoMatcher::new_with_opts(
+                fst,
+                match_type,
+                *rho_label,
+                *rewrite_mode,
+            )?),
+            MatcherEnum::PhiMatcher {
+                phi_label,
+                rewrite_mode,
+                phi_loop,
+            } => ConfiguredMatcherKind::Phi(PhiMatcher::new_with_opts(
+                fst,
+                match_type,
+                *phi_label,
+                *phi_loop,
+                *rewrite_mode,
+            )?),
+        };
+        Ok(ConfiguredMatcher {
+            matcher,
+            match_type,
+            props,
+        })
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F> + Debug> Matcher<W, F, B> for ConfiguredMatcher<W, F, B> {
+    type Iter = ConfiguredMatcherIter<W, F, B>;
+
+    fn new(fst: B, match_type: MatchType) -> Result<Self> {
+        let props = fst.borrow().properties();
+        Ok(ConfiguredMatcher {
+            matcher: ConfiguredMatcherKind::Sorted(SortedMatcher::new(fst, match_type)?),
+            match_type,
+            props,
+        })
+    }
+
+    fn iter(&self, state: StateId, label: Label) -> Result<Self::Iter> {
+        let it = match &self.matcher {
+            ConfiguredMatcherKind::Sorted(m) => {
+                ConfiguredMatcherIter::Sorted(m.iter(state, label)?)
+            }
+            ConfiguredMatcherKind::MultiEps(m) => {
+                ConfiguredMatcherIter::MultiEps(m.iter(state, label)?)
+            }
+            ConfiguredMatcherKind::Sigma(m) => {
+                ConfiguredMatcherIter::Special(m.iter(state, label)?)
+            }
+            ConfiguredMatcherKind::Rho(m) => ConfiguredMatcherIter::Special(m.iter(state, label)?),
+            ConfiguredMatcherKind::Phi(m) => ConfiguredMatcherIter::Special(m.iter(state, label)?),
+        };
+        Ok(it)
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        match &self.matcher {
+            ConfiguredMatcherKind::Sorted(m) => m.final_weight(state),
+            ConfiguredMatcherKind::MultiEps(m) => m.final_weight(state),
+            ConfiguredMatcherKind::Sigma(m) => m.final_weight(state),
+            ConfiguredMatcherKind::Rho(m) => m.final_weight(state),
+            ConfiguredMatcherKind::Phi(m) => m.final_weight(state),
+        }
+    }
+
+    // All the matchers delegate their match type to a `SortedMatcher`, which only depends on
+    // the sort properties. They are read from the properties computed beforehand instead of
+    // being checked on the FST.
+    fn match_type(&self, _test: bool) -> Result<MatchType> {
+        let (true_prop, false_prop) = match self.match_type {
+            MatchType::MatchNone => return Ok(MatchType::MatchNone),
+            MatchType::MatchInput => (
+                FstProperties::I_LABEL_SORTED,
+                FstProperties::NOT_I_LABEL_SORTED,
+            ),
+            _ => (
+                FstProperties::O_LABEL_SORTED,
+                FstProperties::NOT_O_LABEL_SORTED,
+            ),
+        };
+        if self.props.contains(true_prop) {
+            Ok(self.match_type)
+        } else if self.props.contains(false_prop) {
+            Ok(MatchType::MatchNone)
+        } else {
+            Ok(MatchType::MatchUnknown)
+        }
+    }
+
+    fn flags(&self) -> MatcherFlags {
+        match &self.matcher {
+            ConfiguredMatcherKind::Sorted(m) => m.flags(),
+            ConfiguredMatcherKind::MultiEps(m) => m.flags(),
+            ConfiguredMatcherKind::Sigma(m) => m.flags(),
+            ConfiguredMatcherKind::Rho(m) => m.flags(),
+            ConfiguredMatcherKind::Phi(m) => m.flags(),
+        }
+    }
+
+    fn priority(&self, state: StateId) -> Result<usize> {
+        match &self.matcher {
+            ConfiguredMatcherKind::Sorted(m) => m.priority(state),
+            ConfiguredMatcherKind::MultiEps(m) => m.priority(state),
+            ConfiguredMatcherKind::Sigma(m) => m.priority(state),
+            ConfiguredMatcherKind::Rho(m) => m.priority(state),
+            ConfiguredMatcherKind::Phi(m) => m.priority(state),
+        }
+    }
+
+    fn fst(&self) -> &B {
+        match &self.matcher {
+            ConfiguredMatcherKind::Sorted(m) => m.fst(),
+            ConfiguredMatcherKind::MultiEps(m) => m.fst(),
+            ConfiguredMatcherKind::Sigma(m) => m.fst(),
+            ConfiguredMatcherKind::Rho(m) => m.fst(),
+            ConfiguredMatcherKind::Phi(m) => m.fst(),
+        }
+    }
+}
+
+fn compose_with_filter<W, F1, F2, B1, B2, CFB, F3>(
+    fst1: B1,
+    fst2: B2,
+    matcher1: ConfiguredMatcher<W, F1, B1>,
+    matcher2: ConfiguredMatcher<W, F2, B2>,
+) -> Result<F3, Error>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + Debug + Clone,
+    CFB: ComposeFilterBuilder<
+        W,
+        F1,
+        F2,
+        B1,
+        B2,
+        ConfiguredMatcher<W, F1, B1>,
+        ConfiguredMatcher<W, F2, B2>,
+    >,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    let opts = ComposeFstOpOptions::new(matcher1, matcher2, None, None);
+    ComposeFst::<_, _, _, _, _, _, _, CFB>::new_with_options(fst1, fst2, opts)?.compute()
+}
+
+/// Returns an `Error::IncompatibleSymbolTables` if the output symbols of `fst1` are not
+/// compatible with the input symbols of `fst2`. This is the check run by `compose_with_config`
+/// when `compat_symbols` is set.
+pub fn check_compose_symbols<W: Semiring, F1: Fst<W>, F2: Fst<W>>(
+    fst1: &F1,
+    fst2: &F2,
+) -> Result<(), Error> {
+    if !compat_symbols(
+        fst1.output_symbols().map(|s| s.as_ref()),
+        fst2.input_symbols().map(|s| s.as_ref()),
+    ) {
+        return Err(Error::IncompatibleSymbolTables {
+            algorithm: "compose",
+        });
+    }
+    Ok(())
+}
+
 pub fn compose_with_config<
     W: Semiring,
     F1: ExpandedFst<W>,
@@ -49,115 +340,98 @@ pub fn compose_with_config<
     fst1: B1,
     fst2: B2,
     config: ComposeConfig,
-) -> Result<F3> {
+) -> Result<F3, Error> {
+    if config.compat_symbols {
+        check_compose_symbols(fst1.borrow(), fst2.borrow())?;
+    }
+
+    let (match_type1, match_type2) = match config.match_type {
+        MatchType::MatchBoth => (MatchType::MatchOutput, MatchType::MatchInput),
+        MatchType::MatchOutput => (MatchType::MatchOutput, MatchType::MatchNone),
+        MatchType::MatchInput => (MatchType::MatchNone, MatchType::MatchInput),
+        _ => {
+            return Err(Error::Other(format_err!(
+                "Compose: unsupported match type : {:?}",
+                config.match_type
+            )))
+        }
+    };
+    // The matchers require the output labels of the first FST and/or the input labels of the
+    // second one to be sorted. With `MatchBoth`, one of them is enough. The stored sort
+    // properties are used when they are known, otherwise they are computed once and passed on
+    // to the matchers.
+    let props1 = compute_fst_properties(
+        fst1.borrow(),
+        FstProperties::O_LABEL_SORTED | FstProperties::NOT_O_LABEL_SORTED,
+        &mut FstProperties::empty(),
+        true,
+    )?;
+    let props2 = compute_fst_properties(
+        fst2.borrow(),
+        FstProperties::I_LABEL_SORTED | FstProperties::NOT_I_LABEL_SORTED,
+        &mut FstProperties::empty(),
+        true,
+    )?;
+    match config.match_type {
+        MatchType::MatchOutput => {
+            check_fst_properties("compose", props1, FstProperties::O_LABEL_SORTED)?
+        }
+        MatchType::MatchInput => {
+            check_fst_properties("compose", props2, FstProperties::I_LABEL_SORTED)?
+        }
+        _ => {
+            if check_fst_properties("compose", props1, FstProperties::O_LABEL_SORTED).is_err() {
+                check_fst_properties("compose", props2, FstProperties::I_LABEL_SORTED)?
+            }
+        }
+    }
+
+    let matcher1 =
+        ConfiguredMatcher::from_config(fst1.clone(), match_type1, &config.matcher1, props1)?;
+    let matcher2 =
+        ConfiguredMatcher::from_config(fst2.clone(), match_type2, &config.matcher2, props2)?;
+
     let mut ofst: F3 = match config.compose_filter {
-        ComposeFilterEnum::AutoFilter => ComposeFst::new_auto(fst1, fst2)?.compute()?,
-        ComposeFilterEnum::NullFilter => ComposeFst::<
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            NullComposeFilterBuilder<_, _, _, _, _, SortedMatcher<_, _, _>, SortedMatcher<_, _, _>>,
-        >::new(fst1, fst2)?
-        .compute()?,
-        ComposeFilterEnum::SequenceFilter => ComposeFst::<
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            SequenceComposeFilterBuilder<
+        ComposeFilterEnum::AutoFilter | ComposeFilterEnum::SequenceFilter => {
+            compose_with_filter::<
                 _,
                 _,
                 _,
                 _,
                 _,
-                SortedMatcher<_, _, _>,
-                SortedMatcher<_, _, _>,
-            >,
-        >::new(fst1, fst2)?
-        .compute()?,
-        ComposeFilterEnum::AltSequenceFilter => ComposeFst::<
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            AltSequenceComposeFilterBuilder<
-                _,
-                _,
-                _,
-                _,
+                SequenceComposeFilterBuilder<_, _, _, _, _, _, _>,
                 _,
-                SortedMatcher<_, _, _>,
-                SortedMatcher<_, _, _>,
-            >,
-        >::new(fst1, fst2)?
-        .compute()?,
-        ComposeFilterEnum::MatchFilter => ComposeFst::<
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            MatchComposeFilterBuilder<
-                _,
-                _,
-                _,
-                _,
-                _,
-                SortedMatcher<_, _, _>,
-                SortedMatcher<_, _, _>,
-            >,
-        >::new(fst1, fst2)?
-        .compute()?,
-        ComposeFilterEnum::NoMatchFilter => ComposeFst::<
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            _,
-            NoMatchComposeFilterBuilder<
-                _,
-                _,
-                _,
-                _,
-                _,
-                SortedMatcher<_, _, _>,
-                SortedMatcher<_, _, _>,
-            >,
-        >::new(fst1, fst2)?
-        .compute()?,
-        ComposeFilterEnum::TrivialFilter => ComposeFst::<
-            _,
+            >(fst1, fst2, matcher1, matcher2)?
+        }
+        ComposeFilterEnum::NullFilter => {
+            compose_with_filter::<_, _, _, _, _, NullComposeFilterBuilder<_, _, _, _, _, _, _>, _>(
+                fst1, fst2, matcher1, matcher2,
+            )?
+        }
+        ComposeFilterEnum::AltSequenceFilter => compose_with_filter::<
             _,
             _,
             _,
             _,
             _,
+            AltSequenceComposeFilterBuilder<_, _, _, _, _, _, _>,
             _,
-            TrivialComposeFilterBuilder<
-                _,
-                _,
-                _,
-                _,
-                _,
-                SortedMatcher<_, _, _>,
-                SortedMatcher<_, _, _>,
-            >,
-        >::new(fst1, fst2)?
-        .compute()?,
+        >(fst1, fst2, matcher1, matcher2)?,
+        ComposeFilterEnum::MatchFilter => {
+            compose_with_filter::<_, _, _, _, _, MatchComposeFilterBuilder<_, _, _, _, _, _, _>, _>(
+                fst1, fst2, matcher1, matcher2,
+            )?
+        }
+        ComposeFilterEnum::NoMatchFilter => {
+            compose_with_filter::<_, _, _, _, _, NoMatchComposeFilterBuilder<_, _, _, _, _, _, _>, _>(
+                fst1, fst2, matcher1, matcher2,
+            )?
+        }
+        ComposeFilterEnum::TrivialFilter => {
+            compose_with_filter::<_, _, _, _, _, TrivialComposeFilterBuilder<_, _, _, _, _, _, _>, _>(
+                fst1, fst2, matcher1, matcher2,
+            )?
+        }
     };
 
     if config.connect {
@@ -202,7 +476,286 @@ pub fn compose<
 >(
     fst1: B1,
     fst2: B2,
-) -> Result<F3> {
+) -> Result<F3, Error> {
     let config = ComposeConfig::default();
     compose_with_config(fst1, fst2, config)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::tr_compares::ILabelCompare;
+    use crate::algorithms::{tr_sort, PreconditionError};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::transducer;
+    use crate::{SymbolTable, Tr};
+    use std::sync::Arc;
+
+    fn sorted_paths<F: Fst<TropicalWeight>>(fst: &F) -> Vec<(Vec<Label>, Vec<Label>, f32)> {
+        let mut paths: Vec<_> = fst
+            .paths_iter()
+            .map(|p| (p.ilabels, p.olabels, *p.weight.value()))
+            .collect();
+        paths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        paths
+    }
+
+    /// Acceptor of the labels 1 and 2.
+    fn one_or_two() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, TropicalWeight::one(), s1))?;
+        Ok(fst)
+    }
+
+    fn compose_paths(
+        fst_1: &VectorFst<TropicalWeight>,
+        fst_2: &VectorFst<TropicalWeight>,
+        config: ComposeConfig,
+    ) -> Result<Vec<(Vec<Label>, Vec<Label>, f32)>, Error> {
+        let fst_res: VectorFst<_> =
+            compose_with_config::<_, VectorFst<_>, VectorFst<_>, _, _, _>(fst_1, fst_2, config)?;
+        Ok(sorted_paths(&fst_res))
+    }
+
+    #[test]
+    fn test_compose_compat_symbols() -> Result<()> {
+        let mut fst_1: VectorFst<_> = transducer(&[1], &[2], TropicalWeight::one());
+        let mut fst_2: VectorFst<_> = transducer(&[2], &[1], TropicalWeight::one());
+
+        let mut symt_1 = SymbolTable::new();
+        symt_1.add_symbols(vec!["a", "b"]);
+        let mut symt_2 = SymbolTable::new();
+        symt_2.add_symbols(vec!["b", "a"]);
+        fst_1.set_output_symbols(Arc::new(symt_1));
+        fst_2.set_input_symbols(Arc::new(symt_2));
+
+        let mut config = ComposeConfig::default();
+        let res: Result<VectorFst<_>, Error> =
+            compose_with_config::<_, VectorFst<_>, VectorFst<_>, _, _, _>(
+                &fst_1,
+                &fst_2,
+                config.clone(),
+            );
+        assert!(res.is_ok());
+
+        config.compat_symbols = true;
+        let res: Result<VectorFst<_>, Error> =
+            compose_with_config::<_, VectorFst<_>, VectorFst<_>, _, _, _>(
+                &fst_1,
+                &fst_2,
+                config.clone(),
+            );
+        assert!(res.is_err());
+
+        fst_2.set_input_symbols(Arc::clone(fst_1.output_symbols().unwrap()));
+        let res: Result<VectorFst<_>, Error> =
+            compose_with_config::<_, VectorFst<_>, VectorFst<_>, _, _, _>(&fst_1, &fst_2, config);
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_match_type() -> Result<()> {
+        let fst_1: VectorFst<_> = transducer(&[1, 2], &[3, 4], TropicalWeight::new(1.0));
+        let fst_2: VectorFst<_> = transducer(&[3, 4], &[5, 6], TropicalWeight::new(2.0));
+        let expected = vec![(vec![1, 2], vec![5, 6], 3.0)];
+
+        for match_type in &[
+            MatchType::MatchBoth,
+            MatchType::MatchInput,
+            MatchType::MatchOutput,
+        ] {
+            let mut config = ComposeConfig::default();
+            config.match_type = *match_type;
+            assert_eq!(compose_paths(&fst_1, &fst_2, config)?, expected);
+        }
+
+        let mut config = ComposeConfig::default();
+        config.match_type = MatchType::MatchUnknown;
+        assert!(compose_paths(&fst_1, &fst_2, config).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_unsorted() -> Result<()> {
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_states(2);
+        fst_1.set_start(0)?;
+        fst_1.set_final(1, TropicalWeight::one())?;
+        fst_1.add_tr(0, Tr::new(1, 2, TropicalWeight::one(), 1))?;
+        fst_1.add_tr(0, Tr::new(2, 1, TropicalWeight::one(), 1))?;
+        let mut fst_2 = fst_1.clone();
+
+        let err = compose_paths(&fst_1, &fst_2, ComposeConfig::default()).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingFstProperties {
+                    algorithm: "compose",
+                    missing: FstProperties::I_LABEL_SORTED
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+
+        let mut config = ComposeConfig::default();
+        config.match_type = MatchType::MatchOutput;
+        let err = compose_paths(&fst_1, &fst_2, config).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingFstProperties {
+                    algorithm: "compose",
+                    missing: FstProperties::O_LABEL_SORTED
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+
+        tr_sort(&mut fst_2, ILabelCompare {});
+        assert_eq!(
+            compose_paths(&fst_1, &fst_2, ComposeConfig::default())?,
+            vec![(vec![1], vec![1], 0.0), (vec![2], vec![2], 0.0)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_unknown_sort_properties() -> Result<()> {
+        // Sorted FSTs whose properties are not known, e.g after being read from a file.
+        let mut fst_1 = one_or_two()?;
+        let mut fst_2 = one_or_two()?;
+        fst_1.set_properties(FstProperties::empty());
+        fst_2.set_properties(FstProperties::empty());
+
+        let expected = vec![(vec![1], vec![1], 0.0), (vec![2], vec![2], 0.0)];
+        assert_eq!(
+            compose_paths(&fst_1, &fst_2, ComposeConfig::default())?,
+            expected
+        );
+        let mut config = ComposeConfig::default();
+        config.match_type = MatchType::MatchOutput;
+        assert_eq!(compose_paths(&fst_1, &fst_2, config)?, expected);
+
+        // The properties are computed, not assumed.
+        fst_2.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst_2.set_properties(FstProperties::empty());
+        let mut config = ComposeConfig::default();
+        config.match_type = MatchType::MatchInput;
+        let err = compose_paths(&fst_1, &fst_2, config).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingFstProperties {
+                    algorithm: "compose",
+                    missing: FstProperties::I_LABEL_SORTED
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_multi_eps_matcher() -> Result<()> {
+        let fst_1: VectorFst<_> = transducer(&[1], &[1], TropicalWeight::one());
+        let fst_2: VectorFst<_> = transducer(&[5, 1], &[7, 1], TropicalWeight::one());
+
+        let mut config = ComposeConfig::default();
+        config.match_type = MatchType::MatchInput;
+        assert!(compose_paths(&fst_1, &fst_2, config.clone())?.is_empty());
+
+        config.matcher2 = MatcherEnum::MultiEpsMatcher {
+            multi_eps_labels: vec![5],
+            flags: MultiEpsMatcherFlags::MULTI_EPS_LOOP | MultiEpsMatcherFlags::MULTI_EPS_LIST,
+        };
+        assert_eq!(
+            compose_paths(&fst_1, &fst_2, config)?,
+            vec![(vec![1], vec![7, 1], 0.0)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_sigma_matcher() -> Result<()> {
+        let fst_1 = one_or_two()?;
+        let fst_2: VectorFst<_> = transducer(&[10], &[10], TropicalWeight::one());
+
+        let mut config = ComposeConfig::default();
+        assert!(compose_paths(&fst_1, &fst_2, config.clone())?.is_empty());
+
+        config.matcher2 = MatcherEnum::SigmaMatcher {
+            sigma_label: 10,
+            rewrite_mode: MatcherRewriteMode::Auto,
+        };
+        assert_eq!(
+            compose_paths(&fst_1, &fst_2, config)?,
+            vec![(vec![1], vec![1], 0.0), (vec![2], vec![2], 0.0)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_rho_matcher() -> Result<()> {
+        let fst_1 = one_or_two()?;
+        let mut fst_2 = VectorFst::new();
+        let s0 = fst_2.add_state();
+        let s1 = fst_2.add_state();
+        fst_2.set_start(s0)?;
+        fst_2.set_final(s1, TropicalWeight::one())?;
+        fst_2.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(1.0), s1))?;
+        fst_2.add_tr(s0, Tr::new(10, 10, TropicalWeight::new(3.0), s1))?;
+
+        let mut config = ComposeConfig::default();
+        config.matcher2 = MatcherEnum::RhoMatcher {
+            rho_label: 10,
+            rewrite_mode: MatcherRewriteMode::Auto,
+        };
+        assert_eq!(
+            compose_paths(&fst_1, &fst_2, config)?,
+            vec![(vec![1], vec![1], 1.0), (vec![2], vec![2], 3.0)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_phi_matcher() -> Result<()> {
+        let fst_1 = one_or_two()?;
+        let mut fst_2 = VectorFst::new();
+        let s0 = fst_2.add_state();
+        let s1 = fst_2.add_state();
+        let s2 = fst_2.add_state();
+        fst_2.set_start(s0)?;
+        fst_2.set_final(s1, TropicalWeight::one())?;
+        fst_2.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst_2.add_tr(s0, Tr::new(10, 10, TropicalWeight::new(2.0), s2))?;
+        fst_2.add_tr(s2, Tr::new(2, 2, TropicalWeight::one(), s1))?;
+
+        let mut config = ComposeConfig::default();
+        config.matcher2 = MatcherEnum::PhiMatcher {
+            phi_label: 10,
+            rewrite_mode: MatcherRewriteMode::Auto,
+            phi_loop: true,
+        };
+        assert_eq!(
+            compose_paths(&fst_1, &fst_2, config)?,
+            vec![(vec![1], vec![1], 0.0), (vec![2], vec![2], 2.0)]
+        );
+
+        Ok(())
+    }
+}
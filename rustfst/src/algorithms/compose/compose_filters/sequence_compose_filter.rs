@@ -7,7 +7,10 @@ use anyhow::Result;
 
 use crate::algorithms::compose::compose_filters::{ComposeFilter, ComposeFilterBuilder};
 use crate::algorithms::compose::filter_states::{FilterState, IntegerFilterState};
-use crate::algorithms::compose::matchers::{MatchType, Matcher};
+use crate::algorithms::compose::lookahead_filters::lookahead_selector::Selector;
+use crate::algorithms::compose::lookahead_filters::LookAheadComposeFilterTrait;
+use crate::algorithms::compose::lookahead_matchers::{LookAheadMatcherData, LookaheadMatcher};
+use crate::algorithms::compose::matchers::{MatchType, Matcher, MatcherFlags};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::Fst;
 use crate::semirings::Semiring;
@@ -196,3 +199,39 @@ where
         inprops
     }
 }
+
+impl<W: Semiring + 'static, F1, F2, B1, B2, M1, M2>
+    LookAheadComposeFilterTrait<W, F1, F2, B1, B2, M1, M2>
+    for SequenceComposeFilter<W, F1, F2, B1, B2, M1, M2>
+where
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug,
+    B2: Borrow<F2> + Debug,
+    M1: LookaheadMatcher<W, F1, B1>,
+    M2: LookaheadMatcher<W, F2, B2>,
+{
+    fn lookahead_flags(&self) -> MatcherFlags {
+        unreachable!()
+    }
+
+    fn lookahead_tr(&self) -> bool {
+        unreachable!()
+    }
+
+    fn lookahead_type(&self) -> MatchType {
+        unreachable!()
+    }
+
+    fn lookahead_output(&self) -> bool {
+        unreachable!()
+    }
+
+    fn selector(&self) -> &Selector {
+        unreachable!()
+    }
+
+    fn lookahead_matcher_data(&self) -> Option<&LookAheadMatcherData<W>> {
+        unreachable!()
+    }
+}
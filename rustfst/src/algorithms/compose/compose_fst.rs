@@ -1,4 +1,3 @@
-use anyhow::Result;
 use std::borrow::Borrow;
 use std::fmt::Debug;
 
@@ -11,7 +10,7 @@ use crate::algorithms::lazy::{FstCache, LazyFst, SimpleVecCache, StateTable};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
 use crate::semirings::Semiring;
-use crate::{SymbolTable, TrsVec};
+use crate::{Error, SymbolTable, TrsVec};
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -107,7 +106,7 @@ where
                 >,
             >,
         >,
-    ) -> Result<Self>
+    ) -> Result<Self, Error>
     where
         Cache: Default,
     {
@@ -133,7 +132,7 @@ where
             >,
         >,
         fst_cache: Cache,
-    ) -> Result<Self> {
+    ) -> Result<Self, Error> {
         let isymt = fst1.borrow().input_symbols().cloned();
         let osymt = fst2.borrow().output_symbols().cloned();
         let compose_impl = ComposeFstOp::new(fst1, fst2, opts)?;
@@ -142,7 +141,7 @@ where
     }
 
     // TODO: Change API, no really user friendly
-    pub fn new(fst1: B1, fst2: B2) -> Result<Self>
+    pub fn new(fst1: B1, fst2: B2) -> Result<Self, Error>
     where
         Cache: Default,
     {
@@ -150,7 +149,7 @@ where
     }
 
     /// Turns the Lazy FST into a static one.
-    pub fn compute<F: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F> {
+    pub fn compute<F: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F, Error> {
         self.0.compute()
     }
 }
@@ -181,7 +180,7 @@ where
     B1: Borrow<F1> + Debug + Clone,
     B2: Borrow<F2> + Debug + Clone,
 {
-    pub fn new_auto(fst1: B1, fst2: B2) -> Result<Self> {
+    pub fn new_auto(fst1: B1, fst2: B2) -> Result<Self, Error> {
         let isymt = fst1.borrow().input_symbols().cloned();
         let osymt = fst2.borrow().output_symbols().cloned();
         let compose_impl = create_base(fst1, fst2)?;
@@ -210,7 +209,7 @@ where
         self.0.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.0.final_weight(state_id)
     }
 
@@ -218,7 +217,7 @@ where
         self.0.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.0.num_trs(s)
     }
 
@@ -226,7 +225,7 @@ where
         self.0.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.0.get_trs(state_id)
     }
 
@@ -238,11 +237,11 @@ where
         self.0.properties()
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_output_epsilons(state)
     }
 }
@@ -1,11 +1,20 @@
-use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::io::Write;
 use std::slice::Iter as IterSlice;
 use std::vec::IntoIter as IntoIterVec;
+
+use anyhow::Result;
+use nom::multi::count;
+use nom::number::complete::{le_i32, le_i64};
+use nom::IResult;
+use serde::{Deserialize, Serialize};
 use superslice::Ext;
 use unsafe_unwrap::UnsafeUnwrap;
 
+use crate::parsers::bin_fst::utils_serialization::{write_bin_i32, write_bin_i64};
+use crate::parsers::nom_utils::NomCustomError;
+
 /// Half-open integral interval [a, b) of signed integers of type T.
 #[derive(PartialEq, Clone, Eq, Debug, Serialize, Deserialize)]
 pub struct IntInterval {
@@ -191,6 +200,43 @@ impl IntervalSet {
     }
 }
 
+impl IntInterval {
+    pub(crate) fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, begin) = le_i32(i)?;
+        let (i, end) = le_i32(i)?;
+        Ok((i, Self::new(begin as usize, end as usize)))
+    }
+
+    pub(crate) fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        write_bin_i32(file, self.begin as i32)?;
+        write_bin_i32(file, self.end as i32)
+    }
+}
+
+impl IntervalSet {
+    /// Parses an `IntervalSet` serialized in the OpenFst binary format.
+    pub(crate) fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, n_intervals) = le_i64(i)?;
+        let (i, intervals) = count(IntInterval::parse_binary, n_intervals as usize)(i)?;
+        let (i, count) = le_i32(i)?;
+        let intervals = VectorIntervalStore {
+            intervals,
+            // OpenFst uses -1 for an unset count.
+            count: Some(count as usize).filter(|_| count >= 0),
+        };
+        Ok((i, Self { intervals }))
+    }
+
+    /// Serializes an `IntervalSet` in the OpenFst binary format.
+    pub(crate) fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        write_bin_i64(file, self.len() as i64)?;
+        for interval in self.iter() {
+            interval.write_binary(file)?;
+        }
+        write_bin_i32(file, self.count().map_or(-1, |c| c as i32))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
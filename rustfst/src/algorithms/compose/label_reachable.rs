@@ -1,17 +1,25 @@
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
 
 use anyhow::Result;
+use itertools::Itertools;
+use nom::multi::count;
+use nom::number::complete::{le_i32, le_i64, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
 
-use crate::algorithms::compose::{IntervalSet, StateReachable};
+use crate::algorithms::compose::{IntervalSet, MatcherFstData, StateReachable};
 use crate::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
 use crate::algorithms::{fst_convert_from_ref, tr_sort};
 use crate::fst_impls::VectorFst;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, ExpandedFst, Fst, MutableFst};
+use crate::parsers::bin_fst::utils_serialization::{write_bin_i32, write_bin_i64};
+use crate::parsers::nom_utils::NomCustomError;
 use crate::semirings::Semiring;
-use crate::{Label, StateId, Tr, Trs, EPS_LABEL, NO_LABEL, UNASSIGNED};
+use crate::{Error, Label, StateId, Tr, Trs, EPS_LABEL, NO_LABEL, UNASSIGNED};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct LabelReachableData {
@@ -34,7 +42,7 @@ impl LabelReachableData {
     pub fn interval_set(&self, s: StateId) -> Result<&IntervalSet> {
         self.interval_sets
             .get(s)
-            .ok_or_else(|| format_err!("Missing state {}", s))
+            .ok_or_else(|| Error::InvalidStateId(s).into())
     }
 
     pub fn final_label(&self) -> Label {
@@ -113,6 +121,53 @@ impl LabelReachableData {
     }
 }
 
+impl MatcherFstData for LabelReachableData {
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, reach_input) = le_u8(i)?;
+        let (i, keep_relabel_data) = le_u8(i)?;
+        let (i, label2index) = if keep_relabel_data != 0 {
+            let (i, n_labels) = le_i64(i)?;
+            let (i, pairs) = count(tuple((le_i32, le_i32)), n_labels as usize)(i)?;
+            let label2index = pairs
+                .into_iter()
+                .map(|(label, index)| (label as Label, index as Label))
+                .collect();
+            (i, label2index)
+        } else {
+            (i, HashMap::new())
+        };
+        let (i, final_label) = le_i32(i)?;
+        let (i, n_interval_sets) = le_i64(i)?;
+        let (i, interval_sets) = count(IntervalSet::parse_binary, n_interval_sets as usize)(i)?;
+        Ok((
+            i,
+            Self {
+                reach_input: reach_input != 0,
+                final_label: final_label as Label,
+                label2index,
+                interval_sets,
+            },
+        ))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        file.write_all(&[self.reach_input as u8])?;
+        // The relabeling data is always kept.
+        file.write_all(&[1])?;
+        write_bin_i64(file, self.label2index.len() as i64)?;
+        for (label, index) in self.label2index.iter().sorted() {
+            write_bin_i32(file, *label as i32)?;
+            write_bin_i32(file, *index as i32)?;
+        }
+        write_bin_i32(file, self.final_label as i32)?;
+        write_bin_i64(file, self.interval_sets.len() as i64)?;
+        for interval_set in &self.interval_sets {
+            interval_set.write_binary(file)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct LabelReachable {
     data: Arc<LabelReachableData>,
@@ -9,7 +9,7 @@ use crate::algorithms::compose::lookahead_matchers::{
     LookAheadMatcherData, LookaheadMatcher, MatcherFlagsTrait,
 };
 use crate::algorithms::compose::matchers::{MatchType, Matcher, MatcherFlags};
-use crate::algorithms::compose::{LabelReachable, LabelReachableData};
+use crate::algorithms::compose::{LabelReachable, LabelReachableData, MatcherFstType};
 use crate::fst_traits::Fst;
 use crate::semirings::Semiring;
 use crate::{Tr, Trs, EPS_LABEL};
@@ -74,6 +74,23 @@ where
     }
 }
 
+impl<W, F, B, M, MFT> MatcherFstType for LabelLookAheadMatcher<W, F, B, M, MFT>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+    MFT: MatcherFlagsTrait,
+{
+    fn matcher_fst_type() -> String {
+        if MFT::flags().contains(MatcherFlags::INPUT_LOOKAHEAD_MATCHER) {
+            "ilabel_lookahead".to_string()
+        } else {
+            "olabel_lookahead".to_string()
+        }
+    }
+}
+
 impl<W, F, B, M, MFT> LookaheadMatcher<W, F, B> for LabelLookAheadMatcher<W, F, B, M, MFT>
 where
     W: Semiring + 'static,
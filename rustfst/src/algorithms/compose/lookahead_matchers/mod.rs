@@ -24,6 +24,26 @@ pub trait MatcherFlagsTrait: Debug + Clone {
     fn flags() -> MatcherFlags;
 }
 
+/// Flags of a `LabelLookAheadMatcher` doing look-ahead on the input labels.
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub struct ILabelLookAheadFlags {}
+
+impl MatcherFlagsTrait for ILabelLookAheadFlags {
+    fn flags() -> MatcherFlags {
+        MatcherFlags::ILABEL_LOOKAHEAD_FLAGS
+    }
+}
+
+/// Flags of a `LabelLookAheadMatcher` doing look-ahead on the output labels.
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub struct OLabelLookAheadFlags {}
+
+impl MatcherFlagsTrait for OLabelLookAheadFlags {
+    fn flags() -> MatcherFlags {
+        MatcherFlags::OLABEL_LOOKAHEAD_FLAGS
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LookAheadMatcherData<W: Semiring> {
     pub lookahead_weight: W,
@@ -10,11 +10,12 @@ use crate::algorithms::compose::lookahead_matchers::{
     LookAheadMatcherData, LookaheadMatcher, MatcherFlagsTrait,
 };
 use crate::algorithms::compose::matchers::{IterItemMatcher, MatchType, Matcher, MatcherFlags};
+use crate::algorithms::compose::MatcherFstType;
 use crate::fst_traits::Fst;
 use crate::semirings::Semiring;
 use crate::{Label, StateId, Tr, Trs, EPS_LABEL, NO_LABEL};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TrLookAheadMatcher<W, F, B, M, MFT>
 where
     W: Semiring,
@@ -74,6 +75,18 @@ where
     }
 }
 
+impl<W, F, B, M, MFT> MatcherFstType for TrLookAheadMatcher<W, F, B, M, MFT>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    fn matcher_fst_type() -> String {
+        "arc_lookahead".to_string()
+    }
+}
+
 impl<W, F, B, M, MFT> LookaheadMatcher<W, F, B> for TrLookAheadMatcher<W, F, B, M, MFT>
 where
     W: Semiring,
@@ -3,18 +3,59 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
+use std::io::Write;
+
 use anyhow::Result;
+use nom::combinator::verify;
+use nom::number::complete::{le_i32, le_u8};
+use nom::IResult;
 
 use crate::algorithms::compose::lookahead_matchers::{LabelLookAheadRelabeler, LookaheadMatcher};
 use crate::algorithms::compose::matchers::MatchType;
 use crate::algorithms::compose::FstAddOn;
 use crate::algorithms::compose::LabelReachableData;
+use crate::algorithms::{fst_convert, fst_convert_from_ref};
+use crate::fst_impls::const_fst::parse_const_fst;
+use crate::fst_impls::{ConstFst, VectorFst};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{
-    CoreFst, ExpandedFst, Fst, FstIntoIterator, FstIterator, MutableFst, StateIterator,
+    AllocableFst, CoreFst, ExpandedFst, Fst, FstIntoIterator, FstIterator, MutableFst,
+    SerializableFst, StateIterator,
 };
-use crate::semirings::Semiring;
-use crate::SymbolTable;
+use crate::parsers::bin_fst::fst_header::{FstFlags, FstHeader, OpenFstString, FST_MAGIC_NUMBER};
+use crate::parsers::bin_fst::utils_serialization::write_bin_i32;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::parsers::text_fst::ParsedTextFst;
+use crate::semirings::{Semiring, SerializableSemiring};
+use crate::{Error, SymbolTable, Tr};
+
+// Identifies stream data as an add-on FST.
+static ADD_ON_MAGIC_NUMBER: i32 = 446_681_434;
+static ADD_ON_FILE_VERSION: i32 = 1;
+static ADD_ON_MIN_FILE_VERSION: i32 = 1;
+
+/// Type of the FST written in the header of a serialized `MatcherFst`, depending on the matcher.
+/// Same types as in OpenFst.
+pub trait MatcherFstType {
+    fn matcher_fst_type() -> String;
+}
+
+/// Data of the matchers that can be serialized along a `MatcherFst`.
+pub trait MatcherFstData: Sized {
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>>;
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()>;
+}
+
+/// Matchers without data. Nothing is serialized.
+impl MatcherFstData for () {
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        Ok((i, ()))
+    }
+
+    fn write_binary<F: Write>(&self, _file: &mut F) -> Result<()> {
+        Ok(())
+    }
+}
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct MatcherFst<W, F, B, M, T> {
@@ -24,6 +65,16 @@ pub struct MatcherFst<W, F, B, M, T> {
 }
 
 impl<W, F, B, M, T> MatcherFst<W, F, B, M, T> {
+    /// Creates a `MatcherFst` from an FST and the data of its input and output matchers.
+    /// The FST must already be relabeled according to the data.
+    pub fn from_parts(fst: F, data: (Option<Arc<T>>, Option<Arc<T>>)) -> Self {
+        Self {
+            fst_add_on: FstAddOn::new(fst, data),
+            matcher: PhantomData,
+            w: PhantomData,
+        }
+    }
+
     pub fn fst(&self) -> &F {
         self.fst_add_on.fst()
     }
@@ -50,7 +101,7 @@ where
     B: Borrow<F>,
     M: LookaheadMatcher<W, F, B, MatcherData = LabelReachableData>,
 {
-    pub fn new(mut fst: F) -> Result<Self> {
+    pub fn new(mut fst: F) -> Result<Self, Error> {
         let imatcher_data = M::create_data::<F, _>(&fst, MatchType::MatchInput)?;
         let omatcher_data = M::create_data::<F, _>(&fst, MatchType::MatchOutput)?;
 
@@ -72,7 +123,7 @@ where
         mut fst: F,
         fst2: &mut F2,
         relabel_input: bool,
-    ) -> Result<Self> {
+    ) -> Result<Self, Error> {
         let imatcher_data = M::create_data::<F, _>(&fst, MatchType::MatchInput)?;
         let omatcher_data = M::create_data::<F, _>(&fst, MatchType::MatchOutput)?;
 
@@ -98,7 +149,7 @@ impl<W: Semiring, F: CoreFst<W>, B: Borrow<F>, M, T> CoreFst<W> for MatcherFst<W
         self.fst_add_on.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.fst_add_on.final_weight(state_id)
     }
 
@@ -106,7 +157,7 @@ impl<W: Semiring, F: CoreFst<W>, B: Borrow<F>, M, T> CoreFst<W> for MatcherFst<W
         self.fst_add_on.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.fst_add_on.num_trs(s)
     }
 
@@ -114,7 +165,7 @@ impl<W: Semiring, F: CoreFst<W>, B: Borrow<F>, M, T> CoreFst<W> for MatcherFst<W
         self.fst_add_on.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.fst_add_on.get_trs(state_id)
     }
 
@@ -126,11 +177,11 @@ impl<W: Semiring, F: CoreFst<W>, B: Borrow<F>, M, T> CoreFst<W> for MatcherFst<W
         self.fst_add_on.properties()
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.fst_add_on.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.fst_add_on.num_output_epsilons(state)
     }
 }
@@ -218,3 +269,98 @@ where
         self.fst_add_on.fst_into_iter()
     }
 }
+
+fn optionally_parse_data<T: MatcherFstData>(
+    i: &[u8],
+) -> IResult<&[u8], Option<Arc<T>>, NomCustomError<&[u8]>> {
+    let (i, have_data) = le_u8(i)?;
+    if have_data != 0 {
+        let (i, data) = T::parse_binary(i)?;
+        Ok((i, Some(Arc::new(data))))
+    } else {
+        Ok((i, None))
+    }
+}
+
+fn optionally_write_data<T: MatcherFstData, O: Write>(
+    file: &mut O,
+    data: &Option<Arc<T>>,
+) -> Result<()> {
+    file.write_all(&[data.is_some() as u8])?;
+    if let Some(data) = data {
+        data.write_binary(file)?;
+    }
+    Ok(())
+}
+
+fn parse_matcher_fst_parts<W: SerializableSemiring, T: MatcherFstData>(
+    i: &[u8],
+    fst_type: String,
+) -> IResult<&[u8], (ConstFst<W>, Option<Arc<T>>, Option<Arc<T>>), NomCustomError<&[u8]>> {
+    let (i, _hdr) = FstHeader::parse(i, ADD_ON_MIN_FILE_VERSION, fst_type, Tr::<W>::tr_type())?;
+    let (i, _magic_number) = verify(le_i32, |v: &i32| *v == ADD_ON_MAGIC_NUMBER)(i)?;
+    let (i, fst) = parse_const_fst(i)?;
+    let (i, have_add_on) = le_u8(i)?;
+    if have_add_on == 0 {
+        return Ok((i, (fst, None, None)));
+    }
+    let (i, idata) = optionally_parse_data(i)?;
+    let (i, odata) = optionally_parse_data(i)?;
+    Ok((i, (fst, idata, odata)))
+}
+
+/// Binary serialization compatible with the OpenFst lookahead FSTs (`olabel_lookahead`,
+/// `ilabel_lookahead` and `arc_lookahead`). As in OpenFst, the FST is stored as a `ConstFst`.
+impl<W, F, B, M, T> SerializableFst<W> for MatcherFst<W, F, B, M, T>
+where
+    W: SerializableSemiring,
+    F: MutableFst<W> + AllocableFst<W>,
+    B: Borrow<F> + Debug + PartialEq + Clone,
+    M: MatcherFstType + Debug + Clone + PartialEq,
+    T: MatcherFstData + Debug + Clone + PartialEq,
+{
+    fn fst_type() -> String {
+        M::matcher_fst_type()
+    }
+
+    fn load(data: &[u8]) -> Result<Self, Error> {
+        let (_, (fst, idata, odata)) = parse_matcher_fst_parts::<W, T>(data, Self::fst_type())
+            .map_err(|e| Error::from_nom("binary MatcherFst", data.len(), e))?;
+        Ok(Self::from_parts(fst_convert(fst), (idata, odata)))
+    }
+
+    fn store<O: Write>(&self, mut file: O) -> Result<()> {
+        let hdr = FstHeader {
+            magic_number: FST_MAGIC_NUMBER,
+            fst_type: OpenFstString::new(Self::fst_type()),
+            tr_type: OpenFstString::new(Tr::<W>::tr_type()),
+            version: ADD_ON_FILE_VERSION,
+            // The symbol tables are stored with the contained FST.
+            flags: FstFlags::empty(),
+            properties: self.properties().bits(),
+            start: -1,
+            num_states: 0,
+            num_trs: 0,
+            isymt: None,
+            osymt: None,
+        };
+        hdr.write(&mut file)?;
+        write_bin_i32(&mut file, ADD_ON_MAGIC_NUMBER)?;
+
+        let fst: VectorFst<W> = fst_convert_from_ref(self.fst());
+        ConstFst::from(fst).store(&mut file)?;
+
+        // The data of the matchers is always present, even if empty.
+        file.write_all(&[1])?;
+        let (idata, odata) = self.addon();
+        optionally_write_data(&mut file, idata)?;
+        optionally_write_data(&mut file, odata)?;
+
+        file.flush()?;
+        Ok(())
+    }
+
+    fn from_parsed_fst_text(_parsed_fst_text: ParsedTextFst<W>) -> Result<Self, Error> {
+        Err(Error::Other(format_err!("A MatcherFst can't be created from a text FST, the data of the matchers would be missing. Use MatcherFst::new instead.")))
+    }
+}
@@ -5,8 +5,12 @@ use anyhow::Result;
 use bitflags::bitflags;
 pub use generic_matcher::GenericMatcher;
 pub use multi_eps_matcher::{MultiEpsMatcher, MultiEpsMatcherFlags};
+pub use phi_matcher::PhiMatcher;
+pub use rho_matcher::RhoMatcher;
+pub use sigma_matcher::SigmaMatcher;
 pub use sorted_matcher::SortedMatcher;
 
+use crate::fst_properties::FstProperties;
 use crate::fst_traits::Fst;
 use crate::semirings::Semiring;
 use crate::{Label, StateId};
@@ -15,6 +19,9 @@ use std::borrow::Borrow;
 
 mod generic_matcher;
 mod multi_eps_matcher;
+mod phi_matcher;
+mod rho_matcher;
+mod sigma_matcher;
 mod sorted_matcher;
 
 bitflags! {
@@ -67,6 +74,50 @@ pub enum MatchType {
     MatchUnknown,
 }
 
+#[derive(Copy, Debug, PartialOrd, PartialEq, Clone)]
+/// Specifies whether the special label of a `SigmaMatcher`, `RhoMatcher` or `PhiMatcher`
+/// is rewritten on both sides of the matched transitions or only on the matched side.
+pub enum MatcherRewriteMode {
+    /// Rewrites both sides if the FST is an acceptor.
+    Auto,
+    /// Always rewrites both sides.
+    Always,
+    /// Only rewrites the matched side.
+    Never,
+}
+
+impl MatcherRewriteMode {
+    pub(crate) fn rewrite_both<W: Semiring, F: Fst<W>>(self, fst: &F) -> bool {
+        match self {
+            MatcherRewriteMode::Auto => fst.properties().contains(FstProperties::ACCEPTOR),
+            MatcherRewriteMode::Always => true,
+            MatcherRewriteMode::Never => false,
+        }
+    }
+}
+
+/// Replaces the special label of a transition by the label it matched.
+pub(crate) fn rewrite_special_label<W: Semiring>(
+    tr: &mut Tr<W>,
+    special_label: Label,
+    match_label: Label,
+    match_type: MatchType,
+    rewrite_both: bool,
+) {
+    if rewrite_both {
+        if tr.ilabel == special_label {
+            tr.ilabel = match_label;
+        }
+        if tr.olabel == special_label {
+            tr.olabel = match_label;
+        }
+    } else if match_type == MatchType::MatchInput {
+        tr.ilabel = match_label;
+    } else {
+        tr.olabel = match_label;
+    }
+}
+
 // Use this to avoid autoref
 #[derive(Clone)]
 pub enum IterItemMatcher<W: Semiring> {
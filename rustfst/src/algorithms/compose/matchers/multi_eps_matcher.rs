@@ -290,7 +290,7 @@ impl<K: Copy + Ord + AddAssign<usize> + SubAssign<usize> + Add<usize, Output = K
         if self.min_key == self.no_key || key < self.min_key {
             self.min_key = key;
         }
-        if self.max_key == self.no_key || self.max_key > key {
+        if self.max_key == self.no_key || key > self.max_key {
             self.max_key = key;
         }
     }
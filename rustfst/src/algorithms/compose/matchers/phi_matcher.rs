@@ -0,0 +1,195 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::matchers::{
+    rewrite_special_label, IterItemMatcher, MatchType, Matcher, MatcherFlags, MatcherRewriteMode,
+    REQUIRE_PRIORITY,
+};
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, EPS_LABEL, NO_LABEL};
+
+/// Matcher where the transitions labeled with `phi_label` are failure transitions : when a label
+/// can't be matched at a state, the phi transition is followed without consuming the label and
+/// the matching is retried from its destination. The weights of the followed phi transitions
+/// are multiplied to the weights of the matched transitions.
+///
+/// If `phi_loop` is true, a phi self-loop matches the label and stays at the same state.
+/// Otherwise, the phi transitions must not form cycles. At most one phi transition can leave
+/// each state. Using `NO_LABEL` as `phi_label` disables the special matching.
+#[derive(Debug, Clone)]
+pub struct PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    matcher: M,
+    match_type: MatchType,
+    phi_label: Label,
+    rewrite_both: bool,
+    phi_loop: bool,
+    ghost: PhantomData<(W, F, B)>,
+}
+
+impl<W, F, B, M> PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    pub fn new_with_opts(
+        fst: B,
+        match_type: MatchType,
+        phi_label: Label,
+        phi_loop: bool,
+        rewrite_mode: MatcherRewriteMode,
+    ) -> Result<Self> {
+        if match_type == MatchType::MatchBoth {
+            bail!("PhiMatcher: Bad match type")
+        }
+        if phi_label == EPS_LABEL {
+            bail!("PhiMatcher: 0 cannot be used as phi_label")
+        }
+        let rewrite_both = rewrite_mode.rewrite_both(fst.borrow());
+        Ok(Self {
+            matcher: M::new(fst, match_type)?,
+            match_type,
+            phi_label,
+            rewrite_both,
+            phi_loop,
+            ghost: PhantomData,
+        })
+    }
+
+    /// Returns the phi transition leaving `state`, if any.
+    fn phi_tr(&self, state: StateId) -> Result<Option<Tr<W>>> {
+        let mut it = self.matcher.iter(state, self.phi_label)?;
+        let phi_tr = match it.next() {
+            Some(IterItemMatcher::Tr(tr)) => tr,
+            _ => return Ok(None),
+        };
+        if it.next().is_some() {
+            bail!("PhiMatcher: Phi non-determinism not supported")
+        }
+        Ok(Some(phi_tr))
+    }
+}
+
+impl<W, F, B, M> Matcher<W, F, B> for PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    type Iter = std::vec::IntoIter<IterItemMatcher<W>>;
+
+    fn new(fst: B, match_type: MatchType) -> Result<Self> {
+        Self::new_with_opts(fst, match_type, NO_LABEL, true, MatcherRewriteMode::Auto)
+    }
+
+    fn iter(&self, state: StateId, label: Label) -> Result<Self::Iter> {
+        if self.phi_label != NO_LABEL && label == self.phi_label {
+            bail!("PhiMatcher: Bad label (phi)")
+        }
+        if self.phi_label == NO_LABEL || label == EPS_LABEL || label == NO_LABEL {
+            let items: Vec<_> = self.matcher.iter(state, label)?.collect();
+            return Ok(items.into_iter());
+        }
+
+        let mut s = state;
+        let mut phi_weight = W::one();
+        loop {
+            let items: Vec<_> = self.matcher.iter(s, label)?.collect();
+            if !items.is_empty() {
+                let mut trs = Vec::with_capacity(items.len());
+                for item in items {
+                    if let IterItemMatcher::Tr(mut tr) = item {
+                        tr.weight = phi_weight.times(&tr.weight)?;
+                        trs.push(IterItemMatcher::Tr(tr));
+                    }
+                }
+                return Ok(trs.into_iter());
+            }
+
+            let mut phi_tr = match self.phi_tr(s)? {
+                Some(tr) => tr,
+                None => return Ok(vec![].into_iter()),
+            };
+            if phi_tr.nextstate == s {
+                if !self.phi_loop {
+                    return Ok(vec![].into_iter());
+                }
+                // The phi self-loop matches the label.
+                phi_tr.weight = phi_weight.times(&phi_tr.weight)?;
+                rewrite_special_label(
+                    &mut phi_tr,
+                    self.phi_label,
+                    label,
+                    self.match_type,
+                    self.rewrite_both,
+                );
+                return Ok(vec![IterItemMatcher::Tr(phi_tr)].into_iter());
+            }
+            phi_weight.times_assign(&phi_tr.weight)?;
+            s = phi_tr.nextstate;
+        }
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        let final_weight = self.matcher.final_weight(state)?;
+        if self.phi_label == NO_LABEL || final_weight.is_some() {
+            return Ok(final_weight);
+        }
+
+        // A state is final if a final state can be reached with phi transitions.
+        let mut s = state;
+        let mut weight = W::one();
+        loop {
+            if let Some(final_weight) = self.matcher.final_weight(s)? {
+                weight.times_assign(final_weight)?;
+                return Ok(Some(weight));
+            }
+            let phi_tr = match self.phi_tr(s)? {
+                Some(tr) => tr,
+                None => return Ok(None),
+            };
+            // Phi self-loops are not followed.
+            if phi_tr.nextstate == s {
+                return Ok(None);
+            }
+            weight.times_assign(&phi_tr.weight)?;
+            s = phi_tr.nextstate;
+        }
+    }
+
+    fn match_type(&self, test: bool) -> Result<MatchType> {
+        self.matcher.match_type(test)
+    }
+
+    fn flags(&self) -> MatcherFlags {
+        if self.phi_label == NO_LABEL || self.match_type == MatchType::MatchNone {
+            self.matcher.flags()
+        } else {
+            self.matcher.flags() | MatcherFlags::REQUIRE_MATCH
+        }
+    }
+
+    fn priority(&self, state: StateId) -> Result<usize> {
+        if self.phi_label != NO_LABEL {
+            Ok(REQUIRE_PRIORITY)
+        } else {
+            self.matcher.priority(state)
+        }
+    }
+
+    fn fst(&self) -> &B {
+        self.matcher.fst()
+    }
+}
@@ -0,0 +1,134 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::matchers::{
+    rewrite_special_label, IterItemMatcher, MatchType, Matcher, MatcherFlags, MatcherRewriteMode,
+    REQUIRE_PRIORITY,
+};
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, EPS_LABEL, NO_LABEL};
+
+/// Matcher where the transitions labeled with `sigma_label` match any non-epsilon label,
+/// in addition to the transitions explicitly labeled with the requested label.
+///
+/// The `sigma_label` of the matched transitions is replaced by the requested label according to
+/// the `MatcherRewriteMode`. Using `NO_LABEL` as `sigma_label` disables the special matching.
+#[derive(Debug, Clone)]
+pub struct SigmaMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    matcher: M,
+    match_type: MatchType,
+    sigma_label: Label,
+    rewrite_both: bool,
+    ghost: PhantomData<(W, F, B)>,
+}
+
+impl<W, F, B, M> SigmaMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    pub fn new_with_opts(
+        fst: B,
+        match_type: MatchType,
+        sigma_label: Label,
+        rewrite_mode: MatcherRewriteMode,
+    ) -> Result<Self> {
+        if match_type == MatchType::MatchBoth {
+            bail!("SigmaMatcher: Bad match type")
+        }
+        if sigma_label == EPS_LABEL {
+            bail!("SigmaMatcher: 0 cannot be used as sigma_label")
+        }
+        let rewrite_both = rewrite_mode.rewrite_both(fst.borrow());
+        Ok(Self {
+            matcher: M::new(fst, match_type)?,
+            match_type,
+            sigma_label,
+            rewrite_both,
+            ghost: PhantomData,
+        })
+    }
+
+    fn has_sigma(&self, state: StateId) -> Result<bool> {
+        if self.sigma_label == NO_LABEL {
+            return Ok(false);
+        }
+        Ok(self.matcher.iter(state, self.sigma_label)?.next().is_some())
+    }
+}
+
+impl<W, F, B, M> Matcher<W, F, B> for SigmaMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    type Iter = std::vec::IntoIter<IterItemMatcher<W>>;
+
+    fn new(fst: B, match_type: MatchType) -> Result<Self> {
+        Self::new_with_opts(fst, match_type, NO_LABEL, MatcherRewriteMode::Auto)
+    }
+
+    fn iter(&self, state: StateId, label: Label) -> Result<Self::Iter> {
+        if self.sigma_label != NO_LABEL && label == self.sigma_label {
+            bail!("SigmaMatcher: Bad label (sigma)")
+        }
+        let mut items: Vec<_> = self.matcher.iter(state, label)?.collect();
+        if self.sigma_label != NO_LABEL && label != EPS_LABEL && label != NO_LABEL {
+            for item in self.matcher.iter(state, self.sigma_label)? {
+                if let IterItemMatcher::Tr(mut tr) = item {
+                    rewrite_special_label(
+                        &mut tr,
+                        self.sigma_label,
+                        label,
+                        self.match_type,
+                        self.rewrite_both,
+                    );
+                    items.push(IterItemMatcher::Tr(tr));
+                }
+            }
+        }
+        Ok(items.into_iter())
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        self.matcher.final_weight(state)
+    }
+
+    fn match_type(&self, test: bool) -> Result<MatchType> {
+        self.matcher.match_type(test)
+    }
+
+    fn flags(&self) -> MatcherFlags {
+        if self.sigma_label == NO_LABEL || self.match_type == MatchType::MatchNone {
+            self.matcher.flags()
+        } else {
+            self.matcher.flags() | MatcherFlags::REQUIRE_MATCH
+        }
+    }
+
+    fn priority(&self, state: StateId) -> Result<usize> {
+        if self.has_sigma(state)? {
+            Ok(REQUIRE_PRIORITY)
+        } else {
+            self.matcher.priority(state)
+        }
+    }
+
+    fn fst(&self) -> &B {
+        self.matcher.fst()
+    }
+}
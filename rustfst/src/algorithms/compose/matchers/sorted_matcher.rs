@@ -50,7 +50,7 @@ where
     }
 
     fn final_weight(&self, state: usize) -> Result<Option<W>> {
-        self.fst.borrow().final_weight(state)
+        Ok(self.fst.borrow().final_weight(state)?)
     }
 
     fn match_type(&self, test: bool) -> Result<MatchType> {
@@ -89,7 +89,7 @@ where
     }
 
     fn priority(&self, state: StateId) -> Result<usize> {
-        self.fst.borrow().num_trs(state)
+        Ok(self.fst.borrow().num_trs(state)?)
     }
 
     fn fst(&self) -> &B {
@@ -1,5 +1,8 @@
 pub use self::add_on::FstAddOn;
-pub use self::compose::{compose, compose_with_config, ComposeConfig, ComposeFilterEnum};
+pub use self::compose::{
+    check_compose_symbols, compose, compose_with_config, ComposeConfig, ComposeFilterEnum,
+    MatcherEnum,
+};
 pub use self::compose_fst::ComposeFst;
 pub use self::compose_fst_op::ComposeFstOp;
 pub use self::compose_fst_op_options::ComposeFstOpOptions;
@@ -7,7 +10,8 @@ pub use self::compose_state_tuple::ComposeStateTuple;
 pub use self::interval_reach_visitor::IntervalReachVisitor;
 pub use self::interval_set::{IntInterval, IntervalSet};
 pub use self::label_reachable::{LabelReachable, LabelReachableData};
-pub use self::matcher_fst::MatcherFst;
+pub use self::matcher_fst::{MatcherFst, MatcherFstData, MatcherFstType};
+pub use self::nary_compose_fst::NaryComposeFst;
 pub use self::state_reachable::StateReachable;
 
 pub mod compose_filters;
@@ -26,4 +30,5 @@ mod interval_reach_visitor;
 mod interval_set;
 mod label_reachable;
 mod matcher_fst;
+mod nary_compose_fst;
 mod state_reachable;
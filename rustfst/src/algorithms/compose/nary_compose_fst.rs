@@ -0,0 +1,372 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::compose_filters::{
+    ComposeFilterBuilder, SequenceComposeFilterBuilder,
+};
+use crate::algorithms::compose::lookahead_filters::{
+    LookAheadComposeFilterBuilder, PushLabelsComposeFilterBuilder, PushWeightsComposeFilterBuilder,
+    SMatchInput,
+};
+use crate::algorithms::compose::lookahead_matchers::{
+    ILabelLookAheadFlags, LabelLookAheadMatcher, LookaheadMatcher,
+};
+use crate::algorithms::compose::matchers::{MatchType, SortedMatcher};
+use crate::algorithms::compose::{
+    ComposeFstOp, ComposeFstOpOptions, LabelReachableData, MatcherFst,
+};
+use crate::algorithms::lazy::{FstOp, LazyFst, SimpleVecCache};
+use crate::algorithms::tr_compares::{OLabelCompare, TrCompare};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Error, StateId, SymbolTable, TrsVec};
+
+type LookAheadFst<W> = MatcherFst<
+    W,
+    VectorFst<W>,
+    Arc<VectorFst<W>>,
+    LabelLookAheadMatcher<
+        W,
+        VectorFst<W>,
+        Arc<VectorFst<W>>,
+        SortedMatcher<W, VectorFst<W>, Arc<VectorFst<W>>>,
+        ILabelLookAheadFlags,
+    >,
+    LabelReachableData,
+>;
+
+type Matcher1<W, F1> = SortedMatcher<W, F1, Arc<F1>>;
+type Matcher2<W> = LabelLookAheadMatcher<
+    W,
+    LookAheadFst<W>,
+    Arc<LookAheadFst<W>>,
+    SortedMatcher<W, LookAheadFst<W>, Arc<LookAheadFst<W>>>,
+    ILabelLookAheadFlags,
+>;
+
+type SeqFilterBuilder<W, F1> = SequenceComposeFilterBuilder<
+    W,
+    F1,
+    LookAheadFst<W>,
+    Arc<F1>,
+    Arc<LookAheadFst<W>>,
+    Matcher1<W, F1>,
+    Matcher2<W>,
+>;
+type LookFilterBuilder<W, F1> = LookAheadComposeFilterBuilder<
+    W,
+    F1,
+    LookAheadFst<W>,
+    Arc<F1>,
+    Arc<LookAheadFst<W>>,
+    Matcher1<W, F1>,
+    Matcher2<W>,
+    SeqFilterBuilder<W, F1>,
+    SMatchInput,
+>;
+type PushWeightsFilterBuilder<W, F1> = PushWeightsComposeFilterBuilder<
+    W,
+    F1,
+    LookAheadFst<W>,
+    Arc<F1>,
+    Arc<LookAheadFst<W>>,
+    Matcher1<W, F1>,
+    Matcher2<W>,
+    LookFilterBuilder<W, F1>,
+    SMatchInput,
+>;
+type PushLabelsFilterBuilder<W, F1> = PushLabelsComposeFilterBuilder<
+    W,
+    F1,
+    LookAheadFst<W>,
+    Arc<F1>,
+    Arc<LookAheadFst<W>>,
+    Matcher1<W, F1>,
+    Matcher2<W>,
+    PushWeightsFilterBuilder<W, F1>,
+    SMatchInput,
+>;
+
+type CascadeLazyFst<W> = LazyFst<W, Arc<dyn FstOp<W>>, SimpleVecCache<W>>;
+
+/// Sorts the transitions computed by another `FstOp` by output label. Required to look ahead
+/// into a lazy composition, as the label reachability is searched in sorted transitions.
+#[derive(Debug)]
+struct OLabelSortedOp<Op> {
+    op: Op,
+}
+
+impl<W: Semiring, Op: FstOp<W>> FstOp<W> for OLabelSortedOp<Op> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        self.op.compute_start()
+    }
+
+    fn compute_trs(&self, id: usize) -> Result<TrsVec<W>> {
+        let trs = self.op.compute_trs(id)?;
+        let mut trs = Arc::try_unwrap(trs.0).unwrap_or_else(|trs| trs.as_ref().clone());
+        trs.sort_by(|a, b| OLabelCompare::compare(a, b));
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<W>> {
+        self.op.compute_final_weight(id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        OLabelCompare::properties(self.op.properties())
+    }
+}
+
+/// Lazy composition with look-ahead of `fst1` and a look-ahead FST. The result is sorted by
+/// output label so that it can itself be the first operand of a look-ahead composition.
+fn lookahead_compose<W, F1>(fst1: Arc<F1>, fst2: Arc<LookAheadFst<W>>) -> Result<CascadeLazyFst<W>>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: Fst<W> + 'static,
+{
+    let matcher2 = Matcher2::new_with_data(
+        Arc::clone(&fst2),
+        MatchType::MatchInput,
+        fst2.data(MatchType::MatchInput).cloned(),
+    )?;
+    let filter_builder = PushLabelsFilterBuilder::<W, F1>::new(
+        Arc::clone(&fst1),
+        Arc::clone(&fst2),
+        None,
+        Some(matcher2),
+    )?;
+    let opts = ComposeFstOpOptions::new(None, None, filter_builder, None);
+
+    let isymt = fst1.input_symbols().cloned();
+    let osymt = fst2.output_symbols().cloned();
+    let compose_op = ComposeFstOp::<
+        W,
+        F1,
+        LookAheadFst<W>,
+        Arc<F1>,
+        Arc<LookAheadFst<W>>,
+        Matcher1<W, F1>,
+        Matcher2<W>,
+        PushLabelsFilterBuilder<W, F1>,
+    >::new(fst1, fst2, opts)?;
+    let op: Arc<dyn FstOp<W>> = Arc::new(OLabelSortedOp { op: compose_op });
+    Ok(LazyFst::from_op_and_cache(
+        op,
+        SimpleVecCache::default(),
+        isymt,
+        osymt,
+    ))
+}
+
+/// Lazy composition of a cascade of FSTs `fst_1 ∘ fst_2 ∘ ... ∘ fst_n`.
+///
+/// Every FST but the first one is turned into a `MatcherFst` doing look-ahead on its input
+/// labels and the compositions are nested from the left : `((fst_1 ∘ fst_2) ∘ fst_3) ∘ ...`.
+/// The intermediate compositions are never expanded : the look-ahead is done on the
+/// transitions they compute on demand. Look-ahead filtering, weight pushing and label pushing
+/// are thus applied at each level of the cascade.
+///
+/// The labels between two consecutive FSTs are relabeled internally. The input labels of the
+/// first FST and the output labels of the last one are left untouched.
+#[derive(Debug, Clone)]
+pub struct NaryComposeFst<W: Semiring>(CascadeLazyFst<W>);
+
+impl<W> NaryComposeFst<W>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+{
+    /// Creates the lazy composition of `fsts`. At least two FSTs are required.
+    pub fn new(mut fsts: Vec<VectorFst<W>>) -> Result<Self, Error> {
+        if fsts.len() < 2 {
+            return Err(Error::Other(format_err!(
+                "NaryComposeFst: At least two FSTs are required, got {}",
+                fsts.len()
+            )));
+        }
+
+        // The look-ahead FSTs are built from right to left : the reachability data of an FST
+        // is used to relabel the output labels of the FST preceding it.
+        let mut lookahead_fsts = Vec::with_capacity(fsts.len() - 1);
+        while fsts.len() > 1 {
+            let fst = fsts.pop().unwrap();
+            let previous_fst = fsts.last_mut().unwrap();
+            lookahead_fsts.push(Arc::new(LookAheadFst::new_with_relabeling(
+                fst,
+                previous_fst,
+                false,
+            )?));
+        }
+        let first_fst = fsts.pop().unwrap();
+
+        let mut lookahead_fsts = lookahead_fsts.into_iter().rev();
+        let mut fst = lookahead_compose(Arc::new(first_fst), lookahead_fsts.next().unwrap())?;
+        for lookahead_fst in lookahead_fsts {
+            fst = lookahead_compose(Arc::new(fst), lookahead_fst)?;
+        }
+        Ok(NaryComposeFst(fst))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F, Error> {
+        self.0.compute()
+    }
+}
+
+impl<W: Semiring> CoreFst<W> for NaryComposeFst<W> {
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W: Semiring> StateIterator<'a> for NaryComposeFst<W> {
+    type Iter = <CascadeLazyFst<W> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W: Semiring> FstIterator<'a, W> for NaryComposeFst<W> {
+    type FstIter = <CascadeLazyFst<W> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W: Semiring> Fst<W> for NaryComposeFst<W> {
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::compose::compose;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    fn sorted_paths<F: Fst<TropicalWeight>>(fst: &F) -> Vec<(Vec<usize>, Vec<usize>, f32)> {
+        let mut paths: Vec<_> = fst
+            .paths_iter()
+            .map(|p| (p.ilabels, p.olabels, *p.weight.value()))
+            .collect();
+        paths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        paths
+    }
+
+    #[test]
+    fn test_nary_compose_fst() -> Result<()> {
+        // Maps 1 to 1 or 3, and 2 to 2.
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst1.add_state();
+        let s1 = fst1.add_state();
+        fst1.set_start(s0)?;
+        fst1.set_final(s1, TropicalWeight::one())?;
+        fst1.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(1.0), s1))?;
+        fst1.add_tr(s0, Tr::new(1, 3, TropicalWeight::new(2.0), s1))?;
+        fst1.add_tr(s0, Tr::new(2, 2, TropicalWeight::new(0.5), s1))?;
+
+        // Maps 1 to 4 and 3 to 5, 2 to epsilon.
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst2.add_state();
+        fst2.set_start(s0)?;
+        fst2.set_final(s0, TropicalWeight::new(0.25))?;
+        fst2.add_tr(s0, Tr::new(1, 4, TropicalWeight::new(1.5), s0))?;
+        fst2.add_tr(s0, Tr::new(2, 0, TropicalWeight::one(), s0))?;
+        fst2.add_tr(s0, Tr::new(3, 5, TropicalWeight::new(3.0), s0))?;
+
+        // Accepts a single 4 or a single 5.
+        let mut fst3 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst3.add_state();
+        let s1 = fst3.add_state();
+        fst3.set_start(s0)?;
+        fst3.set_final(s1, TropicalWeight::one())?;
+        fst3.add_tr(s0, Tr::new(4, 6, TropicalWeight::new(0.5), s1))?;
+        fst3.add_tr(s0, Tr::new(5, 7, TropicalWeight::one(), s1))?;
+
+        let fst12: VectorFst<_> = compose(Arc::new(fst1.clone()), Arc::new(fst2.clone()))?;
+        let ref_fst: VectorFst<_> = compose(Arc::new(fst12), Arc::new(fst3.clone()))?;
+
+        let nary_fst = NaryComposeFst::new(vec![fst1, fst2, fst3])?;
+        let static_fst: VectorFst<_> = nary_fst.compute()?;
+
+        assert_eq!(sorted_paths(&static_fst), sorted_paths(&ref_fst));
+        Ok(())
+    }
+
+    #[test]
+    fn test_nary_compose_fst_too_few_fsts() {
+        let res = NaryComposeFst::<TropicalWeight>::new(vec![VectorFst::new()]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_nary_compose_fst_clonable() {
+        fn is_clone<T: Clone>() {}
+        is_clone::<NaryComposeFst<TropicalWeight>>();
+    }
+}
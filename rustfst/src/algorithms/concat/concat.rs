@@ -1,11 +1,16 @@
-use anyhow::Result;
-
 use crate::fst_properties::mutable_properties::concat_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
 use crate::semirings::Semiring;
+use crate::symbol_table::compat_symbols;
 use crate::tr::Tr;
-use crate::{Trs, EPS_LABEL};
+use crate::{Error, Trs, EPS_LABEL};
+
+#[derive(PartialOrd, PartialEq, Debug, Clone, Copy, Default)]
+pub struct ConcatConfig {
+    /// If true, an error is returned when the symbol tables of the two FSTs are not compatible.
+    pub compat_symbols: bool,
+}
 
 /// Performs the concatenation of two wFSTs. If `A` transduces string `x` to `y` with weight `a`
 /// and `B` transduces string `w` to `v` with weight `b`, then their concatenation
@@ -51,12 +56,40 @@ use crate::{Trs, EPS_LABEL};
 ///
 /// ![concat_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/concat_out.svg?sanitize=true)
 ///
-pub fn concat<W, F1, F2>(fst_1: &mut F1, fst_2: &F2) -> Result<()>
+pub fn concat<W, F1, F2>(fst_1: &mut F1, fst_2: &F2) -> Result<(), Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W> + MutableFst<W> + AllocableFst<W>,
+    F2: ExpandedFst<W>,
+{
+    concat_with_config(fst_1, fst_2, ConcatConfig::default())
+}
+
+/// Same as `concat` but the behaviour can be customized with a `ConcatConfig`.
+pub fn concat_with_config<W, F1, F2>(
+    fst_1: &mut F1,
+    fst_2: &F2,
+    config: ConcatConfig,
+) -> Result<(), Error>
 where
     W: Semiring,
     F1: ExpandedFst<W> + MutableFst<W> + AllocableFst<W>,
     F2: ExpandedFst<W>,
 {
+    if config.compat_symbols
+        && (!compat_symbols(
+            fst_1.input_symbols().map(|s| s.as_ref()),
+            fst_2.input_symbols().map(|s| s.as_ref()),
+        ) || !compat_symbols(
+            fst_1.output_symbols().map(|s| s.as_ref()),
+            fst_2.output_symbols().map(|s| s.as_ref()),
+        ))
+    {
+        return Err(Error::IncompatibleSymbolTables {
+            algorithm: "concat",
+        });
+    }
+
     let props1 = fst_1.properties();
     let props2 = fst_2.properties();
     let start1 = fst_1.start();
@@ -103,3 +136,24 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_concat_properties_proptest(
+            mut fst_1 in any_fst_with_properties::<TropicalWeight>(),
+            fst_2 in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            concat(&mut fst_1, &fst_2).unwrap();
+            prop_assert!(properties_compatible(&fst_1));
+        }
+    }
+}
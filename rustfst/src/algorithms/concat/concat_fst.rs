@@ -1,13 +1,11 @@
 use std::sync::Arc;
 
-use anyhow::Result;
-
 use crate::algorithms::replace::ReplaceFst;
 use crate::fst_properties::mutable_properties::concat_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
 use crate::semirings::Semiring;
-use crate::{SymbolTable, Tr, TrsVec, EPS_LABEL};
+use crate::{Error, SymbolTable, Tr, TrsVec, EPS_LABEL};
 
 /// Computes the concatenation (product) of two FSTs; this version is a delayed
 /// FST. If FST1 transduces string x to y with weight a and FST2 transduces
@@ -23,7 +21,7 @@ where
 {
     //TODO: Use a borrow and not a move
     //TODO: Allow fsts of different types
-    pub fn new(fst1: F, fst2: F) -> Result<Self> {
+    pub fn new(fst1: F, fst2: F) -> Result<Self, Error> {
         let props1 = fst1.properties();
         let props2 = fst2.properties();
         let mut rfst = F::new();
@@ -51,7 +49,7 @@ where
     }
 
     /// Turns the Lazy FST into a static one.
-    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
         self.0.compute()
     }
 }
@@ -67,7 +65,7 @@ where
         self.0.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.0.final_weight(state_id)
     }
 
@@ -75,7 +73,7 @@ where
         self.0.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.0.num_trs(s)
     }
 
@@ -83,7 +81,7 @@ where
         self.0.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.0.get_trs(state_id)
     }
 
@@ -95,11 +93,11 @@ where
         self.1
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_output_epsilons(state)
     }
 }
@@ -1,5 +1,5 @@
 mod concat;
 mod concat_fst;
 
-pub use concat::concat;
+pub use concat::{concat, concat_with_config, ConcatConfig};
 pub use concat_fst::ConcatFst;
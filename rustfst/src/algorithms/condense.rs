@@ -1,18 +1,16 @@
-use anyhow::Result;
-
 use crate::algorithms::dfs_visit::dfs_visit;
 use crate::algorithms::tr_filters::AnyTrFilter;
 use crate::algorithms::visitors::SccVisitor;
 use crate::fst_traits::{ExpandedFst, Fst, MutableFst};
 use crate::semirings::Semiring;
-use crate::Trs;
+use crate::{Error, Trs};
 
 // Returns an acyclic FST where each SCC in the input FST has been condensed to
 // a single state with transitions between SCCs retained and within SCCs
 // dropped. Also populates 'scc' with a mapping from input to output states.
 pub fn condense<W: Semiring, FI: Fst<W> + ExpandedFst<W>, FO: MutableFst<W>>(
     ifst: &FI,
-) -> Result<(Vec<i32>, FO)> {
+) -> Result<(Vec<i32>, FO), Error> {
     let mut visitor = SccVisitor::new(ifst, true, false);
     dfs_visit(ifst, &mut visitor, &AnyTrFilter {}, false);
     let scc = visitor.scc.unwrap();
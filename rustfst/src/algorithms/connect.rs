@@ -9,9 +9,9 @@ use crate::fst_properties::FstProperties;
 use crate::fst_traits::Fst;
 use crate::fst_traits::{ExpandedFst, MutableFst};
 use crate::semirings::Semiring;
-use crate::StateId;
 use crate::Tr;
 use crate::NO_STATE_ID;
+use crate::{Error, StateId};
 
 /// This operation trims an FST, removing states and trs that are not on successful paths.
 ///
@@ -49,21 +49,47 @@ use crate::NO_STATE_ID;
 ///
 /// ![connect_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/connect_out.svg?sanitize=true)
 ///
-pub fn connect<W: Semiring, F: ExpandedFst<W> + MutableFst<W>>(fst: &mut F) -> Result<()> {
+pub fn connect<W: Semiring, F: ExpandedFst<W> + MutableFst<W>>(fst: &mut F) -> Result<(), Error> {
+    connect_with_state_map(fst)?;
+    Ok(())
+}
+
+/// Same as `connect` but also returns, for each state of the input FST, its id in the
+/// trimmed FST or `None` if it has been removed.
+pub(crate) fn connect_with_state_map<W: Semiring, F: ExpandedFst<W> + MutableFst<W>>(
+    fst: &mut F,
+) -> Result<Vec<Option<StateId>>> {
+    // Already connected, nothing to trim.
+    if fst
+        .properties()
+        .contains(FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE)
+    {
+        return Ok((0..fst.num_states()).map(Some).collect());
+    }
     let mut visitor = ConnectVisitor::new(fst);
     dfs_visit(fst, &mut visitor, &AnyTrFilter {}, false);
     let mut dstates = Vec::with_capacity(visitor.access.len());
+    // Deleting states preserves the relative order of the remaining ones.
+    let mut state_map = Vec::with_capacity(visitor.access.len());
+    let mut nstates = 0;
     for s in 0..visitor.access.len() {
         if !visitor.access[s] || !visitor.coaccess[s] {
             dstates.push(s);
+            state_map.push(None);
+        } else {
+            state_map.push(Some(nstates));
+            nstates += 1;
         }
     }
-    fst.del_states(dstates)?;
+    // Nothing to trim, all the properties are preserved.
+    if !dstates.is_empty() {
+        fst.del_states(dstates)?;
+    }
     fst.set_properties_with_mask(
         FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE,
         FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE,
     );
-    Ok(())
+    Ok(state_map)
 }
 
 struct ConnectVisitor<'a, W: Semiring, F: Fst<W>> {
@@ -190,6 +216,7 @@ mod tests {
 
     use crate::fst_properties::FstProperties;
     use crate::prelude::*;
+    use crate::proptest_fst::any_fst_with_properties;
 
     use super::*;
 
@@ -201,5 +228,17 @@ mod tests {
                 FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE
             ));
         }
+
+        #[test]
+        fn test_connect_known_properties_proptest(
+            mut fst in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            let mut fst_unknown_props = fst.clone();
+            fst_unknown_props.set_properties(FstProperties::empty());
+            let state_map = connect_with_state_map(&mut fst).unwrap();
+            let state_map_unknown_props = connect_with_state_map(&mut fst_unknown_props).unwrap();
+            prop_assert_eq!(state_map, state_map_unknown_props);
+            prop_assert_eq!(fst, fst_unknown_props);
+        }
     }
 }
@@ -0,0 +1,413 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::connect;
+use crate::algorithms::preconditions::check_semiring_properties;
+use crate::algorithms::queues::natural_less;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{CoreFst, Fst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{Error, Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Configuration of the beam search of `decode`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecoderConfig<W: Semiring> {
+    /// The tokens whose weight is worse than the best token of the frame times `beam` are
+    /// pruned. `W::zero()` disables the beam.
+    pub beam: W,
+    /// Maximum number of tokens kept at each frame.
+    pub max_active: Option<usize>,
+    /// Whether the lattice of the surviving tokens is generated.
+    pub generate_lattice: bool,
+}
+
+impl<W: Semiring> Default for DecoderConfig<W> {
+    fn default() -> Self {
+        Self {
+            beam: W::zero(),
+            max_active: None,
+            generate_lattice: false,
+        }
+    }
+}
+
+impl<W: Semiring> DecoderConfig<W> {
+    pub fn with_beam(self, beam: W) -> Self {
+        Self { beam, ..self }
+    }
+
+    pub fn with_max_active(self, max_active: Option<usize>) -> Self {
+        Self { max_active, ..self }
+    }
+
+    pub fn with_generate_lattice(self, generate_lattice: bool) -> Self {
+        Self {
+            generate_lattice,
+            ..self
+        }
+    }
+}
+
+/// Result of `decode`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeResult<W: Semiring> {
+    /// Best successful path as a linear FST, empty if no final state has been reached at the
+    /// last frame.
+    pub best_path: VectorFst<W>,
+    /// Weight of the best path.
+    pub best_weight: W,
+    /// Lattice of the surviving tokens, if requested.
+    pub lattice: Option<VectorFst<W>>,
+}
+
+struct Token<W> {
+    state: StateId,
+    weight: W,
+    best_link: Option<usize>,
+}
+
+struct Link<W> {
+    prev: usize,
+    next: usize,
+    ilabel: Label,
+    olabel: Label,
+    weight: W,
+}
+
+struct Decoder<'a, W: Semiring, F: Fst<W>> {
+    fst: &'a F,
+    config: DecoderConfig<W>,
+    tokens: Vec<Token<W>>,
+    links: Vec<Link<W>>,
+    // Links of the epsilon transitions, by (prev token, transition).
+    eps_links: HashMap<(usize, usize), usize>,
+}
+
+impl<'a, W: Semiring, F: Fst<W>> Decoder<'a, W, F> {
+    /// Relaxes the token of the destination of `tr` in `frame_tokens` with a link from `prev`.
+    /// The links of the epsilon transitions are identified by `eps_key` to be created only once.
+    /// Returns whether the token has been created or improved.
+    fn relax(
+        &mut self,
+        frame_tokens: &mut HashMap<StateId, usize>,
+        prev: usize,
+        tr: &Tr<W>,
+        weight: W,
+        eps_key: Option<(usize, usize)>,
+    ) -> Result<bool> {
+        let total = self.tokens[prev].weight.times(&weight)?;
+        let (next, improved) = match frame_tokens.get(&tr.nextstate) {
+            Some(&next) => (next, natural_less(&total, &self.tokens[next].weight)?),
+            None => {
+                self.tokens.push(Token {
+                    state: tr.nextstate,
+                    weight: W::zero(),
+                    best_link: None,
+                });
+                let next = self.tokens.len() - 1;
+                frame_tokens.insert(tr.nextstate, next);
+                (next, true)
+            }
+        };
+        let mut link = eps_key.and_then(|k| self.eps_links.get(&k).cloned());
+        if link.is_none() && (improved || self.config.generate_lattice) {
+            self.links.push(Link {
+                prev,
+                next,
+                ilabel: tr.ilabel,
+                olabel: tr.olabel,
+                weight,
+            });
+            link = Some(self.links.len() - 1);
+            if let Some(k) = eps_key {
+                self.eps_links.insert(k, self.links.len() - 1);
+            }
+        }
+        if improved {
+            self.tokens[next].weight = total;
+            self.tokens[next].best_link = link;
+        }
+        Ok(improved)
+    }
+
+    /// Follows the epsilon transitions from the tokens of a frame.
+    fn process_epsilons(&mut self, frame_tokens: &mut HashMap<StateId, usize>) -> Result<()> {
+        let mut queue: VecDeque<usize> = frame_tokens.values().cloned().collect();
+        let mut enqueued: HashSet<usize> = queue.iter().cloned().collect();
+        while let Some(token) = queue.pop_front() {
+            enqueued.remove(&token);
+            let state = self.tokens[token].state;
+            for (tr_idx, tr) in self.fst.get_trs(state)?.trs().iter().enumerate() {
+                if tr.ilabel != EPS_LABEL {
+                    continue;
+                }
+                let eps_key = Some((token, tr_idx));
+                if self.relax(frame_tokens, token, tr, tr.weight.clone(), eps_key)? {
+                    let next = frame_tokens[&tr.nextstate];
+                    if enqueued.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the tokens of a frame surviving the beam and the max-active limit.
+    fn prune(&self, frame_tokens: &HashMap<StateId, usize>) -> Result<Vec<usize>> {
+        let mut tokens: Vec<usize> = frame_tokens.values().cloned().collect();
+        tokens.sort_by(|t1, t2| {
+            let (w1, w2) = (&self.tokens[*t1].weight, &self.tokens[*t2].weight);
+            if natural_less(w1, w2).unwrap() {
+                std::cmp::Ordering::Less
+            } else if natural_less(w2, w1).unwrap() {
+                std::cmp::Ordering::Greater
+            } else {
+                t1.cmp(t2)
+            }
+        });
+        let best = match tokens.first() {
+            Some(t) => self.tokens[*t].weight.clone(),
+            None => return Ok(tokens),
+        };
+        let cutoff = best.times(&self.config.beam)?;
+        let mut survivors = Vec::with_capacity(tokens.len());
+        for t in tokens {
+            if natural_less(&cutoff, &self.tokens[t].weight)? {
+                break;
+            }
+            if let Some(max_active) = self.config.max_active {
+                if survivors.len() >= max_active {
+                    break;
+                }
+            }
+            survivors.push(t);
+        }
+        Ok(survivors)
+    }
+}
+
+/// Decodes a sequence of `num_frames` frames with a beam-pruned Viterbi search (token passing)
+/// in an FST whose input labels are the acoustic units. Each transition with a non-epsilon input
+/// label consumes a frame and its weight is multiplied by `acoustic_cost(frame, ilabel)`. The
+/// transitions with an epsilon input label don't consume any frame. The FST can be lazy, for
+/// instance a `ComposeFst` of the decoding graph, only the states reached by the search are
+/// computed.
+///
+/// After each frame, the tokens worse than the best one times `config.beam` are pruned and at
+/// most `config.max_active` tokens are kept. The best path is the best token reaching a final
+/// state after the last frame. If `config.generate_lattice` is set, the lattice of all the
+/// transitions between the surviving tokens is returned as well. The semiring must have the path
+/// property.
+pub fn decode<W, F, C>(
+    fst: &F,
+    num_frames: usize,
+    acoustic_cost: C,
+    config: DecoderConfig<W>,
+) -> Result<DecodeResult<W>, Error>
+where
+    W: Semiring,
+    F: Fst<W>,
+    C: Fn(usize, Label) -> W,
+{
+    check_semiring_properties::<W>("decode", SemiringProperties::PATH)?;
+    let mut result = DecodeResult {
+        best_path: VectorFst::new(),
+        best_weight: W::zero(),
+        lattice: None,
+    };
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(result),
+    };
+    let mut decoder = Decoder {
+        fst,
+        config,
+        tokens: vec![Token {
+            state: start,
+            weight: W::one(),
+            best_link: None,
+        }],
+        links: vec![],
+        eps_links: HashMap::new(),
+    };
+
+    let mut frame_tokens = HashMap::new();
+    frame_tokens.insert(start, 0);
+    decoder.process_epsilons(&mut frame_tokens)?;
+    for frame in 0..num_frames {
+        let active = decoder.prune(&frame_tokens)?;
+        let mut next_tokens = HashMap::new();
+        for token in active {
+            let state = decoder.tokens[token].state;
+            for tr in fst.get_trs(state)?.trs() {
+                if tr.ilabel == EPS_LABEL {
+                    continue;
+                }
+                let weight = tr.weight.times(acoustic_cost(frame, tr.ilabel))?;
+                decoder.relax(&mut next_tokens, token, tr, weight, None)?;
+            }
+        }
+        decoder.process_epsilons(&mut next_tokens)?;
+        frame_tokens = next_tokens;
+    }
+
+    let mut final_tokens = vec![];
+    let mut best: Option<(usize, W)> = None;
+    for &token in frame_tokens.values() {
+        if let Some(final_weight) = fst.final_weight(decoder.tokens[token].state)? {
+            let weight = decoder.tokens[token].weight.times(&final_weight)?;
+            let is_better = match &best {
+                Some((_, best_weight)) => natural_less(&weight, best_weight)?,
+                None => true,
+            };
+            if is_better {
+                best = Some((token, weight));
+            }
+            final_tokens.push((token, final_weight));
+        }
+    }
+
+    if let Some((best_token, best_weight)) = best {
+        let mut links = vec![];
+        let mut token = best_token;
+        while let Some(link) = decoder.tokens[token].best_link {
+            links.push(link);
+            token = decoder.links[link].prev;
+        }
+        let ofst = &mut result.best_path;
+        let mut state = ofst.add_state();
+        ofst.set_start(state)?;
+        for link in links.into_iter().rev() {
+            let link = &decoder.links[link];
+            let nextstate = ofst.add_state();
+            ofst.add_tr(
+                state,
+                Tr::new(link.ilabel, link.olabel, link.weight.clone(), nextstate),
+            )?;
+            state = nextstate;
+        }
+        let final_weight = fst.final_weight(decoder.tokens[best_token].state)?.unwrap();
+        ofst.set_final(state, final_weight)?;
+        result.best_weight = best_weight;
+    }
+    result.best_path.set_symts_from_fst(fst);
+
+    if decoder.config.generate_lattice {
+        let mut lattice = VectorFst::new();
+        lattice.add_states(decoder.tokens.len());
+        lattice.set_start(0)?;
+        for link in decoder.links.iter() {
+            lattice.add_tr(
+                link.prev,
+                Tr::new(link.ilabel, link.olabel, link.weight.clone(), link.next),
+            )?;
+        }
+        for (token, final_weight) in final_tokens {
+            lattice.set_final(token, final_weight)?;
+        }
+        connect(&mut lattice)?;
+        lattice.set_symts_from_fst(fst);
+        result.lattice = Some(lattice);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::compose::ComposeFst;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::TropicalWeight;
+    use std::sync::Arc;
+
+    // Words "ab" (1 : 10 20) and "ba" (2 : 20 10) with an optional silence (30) between them.
+    fn graph() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s0, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(10, 1, TropicalWeight::new(1.0), s1))?;
+        fst.add_tr(s1, Tr::new(20, EPS_LABEL, TropicalWeight::one(), s3))?;
+        fst.add_tr(s0, Tr::new(20, 2, TropicalWeight::new(1.0), s2))?;
+        fst.add_tr(s2, Tr::new(10, EPS_LABEL, TropicalWeight::one(), s3))?;
+        fst.add_tr(s3, Tr::new(EPS_LABEL, EPS_LABEL, TropicalWeight::one(), s0))?;
+        fst.add_tr(s3, Tr::new(30, EPS_LABEL, TropicalWeight::new(0.5), s0))?;
+        Ok(fst)
+    }
+
+    // Frames of "ab ba" : the acoustic cost is 0 for the expected unit and 5 otherwise.
+    fn acoustic_cost(frame: usize, ilabel: Label) -> TropicalWeight {
+        let units = [10, 20, 20, 10];
+        if units.get(frame) == Some(&ilabel) {
+            TropicalWeight::one()
+        } else {
+            TropicalWeight::new(5.0)
+        }
+    }
+
+    #[test]
+    fn test_decode_best_path() -> Result<()> {
+        let fst = graph()?;
+        let config = DecoderConfig::default().with_beam(TropicalWeight::new(8.0));
+        let res = decode(&fst, 4, acoustic_cost, config)?;
+        let paths: Vec<_> = res.best_path.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![10, 20, 20, 10]);
+        assert_eq!(paths[0].olabels, vec![1, 2]);
+        assert_eq!(res.best_weight, TropicalWeight::new(2.0));
+        assert_eq!(paths[0].weight, res.best_weight);
+        assert!(res.lattice.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_lattice_and_max_active() -> Result<()> {
+        let fst = graph()?;
+        let config = DecoderConfig::default()
+            .with_beam(TropicalWeight::new(20.0))
+            .with_generate_lattice(true);
+        let res = decode(&fst, 4, acoustic_cost, config)?;
+        let lattice = res.lattice.unwrap();
+        let mut olabels: Vec<_> = lattice.paths_iter().map(|p| p.olabels).collect();
+        olabels.sort();
+        olabels.dedup();
+        // Each pair of words is a hypothesis of the lattice.
+        assert_eq!(
+            olabels,
+            vec![vec![1, 1], vec![1, 2], vec![2, 1], vec![2, 2]]
+        );
+
+        // Two active tokens are enough to find the best path here.
+        let config = DecoderConfig::default().with_max_active(Some(2));
+        let res = decode(&fst, 4, acoustic_cost, config)?;
+        let paths: Vec<_> = res.best_path.paths_iter().collect();
+        assert_eq!(paths[0].olabels, vec![1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_lazy_compose() -> Result<()> {
+        // Rewrite the words with a lazy composition.
+        let fst = graph()?;
+        let mut rewrite = VectorFst::<TropicalWeight>::new();
+        let s0 = rewrite.add_state();
+        rewrite.set_start(s0)?;
+        rewrite.set_final(s0, TropicalWeight::one())?;
+        rewrite.add_tr(s0, Tr::new(1, 5, TropicalWeight::one(), s0))?;
+        rewrite.add_tr(s0, Tr::new(2, 6, TropicalWeight::one(), s0))?;
+        let composed = ComposeFst::<_, VectorFst<_>, VectorFst<_>, _, _, _, _, _>::new_auto(
+            Arc::new(fst),
+            Arc::new(rewrite),
+        )?;
+        let res = decode(&composed, 4, acoustic_cost, DecoderConfig::default())?;
+        let paths: Vec<_> = res.best_path.paths_iter().collect();
+        assert_eq!(paths[0].olabels, vec![5, 6]);
+        assert_eq!(res.best_weight, TropicalWeight::new(2.0));
+        Ok(())
+    }
+}
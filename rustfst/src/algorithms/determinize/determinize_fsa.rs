@@ -4,7 +4,7 @@ use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
 use crate::semirings::{WeaklyDivisibleSemiring, WeightQuantize};
-use crate::{Semiring, SymbolTable, TrsVec};
+use crate::{Error, Semiring, SymbolTable, TrsVec};
 use anyhow::Result;
 use std::borrow::Borrow;
 use std::fmt::Debug;
@@ -37,7 +37,7 @@ where
         self.0.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.0.final_weight(state_id)
     }
 
@@ -45,7 +45,7 @@ where
         self.0.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.0.num_trs(s)
     }
 
@@ -53,7 +53,7 @@ where
         self.0.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.0.get_trs(state_id)
     }
 
@@ -65,11 +65,11 @@ where
         unimplemented!()
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_output_epsilons(state)
     }
 }
@@ -162,7 +162,7 @@ where
     }
 
     /// Turns the Lazy FST into a static one.
-    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
         self.0.compute()
     }
 
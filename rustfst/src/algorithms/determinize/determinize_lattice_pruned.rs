@@ -0,0 +1,350 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::algorithms::determinize::{DeterminizeFsa, GallicCommonDivisor};
+use crate::algorithms::factor_weight::factor_iterators::GallicFactorMin;
+use crate::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
+use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
+use crate::algorithms::{connect, fst_convert_from_ref, shortest_distance, weight_convert};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, Fst, MutableFst};
+use crate::semirings::{CostWeight, GallicWeightMin, LatticeWeight, Semiring};
+use crate::{Error, StateId, Tr, Trs, EPS_LABEL, KDELTA};
+
+/// Configuration of `determinize_lattice_pruned`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeterminizeLatticePrunedConfig {
+    /// Paths whose total cost is more than `beam` above the cost of the best path are pruned.
+    pub beam: f32,
+    /// Maximum number of states of the determinized lattice.
+    pub max_states: Option<usize>,
+    /// Maximum number of transitions of the determinized lattice.
+    pub max_arcs: Option<usize>,
+    /// Factor applied to the beam each time the determinization exceeds one of the limits.
+    pub beam_ratio: f32,
+    /// Maximum number of times the determinization is restarted with a tighter beam.
+    pub max_retries: usize,
+    pub delta: f32,
+}
+
+impl Default for DeterminizeLatticePrunedConfig {
+    fn default() -> Self {
+        Self {
+            beam: 10.0,
+            max_states: None,
+            max_arcs: None,
+            beam_ratio: 0.9,
+            max_retries: 20,
+            delta: KDELTA,
+        }
+    }
+}
+
+impl DeterminizeLatticePrunedConfig {
+    pub fn new(beam: f32) -> Self {
+        Self {
+            beam,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_beam(self, beam: f32) -> Self {
+        Self { beam, ..self }
+    }
+
+    pub fn with_max_states(self, max_states: Option<usize>) -> Self {
+        Self { max_states, ..self }
+    }
+
+    pub fn with_max_arcs(self, max_arcs: Option<usize>) -> Self {
+        Self { max_arcs, ..self }
+    }
+
+    pub fn with_beam_ratio(self, beam_ratio: f32) -> Self {
+        Self { beam_ratio, ..self }
+    }
+
+    pub fn with_max_retries(self, max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+/// Output of `determinize_lattice_pruned`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeterminizeLatticePrunedResult<F> {
+    /// The determinized lattice.
+    pub fst: F,
+    /// Beam actually used, lower than the requested one if the limits were exceeded.
+    pub effective_beam: f32,
+    /// `false` if the limits were still exceeded with the tightest beam. In that case `fst`
+    /// only contains the part of the lattice determinized before the limit was reached.
+    pub completed: bool,
+}
+
+/// Removes the states and transitions of a lattice that are not on a path whose cost is within
+/// `beam` of the cost of the best path.
+pub fn prune_lattice<F>(fst: &mut F, beam: f32) -> Result<(), Error>
+where
+    F: MutableFst<LatticeWeight> + ExpandedFst<LatticeWeight>,
+{
+    let start = match fst.start() {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+    let alpha = shortest_distance(fst, false)?;
+    let beta = shortest_distance(fst, true)?;
+    let cost = |d: &[LatticeWeight], s: StateId| d.get(s).map_or(f64::INFINITY, |w| w.to_cost());
+    let cutoff = cost(&beta, start) + f64::from(beam);
+    if !cutoff.is_finite() {
+        // Nothing is reachable.
+        fst.del_all_states();
+        return Ok(());
+    }
+
+    for state in 0..fst.num_states() {
+        let a = cost(&alpha, state);
+        let trs: Vec<Tr<LatticeWeight>> = fst
+            .pop_trs(state)?
+            .into_iter()
+            .filter(|tr| a + tr.weight.to_cost() + cost(&beta, tr.nextstate) <= cutoff)
+            .collect();
+        for tr in trs {
+            fst.add_tr(state, tr)?;
+        }
+        if let Some(final_weight) = fst.final_weight(state)? {
+            if a + final_weight.to_cost() > cutoff {
+                fst.delete_final_weight(state)?;
+            }
+        }
+    }
+    connect(fst)
+}
+
+fn determinize_with_limits(
+    fst: &VectorFst<GallicWeightMin<LatticeWeight>>,
+    config: &DeterminizeLatticePrunedConfig,
+) -> Result<(VectorFst<GallicWeightMin<LatticeWeight>>, bool)> {
+    let det_fsa = DeterminizeFsa::<_, VectorFst<_>, GallicCommonDivisor, _, Vec<_>>::new(
+        fst,
+        None,
+        config.delta,
+    )?;
+    let mut ofst = VectorFst::new();
+    let start = match det_fsa.start() {
+        Some(s) => s,
+        None => return Ok((ofst, true)),
+    };
+
+    let mut state_map = HashMap::new();
+    let mut queue = VecDeque::new();
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    state_map.insert(start, ostart);
+    queue.push_back(start);
+    let mut num_trs = 0;
+
+    while let Some(state) = queue.pop_front() {
+        let ostate = state_map[&state];
+        if let Some(final_weight) = det_fsa.final_weight(state)? {
+            ofst.set_final(ostate, final_weight)?;
+        }
+        for tr in det_fsa.get_trs(state)?.trs() {
+            let onext = match state_map.entry(tr.nextstate) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    queue.push_back(tr.nextstate);
+                    *e.insert(ofst.add_state())
+                }
+            };
+            ofst.add_tr(
+                ostate,
+                Tr::new(tr.ilabel, tr.olabel, tr.weight.clone(), onext),
+            )?;
+            num_trs += 1;
+        }
+        let too_many_states = config
+            .max_states
+            .map_or(false, |max| ofst.num_states() > max);
+        let too_many_trs = config.max_arcs.map_or(false, |max| num_trs > max);
+        if too_many_states || too_many_trs {
+            return Ok((ofst, false));
+        }
+    }
+    Ok((ofst, true))
+}
+
+/// Determinizes a lattice on its input labels while pruning the paths whose cost is more than
+/// `beam` above the cost of the best path, in the spirit of Kaldi's `determinize-lattice-pruned`.
+///
+/// The lattice is first pruned, then encoded as an acceptor over
+/// `GallicWeightMin<LatticeWeight>` (the output labels being moved to the string part of the
+/// weights) and determinized lazily. If the determinized lattice grows beyond `max_states` or
+/// `max_arcs`, the determinization restarts with the beam multiplied by `beam_ratio`, at most
+/// `max_retries` times. When every attempt fails, the partial result is returned and flagged as
+/// not completed. The weights of the ambiguous output strings are combined by keeping the best
+/// one.
+///
+/// To determinize on the words of a lattice whose input labels are transition ids, invert it
+/// first.
+pub fn determinize_lattice_pruned<F1, F2>(
+    ifst: &F1,
+    config: DeterminizeLatticePrunedConfig,
+) -> Result<DeterminizeLatticePrunedResult<F2>, Error>
+where
+    F1: ExpandedFst<LatticeWeight>,
+    F2: MutableFst<LatticeWeight> + AllocableFst<LatticeWeight>,
+{
+    if config.beam < 0.0 || !(config.beam_ratio > 0.0 && config.beam_ratio < 1.0) {
+        return Err(Error::Other(format_err!(
+            "determinize_lattice_pruned : invalid beam {} or beam ratio {}",
+            config.beam,
+            config.beam_ratio
+        )));
+    }
+    let mut beam = config.beam;
+    let mut retries = 0;
+    let (det_fsa, completed) = loop {
+        let mut pruned: VectorFst<LatticeWeight> = fst_convert_from_ref(ifst);
+        prune_lattice(&mut pruned, beam)?;
+        let fsa: VectorFst<GallicWeightMin<LatticeWeight>> =
+            weight_convert(&pruned, &mut ToGallicConverter {})?;
+        let (det_fsa, completed) = determinize_with_limits(&fsa, &config)?;
+        if completed || retries >= config.max_retries {
+            break (det_fsa, completed);
+        }
+        beam *= config.beam_ratio;
+        retries += 1;
+    };
+
+    let mut det_fsa = det_fsa;
+    if !completed {
+        connect(&mut det_fsa)?;
+    }
+    let factor_opts = FactorWeightOptions {
+        delta: KDELTA,
+        mode: FactorWeightType::FACTOR_FINAL_WEIGHTS,
+        final_ilabel: EPS_LABEL,
+        final_olabel: EPS_LABEL,
+        increment_final_ilabel: false,
+        increment_final_olabel: false,
+    };
+    let factored: VectorFst<GallicWeightMin<LatticeWeight>> = factor_weight::<
+        _,
+        VectorFst<GallicWeightMin<LatticeWeight>>,
+        _,
+        _,
+        GallicFactorMin<LatticeWeight>,
+    >(&det_fsa, factor_opts)?;
+    let mut from_gallic = FromGallicConverter {
+        superfinal_label: EPS_LABEL,
+    };
+    let mut ofst: VectorFst<LatticeWeight> = weight_convert(&factored, &mut from_gallic)?;
+    prune_lattice(&mut ofst, beam)?;
+
+    let mut fst: F2 = fst_convert_from_ref(&ofst);
+    fst.set_symts_from_fst(ifst);
+    Ok(DeterminizeLatticePrunedResult {
+        fst,
+        effective_beam: beam,
+        completed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    use crate::algorithms::determinize::determinize_with_config;
+    use crate::algorithms::determinize::{DeterminizeConfig, DeterminizeType};
+    use crate::fst_properties::FstProperties;
+
+    fn lw(graph: f32, acoustic: f32) -> LatticeWeight {
+        LatticeWeight::new((graph, acoustic))
+    }
+
+    // Two words sequences "1 2" and "1 3" with two alignments each.
+    fn build_lattice() -> Result<VectorFst<LatticeWeight>> {
+        let mut fst = VectorFst::new();
+        let s: Vec<_> = (0..6).map(|_| fst.add_state()).collect();
+        fst.set_start(s[0])?;
+        fst.add_tr(s[0], Tr::new(1, 10, lw(1.0, 1.0), s[1]))?;
+        fst.add_tr(s[0], Tr::new(1, 10, lw(1.0, 3.0), s[2]))?;
+        fst.add_tr(s[1], Tr::new(2, 20, lw(0.5, 0.5), s[3]))?;
+        fst.add_tr(s[2], Tr::new(2, 20, lw(0.5, 0.0), s[3]))?;
+        fst.add_tr(s[1], Tr::new(3, 30, lw(2.0, 6.0), s[4]))?;
+        fst.add_tr(s[2], Tr::new(3, 30, lw(4.0, 5.0), s[5]))?;
+        fst.set_final(s[3], LatticeWeight::one())?;
+        fst.set_final(s[4], LatticeWeight::one())?;
+        fst.set_final(s[5], LatticeWeight::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_determinize_lattice_pruned_wide_beam() -> Result<()> {
+        let fst = build_lattice()?;
+        let res: DeterminizeLatticePrunedResult<VectorFst<LatticeWeight>> =
+            determinize_lattice_pruned(&fst, DeterminizeLatticePrunedConfig::new(100.0))?;
+        assert!(res.completed);
+        assert_eq!(res.effective_beam, 100.0);
+
+        let config =
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeDisambiguate);
+        let ref_fst: VectorFst<LatticeWeight> = determinize_with_config(&fst, config)?;
+        assert_eq!(res.fst, ref_fst);
+        assert!(res
+            .fst
+            .properties_check(FstProperties::I_DETERMINISTIC)?
+            .contains(FstProperties::I_DETERMINISTIC));
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_lattice_pruned_beam() -> Result<()> {
+        let fst = build_lattice()?;
+        // Best path costs 3, the "1 3" sequence costs 10.
+        let res: DeterminizeLatticePrunedResult<VectorFst<LatticeWeight>> =
+            determinize_lattice_pruned(&fst, DeterminizeLatticePrunedConfig::new(5.0))?;
+        assert!(res.completed);
+
+        let mut ref_fst = VectorFst::<LatticeWeight>::new();
+        let s0 = ref_fst.add_state();
+        let s1 = ref_fst.add_state();
+        let s2 = ref_fst.add_state();
+        ref_fst.set_start(s0)?;
+        ref_fst.add_tr(s0, Tr::new(1, 10, lw(1.0, 1.0), s1))?;
+        ref_fst.add_tr(s1, Tr::new(2, 20, lw(0.5, 0.5), s2))?;
+        ref_fst.set_final(s2, LatticeWeight::one())?;
+        assert_eq!(res.fst, ref_fst);
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_lattice_pruned_max_states() -> Result<()> {
+        let fst = build_lattice()?;
+        let config = DeterminizeLatticePrunedConfig::new(100.0)
+            .with_max_states(Some(3))
+            .with_beam_ratio(0.5)
+            .with_max_retries(10);
+        let res: DeterminizeLatticePrunedResult<VectorFst<LatticeWeight>> =
+            determinize_lattice_pruned(&fst, config)?;
+        assert!(res.completed);
+        assert!(res.effective_beam < 7.0);
+        assert_eq!(res.fst.num_states(), 3);
+
+        let config = config.with_max_states(Some(1)).with_max_retries(2);
+        let res: DeterminizeLatticePrunedResult<VectorFst<LatticeWeight>> =
+            determinize_lattice_pruned(&fst, config)?;
+        assert!(!res.completed);
+        assert_eq!(res.effective_beam, 25.0);
+        Ok(())
+    }
+}
@@ -1,7 +1,5 @@
 use std::borrow::Borrow;
 
-use anyhow::Result;
-
 use crate::algorithms::determinize::divisors::CommonDivisor;
 use crate::algorithms::determinize::DeterminizeFsa;
 use crate::algorithms::determinize::{DefaultCommonDivisor, DeterminizeType, GallicCommonDivisor};
@@ -9,6 +7,8 @@ use crate::algorithms::factor_weight::factor_iterators::{
     GallicFactor, GallicFactorMin, GallicFactorRestrict,
 };
 use crate::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
+use crate::algorithms::fst_convert_from_ref;
+use crate::algorithms::preconditions::check_semiring_properties;
 use crate::algorithms::weight_convert;
 use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
 use crate::fst_impls::VectorFst;
@@ -19,40 +19,40 @@ use crate::semirings::SemiringProperties;
 use crate::semirings::{
     GallicWeight, GallicWeightMin, GallicWeightRestrict, WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::{EPS_LABEL, KDELTA};
+use crate::{Error, EPS_LABEL, KDELTA};
 
 pub fn determinize_with_distance<W, F1, F2>(
     ifst: &F1,
     in_dist: &[W],
     delta: f32,
-) -> Result<(F2, Vec<W>)>
+) -> Result<(F2, Vec<W>), Error>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: ExpandedFst<W>,
     F2: MutableFst<W> + AllocableFst<W>,
 {
-    if !W::properties().contains(SemiringProperties::LEFT_SEMIRING) {
-        bail!("determinize_fsa : weight must be left distributive")
-    }
+    check_semiring_properties::<W>("determinize_fsa", SemiringProperties::LEFT_SEMIRING)?;
     let fst = DeterminizeFsa::<_, F1, DefaultCommonDivisor, _, _>::new(ifst, Some(in_dist), delta)?;
-    fst.compute_with_distance()
+    Ok(fst.compute_with_distance()?)
 }
 
-pub fn determinize_fsa<W, F1, F2, CD>(fst_in: &F1, delta: f32) -> Result<F2>
+pub fn determinize_fsa<W, F1, F2, CD>(fst_in: &F1, delta: f32) -> Result<F2, Error>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: Fst<W>,
     F2: MutableFst<W> + AllocableFst<W>,
     CD: CommonDivisor<W>,
 {
-    if !W::properties().contains(SemiringProperties::LEFT_SEMIRING) {
-        bail!("determinize_fsa : weight must be left distributive")
-    }
+    check_semiring_properties::<W>("determinize_fsa", SemiringProperties::LEFT_SEMIRING)?;
     let det_fsa: DeterminizeFsa<W, F1, CD, _, Vec<W>> = DeterminizeFsa::new(fst_in, None, delta)?;
     det_fsa.compute()
 }
 
-pub fn determinize_fst<W, F1, F2>(fst_in: &F1, det_type: DeterminizeType, delta: f32) -> Result<F2>
+pub fn determinize_fst<W, F1, F2>(
+    fst_in: &F1,
+    det_type: DeterminizeType,
+    delta: f32,
+) -> Result<F2, Error>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
     F1: ExpandedFst<W>,
@@ -74,9 +74,7 @@ where
 
     match det_type {
         DeterminizeType::DeterminizeDisambiguate => {
-            if !W::properties().contains(SemiringProperties::PATH) {
-                bail!("determinize : weight needs to have the path property to disambiguate output")
-            }
+            check_semiring_properties::<W>("determinize", SemiringProperties::PATH)?;
             let fsa: VectorFst<GallicWeightMin<W>> =
                 weight_convert(fst_in.borrow(), &mut to_gallic)?;
             let determinized_fsa: VectorFst<GallicWeightMin<W>> =
@@ -146,7 +144,7 @@ impl Default for DeterminizeConfig {
     }
 }
 
-pub fn determinize<W, F1, F2>(fst_in: &F1) -> Result<F2>
+pub fn determinize<W, F1, F2>(fst_in: &F1) -> Result<F2, Error>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: ExpandedFst<W>,
@@ -169,7 +167,10 @@ where
 ///
 /// ![determinize_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/determinize_out.svg?sanitize=true)
 ///
-pub fn determinize_with_config<W, F1, F2>(fst_in: &F1, config: DeterminizeConfig) -> Result<F2>
+pub fn determinize_with_config<W, F1, F2>(
+    fst_in: &F1,
+    config: DeterminizeConfig,
+) -> Result<F2, Error>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: ExpandedFst<W>,
@@ -177,7 +178,14 @@ where
 {
     let delta = config.delta;
     let det_type = config.det_type;
+    check_semiring_properties::<W>("determinize", SemiringProperties::LEFT_SEMIRING)?;
     let iprops = fst_in.borrow().properties();
+    // The determinization of an accessible deterministic acceptor is a copy of it.
+    if iprops.contains(
+        FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC | FstProperties::ACCESSIBLE,
+    ) {
+        return Ok(fst_convert_from_ref(fst_in));
+    }
     let mut fst_res: F2 = if iprops.contains(FstProperties::ACCEPTOR) {
         determinize_fsa::<_, F1, _, DefaultCommonDivisor>(fst_in, delta)?
     } else {
@@ -201,8 +209,11 @@ where
 
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
+
+    use crate::algorithms::{isomorphic, PreconditionError};
     use crate::fst_impls::VectorFst;
-    use crate::semirings::TropicalWeight;
+    use crate::semirings::{LogWeight, TropicalWeight};
     use crate::tr::Tr;
     use crate::Semiring;
 
@@ -269,4 +280,56 @@ mod tests {
         assert_eq!(determinized_fst, ref_fst);
         Ok(())
     }
+
+    #[test]
+    fn test_determinize_deterministic_acceptor() -> Result<()> {
+        let mut input_fst = VectorFst::<TropicalWeight>::new();
+        let s0 = input_fst.add_state();
+        let s1 = input_fst.add_state();
+        let s2 = input_fst.add_state();
+
+        input_fst.set_start(s0)?;
+        input_fst.set_final(s2, TropicalWeight::new(1.5))?;
+
+        input_fst.add_tr(s0, Tr::new(1, 1, 2.0, s1))?;
+        input_fst.add_tr(s0, Tr::new(2, 2, 3.0, s2))?;
+        input_fst.add_tr(s1, Tr::new(3, 3, 4.0, s2))?;
+        input_fst.compute_and_update_properties_all()?;
+
+        let determinized_fst: VectorFst<TropicalWeight> = determinize(&input_fst)?;
+        assert_eq!(determinized_fst, input_fst);
+
+        // Same result when the properties are not known.
+        let mut input_fst_unknown_props = input_fst.clone();
+        input_fst_unknown_props.set_properties(FstProperties::empty());
+        let determinized_fst: VectorFst<TropicalWeight> = determinize(&input_fst_unknown_props)?;
+        assert!(isomorphic(&determinized_fst, &input_fst)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_disambiguate_without_path_property() -> Result<()> {
+        let mut input_fst = VectorFst::<LogWeight>::new();
+        let s0 = input_fst.add_state();
+        let s1 = input_fst.add_state();
+
+        input_fst.set_start(s0)?;
+        input_fst.set_final(s1, LogWeight::one())?;
+        input_fst.add_tr(s0, Tr::new(1, 2, 1.0, s1))?;
+
+        let config =
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeDisambiguate);
+        let err = determinize_with_config::<_, _, VectorFst<_>>(&input_fst, config).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingSemiringProperties {
+                    algorithm: "determinize",
+                    missing: SemiringProperties::PATH
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
 }
@@ -1,5 +1,9 @@
 pub(self) use determinize_fsa::DeterminizeFsa;
 pub(self) use determinize_fsa_op::DeterminizeFsaOp;
+pub use determinize_lattice_pruned::{
+    determinize_lattice_pruned, prune_lattice, DeterminizeLatticePrunedConfig,
+    DeterminizeLatticePrunedResult,
+};
 pub use determinize_static::{
     determinize, determinize_with_config, determinize_with_distance, DeterminizeConfig,
 };
@@ -9,6 +13,7 @@ pub(self) use state_table::DeterminizeStateTable;
 
 mod determinize_fsa;
 mod determinize_fsa_op;
+mod determinize_lattice_pruned;
 mod determinize_static;
 mod divisors;
 mod element;
@@ -0,0 +1,174 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::algorithms::encode::decode_fst_op::DecodeFstOp;
+use crate::algorithms::encode::EncodeTable;
+use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::Semiring;
+use crate::{Error, SymbolTable, TrsVec};
+
+/// Delayed version of `decode`. Unlike `decode`, the final transitions to the superfinal state
+/// created by the encoding of the weights are not removed.
+pub struct DecodeFst<W: Semiring, F: Fst<W>, B: Borrow<F>>(
+    LazyFst<W, DecodeFstOp<W, F, B>, SimpleHashMapCache<W>>,
+);
+
+impl<W, F, B> CoreFst<W> for DecodeFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B> StateIterator<'a> for DecodeFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type Iter =
+        <LazyFst<W, DecodeFstOp<W, F, B>, SimpleHashMapCache<W>> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B> FstIterator<'a, W> for DecodeFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type FstIter =
+        <LazyFst<W, DecodeFstOp<W, F, B>, SimpleHashMapCache<W>> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B> Fst<W> for DecodeFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B> Debug for DecodeFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B> DecodeFst<W, F, B>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    pub fn new(fst: B, encode_table: Arc<EncodeTable<W>>) -> Self {
+        let isymt = encode_table
+            .input_symbols()
+            .or_else(|| fst.borrow().input_symbols().cloned());
+        let osymt = encode_table
+            .output_symbols()
+            .or_else(|| fst.borrow().output_symbols().cloned());
+        let fst_op = DecodeFstOp::new(fst, encode_table);
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        DecodeFst(lazy_fst)
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
+        self.0.compute()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_decode_fst_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<DecodeFst<TropicalWeight, VectorFst<_>, VectorFst<_>>>();
+    }
+}
@@ -0,0 +1,70 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::encode::decode_static::DecodeMapper;
+use crate::algorithms::encode::EncodeTable;
+use crate::algorithms::lazy::FstOp;
+use crate::algorithms::TrMapper;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{StateId, Trs, TrsVec};
+
+pub struct DecodeFstOp<W: Semiring, F: Fst<W>, B: Borrow<F>> {
+    fst: B,
+    encode_table: Arc<EncodeTable<W>>,
+    properties: FstProperties,
+    f: PhantomData<F>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> std::fmt::Debug for DecodeFstOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DecodeFstOp {{ encode_table : {:?}, fst : {:?} }}",
+            self.encode_table,
+            self.fst.borrow()
+        )
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> DecodeFstOp<W, F, B> {
+    pub fn new(fst: B, encode_table: Arc<EncodeTable<W>>) -> Self {
+        let properties =
+            DecodeMapper::new(encode_table.as_ref()).properties(fst.borrow().properties());
+        Self {
+            fst,
+            encode_table,
+            properties,
+            f: PhantomData,
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FstOp<W> for DecodeFstOp<W, F, B> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start())
+    }
+
+    fn compute_trs(&self, state: usize) -> Result<TrsVec<W>> {
+        let mapper = DecodeMapper::new(self.encode_table.as_ref());
+        let mut trs = vec![];
+        for tr in self.fst.borrow().get_trs(state)?.trs() {
+            let mut tr = tr.clone();
+            mapper.tr_map(&mut tr)?;
+            trs.push(tr);
+        }
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, state: StateId) -> Result<Option<W>> {
+        Ok(self.fst.borrow().final_weight(state)?)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
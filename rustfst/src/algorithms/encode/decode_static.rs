@@ -1,37 +1,49 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 
-use crate::algorithms::{encode::EncodeTable, rm_final_epsilon};
+use crate::algorithms::encode::EncodeTable;
+use crate::algorithms::rm_final_epsilon::rm_final_epsilon_with_state_map;
 use crate::algorithms::{FinalTr, MapFinalAction, TrMapper};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
-use crate::{Semiring, Tr};
+use crate::{Error, Semiring, StateId, Tr};
 
-struct DecodeMapper<W: Semiring> {
-    encode_table: EncodeTable<W>,
+pub(crate) struct DecodeMapper<'a, W: Semiring> {
+    encode_table: &'a EncodeTable<W>,
 }
 
-impl<W: Semiring> DecodeMapper<W> {
-    pub fn new(encode_table: EncodeTable<W>) -> Self {
+impl<'a, W: Semiring> DecodeMapper<'a, W> {
+    pub fn new(encode_table: &'a EncodeTable<W>) -> Self {
         DecodeMapper { encode_table }
     }
 
     pub fn encode_weights(&self) -> bool {
-        self.encode_table.0.borrow().encode_type.encode_weights()
+        self.encode_table
+            .0
+            .lock()
+            .unwrap()
+            .encode_type
+            .encode_weights()
     }
 
     pub fn encode_labels(&self) -> bool {
-        self.encode_table.0.borrow().encode_type.encode_labels()
+        self.encode_table
+            .0
+            .lock()
+            .unwrap()
+            .encode_type
+            .encode_labels()
     }
 }
 
-impl<W: Semiring> TrMapper<W> for DecodeMapper<W> {
+impl<'a, W: Semiring> TrMapper<W> for DecodeMapper<'a, W> {
     fn tr_map(&self, tr: &mut Tr<W>) -> Result<()> {
         let tuple = self
             .encode_table
             .0
-            .borrow_mut()
-            .decode(tr.ilabel)
+            .lock()
             .unwrap()
+            .decode(tr.ilabel)
+            .ok_or_else(|| format_err!("Label {} is missing from the EncodeTable", tr.ilabel))?
             .clone();
         tr.ilabel = tuple.ilabel;
         if self.encode_labels() {
@@ -69,14 +81,43 @@ impl<W: Semiring> TrMapper<W> for DecodeMapper<W> {
 
 /// The `decode` operation takes as input an encoded FST and the corresponding `EncodeTable` object
 /// and reverts the encoding.
-pub fn decode<W, F>(fst: &mut F, encode_table: EncodeTable<W>) -> Result<()>
+pub fn decode<W, F>(fst: &mut F, encode_table: EncodeTable<W>) -> Result<(), Error>
 where
     W: Semiring,
     F: MutableFst<W>,
 {
-    let mut decode_mapper = DecodeMapper::new(encode_table);
-    fst.tr_map(&mut decode_mapper)
-        .with_context(|| format_err!("Error calling TrMap with EncodeMapper."))?;
-    rm_final_epsilon(fst)?;
+    decode_with_table(fst, &encode_table)
+}
+
+/// Same as `decode` but borrows the `EncodeTable` so that it can be used to decode other FSTs.
+/// The symbol tables recorded in the `EncodeTable` are restored.
+pub fn decode_with_table<W, F>(fst: &mut F, encode_table: &EncodeTable<W>) -> Result<(), Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    decode_with_state_map(fst, encode_table)?;
     Ok(())
 }
+
+/// Same as `decode_with_table` but also returns, for each state of the encoded FST, its id in
+/// the decoded FST or `None` if it has been removed with the final epsilon transitions.
+pub(crate) fn decode_with_state_map<W, F>(
+    fst: &mut F,
+    encode_table: &EncodeTable<W>,
+) -> Result<Vec<Option<StateId>>, Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let mut decode_mapper = DecodeMapper::new(encode_table);
+    fst.tr_map(&mut decode_mapper)?;
+    let state_map = rm_final_epsilon_with_state_map(fst)?;
+    if let Some(isymt) = encode_table.input_symbols() {
+        fst.set_input_symbols(isymt);
+    }
+    if let Some(osymt) = encode_table.output_symbols() {
+        fst.set_output_symbols(osymt);
+    }
+    Ok(state_map)
+}
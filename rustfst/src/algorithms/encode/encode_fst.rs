@@ -0,0 +1,226 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::algorithms::encode::encode_fst_op::EncodeFstOp;
+use crate::algorithms::encode::{EncodeTable, EncodeType};
+use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{
+    AllocableFst, CoreFst, ExpandedFst, Fst, FstIterator, MutableFst, StateIterator,
+};
+use crate::semirings::Semiring;
+use crate::{Error, SymbolTable, TrsVec};
+
+/// Delayed version of `encode`. The trs are encoded when their state is expanded, so the
+/// `EncodeTable` only contains the tuples of the states visited so far.
+pub struct EncodeFst<W: Semiring, F: ExpandedFst<W>, B: Borrow<F>>(
+    LazyFst<W, EncodeFstOp<W, F, B>, SimpleHashMapCache<W>>,
+);
+
+impl<W, F, B> CoreFst<W> for EncodeFst<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F>,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B> StateIterator<'a> for EncodeFst<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type Iter =
+        <LazyFst<W, EncodeFstOp<W, F, B>, SimpleHashMapCache<W>> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B> FstIterator<'a, W> for EncodeFst<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type FstIter =
+        <LazyFst<W, EncodeFstOp<W, F, B>, SimpleHashMapCache<W>> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B> Fst<W> for EncodeFst<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + 'static,
+    B: Borrow<F> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B> Debug for EncodeFst<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B> EncodeFst<W, F, B>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    B: Borrow<F>,
+{
+    pub fn new(fst: B, encode_type: EncodeType) -> Self {
+        Self::new_with_table(fst, Arc::new(EncodeTable::new(encode_type)))
+    }
+
+    /// Encodes the FST with an existing table, which is shared with the caller. The tuples
+    /// are added to the table as the states are expanded.
+    pub fn new_with_table(fst: B, encode_table: Arc<EncodeTable<W>>) -> Self {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = EncodeFstOp::new(fst, encode_table);
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        EncodeFst(lazy_fst)
+    }
+
+    /// Table of the encoding. It only contains the tuples of the states expanded so far.
+    pub fn encode_table(&self) -> Arc<EncodeTable<W>> {
+        Arc::clone(self.0.op.encode_table())
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
+        self.0.compute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    use crate::algorithms::encode::DecodeFst;
+    use crate::algorithms::encode::{decode, encode};
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_encode_fst_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<EncodeFst<TropicalWeight, VectorFst<_>, VectorFst<_>>>();
+    }
+
+    #[test]
+    fn test_encode_fst() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 2, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(3, 4, 2.0, s2))?;
+        fst.add_tr(s1, Tr::new(1, 2, 1.0, s2))?;
+        fst.set_final(s1, 0.5)?;
+        fst.set_final(s2, 1.5)?;
+
+        for encode_type in &[
+            EncodeType::EncodeLabels,
+            EncodeType::EncodeWeights,
+            EncodeType::EncodeWeightsAndLabels,
+        ] {
+            let mut ref_encoded = fst.clone();
+            let ref_table = encode(&mut ref_encoded, *encode_type)?;
+
+            let encode_fst: EncodeFst<_, VectorFst<_>, _> = EncodeFst::new(&fst, *encode_type);
+            let encoded: VectorFst<_> = encode_fst.compute()?;
+            assert_eq!(encoded, ref_encoded);
+            let table = encode_fst.encode_table();
+            assert_eq!(table.len(), ref_table.len());
+
+            let decode_fst: DecodeFst<_, VectorFst<_>, _> = DecodeFst::new(&encoded, table);
+            let mut decoded: VectorFst<_> = decode_fst.compute()?;
+            let mut ref_decoded = ref_encoded.clone();
+            decode(&mut ref_decoded, ref_table)?;
+            rm_final_epsilon(&mut decoded)?;
+            assert_eq!(decoded, ref_decoded);
+        }
+        Ok(())
+    }
+}
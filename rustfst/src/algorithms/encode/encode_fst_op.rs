@@ -0,0 +1,118 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::encode::encode_static::EncodeMapper;
+use crate::algorithms::encode::EncodeTable;
+use crate::algorithms::lazy::FstOp;
+use crate::algorithms::{FinalTr, TrMapper};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs, TrsVec, EPS_LABEL};
+
+pub struct EncodeFstOp<W: Semiring, F: ExpandedFst<W>, B: Borrow<F>> {
+    fst: B,
+    encode_table: Arc<EncodeTable<W>>,
+    // When the weights are encoded, the final weights are moved to transitions
+    // to this additional state.
+    superfinal: Option<StateId>,
+    properties: FstProperties,
+    f: PhantomData<F>,
+}
+
+impl<W: Semiring, F: ExpandedFst<W>, B: Borrow<F>> std::fmt::Debug for EncodeFstOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EncodeFstOp {{ encode_table : {:?}, superfinal : {:?}, fst : {:?} }}",
+            self.encode_table,
+            self.superfinal,
+            self.fst.borrow()
+        )
+    }
+}
+
+impl<W: Semiring, F: ExpandedFst<W>, B: Borrow<F>> EncodeFstOp<W, F, B> {
+    pub fn new(fst: B, encode_table: Arc<EncodeTable<W>>) -> Self {
+        encode_table
+            .0
+            .lock()
+            .unwrap()
+            .set_symts_if_missing(fst.borrow().input_symbols(), fst.borrow().output_symbols());
+        let superfinal = if encode_table.encode_type().encode_weights() {
+            Some(fst.borrow().num_states())
+        } else {
+            None
+        };
+        let properties =
+            EncodeMapper::new(encode_table.as_ref()).properties(fst.borrow().properties());
+        Self {
+            fst,
+            encode_table,
+            superfinal,
+            properties,
+            f: PhantomData,
+        }
+    }
+
+    pub fn encode_table(&self) -> &Arc<EncodeTable<W>> {
+        &self.encode_table
+    }
+}
+
+impl<W: Semiring, F: ExpandedFst<W>, B: Borrow<F>> FstOp<W> for EncodeFstOp<W, F, B> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start())
+    }
+
+    fn compute_trs(&self, state: usize) -> Result<TrsVec<W>> {
+        if Some(state) == self.superfinal {
+            return Ok(TrsVec::default());
+        }
+        let mapper = EncodeMapper::new(self.encode_table.as_ref());
+        let mut trs = vec![];
+        for tr in self.fst.borrow().get_trs(state)?.trs() {
+            let mut tr = tr.clone();
+            mapper.tr_map(&mut tr)?;
+            trs.push(tr);
+        }
+        if let Some(superfinal) = self.superfinal {
+            if let Some(weight) = self.fst.borrow().final_weight(state)? {
+                let mut final_tr = FinalTr {
+                    ilabel: EPS_LABEL,
+                    olabel: EPS_LABEL,
+                    weight,
+                };
+                mapper.final_tr_map(&mut final_tr)?;
+                if final_tr.ilabel != EPS_LABEL
+                    || final_tr.olabel != EPS_LABEL
+                    || !final_tr.weight.is_zero()
+                {
+                    trs.push(Tr::new(
+                        final_tr.ilabel,
+                        final_tr.olabel,
+                        final_tr.weight,
+                        superfinal,
+                    ));
+                }
+            }
+        }
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, state: StateId) -> Result<Option<W>> {
+        match self.superfinal {
+            Some(superfinal) if state == superfinal => Ok(Some(W::one())),
+            // The final weights are moved to the transitions to the superfinal state.
+            Some(_) => Ok(None),
+            None => Ok(self.fst.borrow().final_weight(state)?),
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
@@ -1,37 +1,43 @@
-use std::cell::RefCell;
+use anyhow::Result;
 
-use anyhow::{Context, Result};
-
-use crate::algorithms::encode::{EncodeTable, EncodeTableMut, EncodeType};
+use crate::algorithms::encode::{EncodeTable, EncodeType};
 use crate::algorithms::{FinalTr, MapFinalAction, TrMapper};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
-use crate::{Semiring, Tr};
+use crate::{Error, Semiring, Tr};
 
-struct EncodeMapper<W: Semiring> {
-    encode_table: EncodeTable<W>,
+pub(crate) struct EncodeMapper<'a, W: Semiring> {
+    encode_table: &'a EncodeTable<W>,
 }
 
-impl<W: Semiring> EncodeMapper<W> {
-    pub fn new(encode_type: EncodeType) -> Self {
-        EncodeMapper {
-            encode_table: EncodeTable(RefCell::new(EncodeTableMut::new(encode_type))),
-        }
+impl<'a, W: Semiring> EncodeMapper<'a, W> {
+    pub fn new(encode_table: &'a EncodeTable<W>) -> Self {
+        EncodeMapper { encode_table }
     }
 
     pub fn encode_weights(&self) -> bool {
-        self.encode_table.0.borrow().encode_type.encode_weights()
+        self.encode_table
+            .0
+            .lock()
+            .unwrap()
+            .encode_type
+            .encode_weights()
     }
 
     pub fn encode_labels(&self) -> bool {
-        self.encode_table.0.borrow().encode_type.encode_labels()
+        self.encode_table
+            .0
+            .lock()
+            .unwrap()
+            .encode_type
+            .encode_labels()
     }
 }
 
-impl<W: Semiring> TrMapper<W> for EncodeMapper<W> {
+impl<'a, W: Semiring> TrMapper<W> for EncodeMapper<'a, W> {
     fn tr_map(&self, tr: &mut Tr<W>) -> Result<()> {
-        let tuple = self.encode_table.0.borrow().tr_to_tuple(tr);
-        let label = self.encode_table.0.borrow_mut().encode(tuple);
+        let tuple = self.encode_table.0.lock().unwrap().tr_to_tuple(tr);
+        let label = self.encode_table.0.lock().unwrap().encode(tuple);
         tr.ilabel = label;
         if self.encode_labels() {
             tr.olabel = label;
@@ -44,8 +50,13 @@ impl<W: Semiring> TrMapper<W> for EncodeMapper<W> {
 
     fn final_tr_map(&self, final_tr: &mut FinalTr<W>) -> Result<()> {
         if self.encode_weights() {
-            let tuple = self.encode_table.0.borrow().final_tr_to_tuple(final_tr);
-            let label = self.encode_table.0.borrow_mut().encode(tuple);
+            let tuple = self
+                .encode_table
+                .0
+                .lock()
+                .unwrap()
+                .final_tr_to_tuple(final_tr);
+            let label = self.encode_table.0.lock().unwrap().encode(tuple);
             final_tr.ilabel = label;
             if self.encode_labels() {
                 final_tr.olabel = label;
@@ -66,7 +77,6 @@ impl<W: Semiring> TrMapper<W> for EncodeMapper<W> {
     }
 
     fn properties(&self, inprops: FstProperties) -> FstProperties {
-        let outprops = inprops;
         let mut mask = FstProperties::all_properties();
         if self.encode_labels() {
             mask &= FstProperties::i_label_invariant_properties()
@@ -77,7 +87,22 @@ impl<W: Semiring> TrMapper<W> for EncodeMapper<W> {
                 & FstProperties::weight_invariant_properties()
                 & FstProperties::add_super_final_properties()
         }
-        outprops & mask
+        let mut outprops = inprops & mask;
+        // Every transition is labeled with a key, which is never an epsilon.
+        if self.encode_labels() {
+            outprops |= FstProperties::ACCEPTOR
+                | FstProperties::NO_EPSILONS
+                | FstProperties::NO_I_EPSILONS
+                | FstProperties::NO_O_EPSILONS;
+        }
+        // The final weights are moved to transitions towards a superfinal state of weight one.
+        if self.encode_weights() {
+            outprops |= FstProperties::NO_EPSILONS
+                | FstProperties::NO_I_EPSILONS
+                | FstProperties::UNWEIGHTED
+                | FstProperties::UNWEIGHTED_CYCLES;
+        }
+        outprops
     }
 }
 
@@ -89,13 +114,61 @@ impl<W: Semiring> TrMapper<W> for EncodeMapper<W> {
 ///
 /// The encoding of each pair or triple of labels and/or weights as a unique key is stored
 /// in an `EncodeTable` object.
-pub fn encode<W, F>(fst: &mut F, encode_type: EncodeType) -> Result<EncodeTable<W>>
+pub fn encode<W, F>(fst: &mut F, encode_type: EncodeType) -> Result<EncodeTable<W>, Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let encode_table = EncodeTable::new(encode_type);
+    encode_with_table(fst, &encode_table)?;
+    Ok(encode_table)
+}
+
+/// Same as `encode` but reuses an existing `EncodeTable`, for instance one loaded from an
+/// encoder file or one used to encode other FSTs. The tuples that are not yet in the table
+/// are added to it.
+pub fn encode_with_table<W, F>(fst: &mut F, encode_table: &EncodeTable<W>) -> Result<(), Error>
 where
     W: Semiring,
     F: MutableFst<W>,
 {
-    let mut encode_mapper = EncodeMapper::new(encode_type);
-    fst.tr_map(&mut encode_mapper)
-        .with_context(|| format_err!("Error calling TrMap with EncodeMapper."))?;
-    Ok(encode_mapper.encode_table)
+    encode_table
+        .0
+        .lock()
+        .unwrap()
+        .set_symts_if_missing(fst.input_symbols(), fst.output_symbols());
+    let mut encode_mapper = EncodeMapper::new(encode_table);
+    fst.tr_map(&mut encode_mapper)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_encode_properties_proptest(fst in any_fst_with_properties::<TropicalWeight>()) {
+            for encode_type in &[
+                EncodeType::EncodeLabels,
+                EncodeType::EncodeWeights,
+                EncodeType::EncodeWeightsAndLabels,
+            ] {
+                let mut fst = fst.clone();
+                encode(&mut fst, *encode_type).unwrap();
+                prop_assert!(properties_compatible(&fst));
+                if encode_type.encode_labels() {
+                    prop_assert!(fst.properties().contains(FstProperties::ACCEPTOR));
+                }
+                if encode_type.encode_weights() {
+                    prop_assert!(fst.properties().contains(FstProperties::UNWEIGHTED));
+                }
+            }
+        }
+    }
 }
@@ -1,10 +1,16 @@
-pub use decode_static::decode;
-pub use encode_static::encode;
+pub use decode_fst::DecodeFst;
+pub use decode_static::{decode, decode_with_table};
+pub(crate) use decode_static::decode_with_state_map;
+pub use encode_fst::EncodeFst;
+pub use encode_static::{encode, encode_with_table};
 pub use encode_type::EncodeType;
 pub use table::EncodeTable;
-pub(self) use table::EncodeTableMut;
 
+mod decode_fst;
+mod decode_fst_op;
 mod decode_static;
+mod encode_fst;
+mod encode_fst_op;
 mod encode_static;
 mod encode_type;
 mod table;
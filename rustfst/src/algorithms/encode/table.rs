@@ -1,26 +1,63 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fs::{read, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use nom::combinator::verify;
+use nom::multi::count;
+use nom::number::complete::{le_i32, le_i64, le_u32, le_u64};
+use nom::IResult;
 
 use crate::algorithms::encode::EncodeType;
 use crate::algorithms::FinalTr;
-use crate::{Label, Semiring, Tr, EPS_LABEL};
+use crate::parsers::bin_fst::fst_header::{
+    optionally_parse_symt, optionally_write_symt, OpenFstString, FST_MAGIC_NUMBER,
+};
+use crate::parsers::bin_fst::utils_serialization::{
+    write_bin_i32, write_bin_i64, write_bin_u32, write_bin_u64,
+};
+use crate::parsers::nom_utils::NomCustomError;
+use crate::semirings::SerializableSemiring;
+use crate::{Error, Label, Semiring, SymbolTable, Tr, EPS_LABEL};
 use std::collections::hash_map::Entry;
 
-#[derive(PartialEq, Eq, Hash, Clone)]
+// Identifies stream data as an encode table in the format used before the encode tables were
+// written with an FST header (and its endianity).
+static ENCODE_MAGIC_NUMBER: i32 = 2_129_983_209;
+
+// FST type written in the header of an encode table.
+static ENCODE_FST_TYPE: &str = "encode";
+
+// Flags of the header of an encode table, as defined by OpenFst.
+const ENCODE_LABELS: u32 = 0x01;
+const ENCODE_WEIGHTS: u32 = 0x02;
+const ENCODE_HAS_ISYMBOLS: u32 = 0x04;
+const ENCODE_HAS_OSYMBOLS: u32 = 0x08;
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct EncodeTuple<W: Semiring> {
     pub ilabel: Label,
     pub olabel: Label,
     pub weight: W,
 }
 
+#[derive(Debug)]
 pub struct EncodeTableMut<W: Semiring> {
     pub encode_type: EncodeType,
     // FIXME : Store references ?
     id_to_tuple: Vec<EncodeTuple<W>>,
     tuple_to_id: HashMap<EncodeTuple<W>, usize>,
+    isymt: Option<Arc<SymbolTable>>,
+    osymt: Option<Arc<SymbolTable>>,
 }
 
-pub struct EncodeTable<W: Semiring>(pub RefCell<EncodeTableMut<W>>);
+/// Mapping between the encoded labels and the (input label, output label, weight) tuples
+/// they represent. The same table can be used to encode several FSTs and can be saved in
+/// the binary format of the OpenFst encoder files to decode the FSTs later.
+#[derive(Debug)]
+pub struct EncodeTable<W: Semiring>(pub Mutex<EncodeTableMut<W>>);
 
 impl<W: Semiring> EncodeTableMut<W> {
     pub fn new(encode_type: EncodeType) -> Self {
@@ -28,6 +65,8 @@ impl<W: Semiring> EncodeTableMut<W> {
             encode_type,
             id_to_tuple: vec![],
             tuple_to_id: HashMap::new(),
+            isymt: None,
+            osymt: None,
         }
     }
 
@@ -76,7 +115,31 @@ impl<W: Semiring> EncodeTableMut<W> {
     }
 
     pub fn decode(&mut self, tuple_id: usize) -> Option<&EncodeTuple<W>> {
-        self.id_to_tuple.get(tuple_id - 1)
+        tuple_id
+            .checked_sub(1)
+            .and_then(move |idx| self.id_to_tuple.get(idx))
+    }
+
+    pub fn len(&self) -> usize {
+        self.id_to_tuple.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.id_to_tuple.is_empty()
+    }
+
+    /// Records the symbol tables of an encoded FST, unless some were already recorded.
+    pub fn set_symts_if_missing(
+        &mut self,
+        isymt: Option<&Arc<SymbolTable>>,
+        osymt: Option<&Arc<SymbolTable>>,
+    ) {
+        if self.isymt.is_none() {
+            self.isymt = isymt.cloned();
+        }
+        if self.osymt.is_none() {
+            self.osymt = osymt.cloned();
+        }
     }
 }
 
@@ -85,3 +148,282 @@ impl<W: Semiring> Default for EncodeTableMut<W> {
         Self::new(EncodeType::EncodeWeightsAndLabels)
     }
 }
+
+impl<W: Semiring> EncodeTable<W> {
+    /// Creates an empty table. It can be filled by `encode_with_table`.
+    pub fn new(encode_type: EncodeType) -> Self {
+        EncodeTable(Mutex::new(EncodeTableMut::new(encode_type)))
+    }
+
+    pub fn encode_type(&self) -> EncodeType {
+        self.0.lock().unwrap().encode_type
+    }
+
+    /// Number of encoded tuples.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+
+    /// Input symbol table of the first FST encoded with this table.
+    pub fn input_symbols(&self) -> Option<Arc<SymbolTable>> {
+        self.0.lock().unwrap().isymt.clone()
+    }
+
+    /// Output symbol table of the first FST encoded with this table.
+    pub fn output_symbols(&self) -> Option<Arc<SymbolTable>> {
+        self.0.lock().unwrap().osymt.clone()
+    }
+}
+
+impl<W: SerializableSemiring> EncodeTable<W> {
+    /// Loads an encode table from a file in binary format.
+    pub fn read<P: AsRef<Path>>(path_bin_table: P) -> Result<Self, Error> {
+        let data = read(path_bin_table.as_ref())
+            .map_err(|e| Error::read_file("EncodeTable binary file", path_bin_table.as_ref(), e))?;
+        Self::load(&data)
+    }
+
+    /// Writes the encode table to a file in binary format.
+    pub fn write<P: AsRef<Path>>(&self, path_bin_table: P) -> Result<()> {
+        self.store(BufWriter::new(File::create(path_bin_table)?))
+    }
+
+    /// Loads an encode table from bytes in the binary format of the OpenFst encoder files.
+    pub fn load(data: &[u8]) -> Result<Self, Error> {
+        let (_, table) = parse_encode_table(data)
+            .map_err(|e| Error::from_nom("binary EncodeTable", data.len(), e))?;
+        Ok(EncodeTable(Mutex::new(table)))
+    }
+
+    /// Writes the encode table in binary format to any writer.
+    pub fn store<O: Write>(&self, mut file: O) -> Result<()> {
+        let table = self.0.lock().unwrap();
+        let mut flags = 0;
+        if table.encode_type.encode_labels() {
+            flags |= ENCODE_LABELS;
+        }
+        if table.encode_type.encode_weights() {
+            flags |= ENCODE_WEIGHTS;
+        }
+        if table.isymt.is_some() {
+            flags |= ENCODE_HAS_ISYMBOLS;
+        }
+        if table.osymt.is_some() {
+            flags |= ENCODE_HAS_OSYMBOLS;
+        }
+
+        // FST header without symbol tables: the number of states is the number of tuples.
+        write_bin_i32(&mut file, FST_MAGIC_NUMBER)?;
+        OpenFstString::new(ENCODE_FST_TYPE).write(&mut file)?;
+        OpenFstString::new(Tr::<W>::tr_type()).write(&mut file)?;
+        // Version
+        write_bin_i32(&mut file, 0)?;
+        write_bin_u32(&mut file, flags)?;
+        // Properties
+        write_bin_u64(&mut file, 0)?;
+        // Start state
+        write_bin_i64(&mut file, -1)?;
+        write_bin_i64(&mut file, table.id_to_tuple.len() as i64)?;
+        // Number of transitions
+        write_bin_i64(&mut file, 0)?;
+        for tuple in table.id_to_tuple.iter() {
+            write_bin_i32(&mut file, tuple.ilabel as i32)?;
+            write_bin_i32(&mut file, tuple.olabel as i32)?;
+            tuple.weight.write_binary(&mut file)?;
+        }
+        optionally_write_symt(&mut file, &table.isymt)?;
+        optionally_write_symt(&mut file, &table.osymt)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+fn parse_encode_tuple<W: SerializableSemiring>(
+    i: &[u8],
+) -> IResult<&[u8], EncodeTuple<W>, NomCustomError<&[u8]>> {
+    let (i, ilabel) = le_i32(i)?;
+    let (i, olabel) = le_i32(i)?;
+    let (i, weight) = W::parse_binary(i)?;
+    Ok((
+        i,
+        EncodeTuple {
+            ilabel: ilabel as Label,
+            olabel: olabel as Label,
+            weight,
+        },
+    ))
+}
+
+fn parse_encode_flags(i: &[u8]) -> IResult<&[u8], u32, NomCustomError<&[u8]>> {
+    verify(le_u32, |v: &u32| v & (ENCODE_LABELS | ENCODE_WEIGHTS) != 0)(i)
+}
+
+fn parse_encode_size(i: &[u8]) -> IResult<&[u8], i64, NomCustomError<&[u8]>> {
+    verify(le_i64, |v: &i64| *v >= 0)(i)
+}
+
+/// Parses the FST header of an encode table, after the magic number. Returns the flags and the
+/// number of tuples.
+fn parse_encode_table_header<W: SerializableSemiring>(
+    i: &[u8],
+) -> IResult<&[u8], (u32, i64), NomCustomError<&[u8]>> {
+    let (i, _fst_type) = verify(OpenFstString::parse, |v| v.as_str() == ENCODE_FST_TYPE)(i)?;
+    let (i, _tr_type) = verify(OpenFstString::parse, |v| v.as_str() == Tr::<W>::tr_type())(i)?;
+    let (i, _version) = le_i32(i)?;
+    let (i, flags) = parse_encode_flags(i)?;
+    let (i, _properties) = le_u64(i)?;
+    let (i, _start) = le_i64(i)?;
+    let (i, num_tuples) = parse_encode_size(i)?;
+    let (i, _num_trs) = le_i64(i)?;
+    Ok((i, (flags, num_tuples)))
+}
+
+fn parse_encode_table<W: SerializableSemiring>(
+    i: &[u8],
+) -> IResult<&[u8], EncodeTableMut<W>, NomCustomError<&[u8]>> {
+    let (i, magic_number) = verify(le_i32, |v: &i32| {
+        *v == FST_MAGIC_NUMBER || *v == ENCODE_MAGIC_NUMBER
+    })(i)?;
+    let (i, (flags, size)) = if magic_number == FST_MAGIC_NUMBER {
+        parse_encode_table_header::<W>(i)?
+    } else {
+        // Former layout : flags and size only.
+        let (i, flags) = parse_encode_flags(i)?;
+        let (i, size) = parse_encode_size(i)?;
+        (i, (flags, size))
+    };
+    let (i, tuples) = count(parse_encode_tuple, size as usize)(i)?;
+    let (i, isymt) = optionally_parse_symt(i, flags & ENCODE_HAS_ISYMBOLS != 0)?;
+    let (i, osymt) = optionally_parse_symt(i, flags & ENCODE_HAS_OSYMBOLS != 0)?;
+
+    // Checked by the verify above.
+    let encode_type =
+        EncodeType::from_bools(flags & ENCODE_WEIGHTS != 0, flags & ENCODE_LABELS != 0).unwrap();
+    let mut table = EncodeTableMut::new(encode_type);
+    for tuple in tuples {
+        table.encode(tuple);
+    }
+    table.isymt = isymt.map(Arc::new);
+    table.osymt = osymt.map(Arc::new);
+    Ok((i, table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::encode::{decode_with_table, encode_with_table};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, Fst, MutableFst};
+    use crate::semirings::TropicalWeight;
+
+    fn build_fst(labels: &[(Label, Label, f32)]) -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        let mut state = fst.add_state();
+        fst.set_start(state)?;
+        for &(ilabel, olabel, weight) in labels {
+            let next = fst.add_state();
+            fst.add_tr(state, Tr::new(ilabel, olabel, weight, next))?;
+            state = next;
+        }
+        fst.set_final(state, 0.5)?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_encode_table_store_load() -> Result<()> {
+        let mut fst = build_fst(&[(1, 2, 1.0), (3, 4, 2.0), (1, 2, 1.0)])?;
+        let mut isymt = SymbolTable::new();
+        isymt.add_symbol("a");
+        fst.set_input_symbols(Arc::new(isymt));
+
+        let table = EncodeTable::new(EncodeType::EncodeWeightsAndLabels);
+        encode_with_table(&mut fst, &table)?;
+        // Two transitions and the final weight.
+        assert_eq!(table.len(), 3);
+
+        let mut data = vec![];
+        table.store(&mut data)?;
+        let loaded = EncodeTable::<TropicalWeight>::load(&data)?;
+        assert_eq!(loaded.encode_type(), EncodeType::EncodeWeightsAndLabels);
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.input_symbols(), table.input_symbols());
+        assert!(loaded.output_symbols().is_none());
+        for label in 1..=3 {
+            assert_eq!(
+                loaded.0.lock().unwrap().decode(label).cloned(),
+                table.0.lock().unwrap().decode(label).cloned()
+            );
+        }
+
+        let mut data_loaded = vec![];
+        loaded.store(&mut data_loaded)?;
+        assert_eq!(data, data_loaded);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_table_reuse() -> Result<()> {
+        let ref_fst1 = build_fst(&[(1, 2, 1.0), (3, 4, 2.0)])?;
+        let ref_fst2 = build_fst(&[(3, 4, 2.0), (5, 6, 3.0)])?;
+        let mut fst1 = ref_fst1.clone();
+        let mut fst2 = ref_fst2.clone();
+
+        let table = EncodeTable::new(EncodeType::EncodeLabels);
+        encode_with_table(&mut fst1, &table)?;
+        encode_with_table(&mut fst2, &table)?;
+        assert_eq!(table.len(), 3);
+        // The shared tuple is encoded with the same label.
+        assert_eq!(
+            fst1.get_trs(1)?.trs()[0].ilabel,
+            fst2.get_trs(0)?.trs()[0].ilabel
+        );
+
+        let mut data = vec![];
+        table.store(&mut data)?;
+        let loaded = EncodeTable::load(&data)?;
+        decode_with_table(&mut fst1, &loaded)?;
+        decode_with_table(&mut fst2, &loaded)?;
+        assert_eq!(fst1, ref_fst1);
+        assert_eq!(fst2, ref_fst2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_table_load_former_layout() -> Result<()> {
+        let mut data = vec![];
+        write_bin_i32(&mut data, ENCODE_MAGIC_NUMBER)?;
+        write_bin_u32(&mut data, ENCODE_LABELS)?;
+        write_bin_i64(&mut data, 1)?;
+        write_bin_i32(&mut data, 1)?;
+        write_bin_i32(&mut data, 2)?;
+        TropicalWeight::one().write_binary(&mut data)?;
+
+        let table = EncodeTable::<TropicalWeight>::load(&data)?;
+        assert_eq!(table.encode_type(), EncodeType::EncodeLabels);
+        assert_eq!(
+            table.0.lock().unwrap().decode(1).cloned(),
+            Some(EncodeTuple {
+                ilabel: 1,
+                olabel: 2,
+                weight: TropicalWeight::one()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_table_load_wrong_tr_type() -> Result<()> {
+        let mut fst = build_fst(&[(1, 2, 1.0)])?;
+        let table = EncodeTable::new(EncodeType::EncodeWeights);
+        encode_with_table(&mut fst, &table)?;
+        let mut data = vec![];
+        table.store(&mut data)?;
+        assert!(EncodeTable::<crate::semirings::LogWeight>::load(&data).is_err());
+        Ok(())
+    }
+}
@@ -2,15 +2,13 @@ use std::borrow::Borrow;
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use anyhow::Result;
-
 use crate::algorithms::factor_weight::factor_weight_op::FactorWeightOp;
 use crate::algorithms::factor_weight::{FactorIterator, FactorWeightOptions};
 use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
 use crate::semirings::WeightQuantize;
-use crate::{SymbolTable, TrsVec};
+use crate::{Error, StateId, SymbolTable, TrsVec};
 
 /// The result of weight factoring is a transducer equivalent to the
 /// input whose path weights have been factored according to the FactorIterator.
@@ -34,7 +32,7 @@ where
         self.0.start()
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         self.0.final_weight(state_id)
     }
 
@@ -42,7 +40,7 @@ where
         self.0.final_weight_unchecked(state_id)
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.0.num_trs(s)
     }
 
@@ -50,7 +48,7 @@ where
         self.0.num_trs_unchecked(s)
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         self.0.get_trs(state_id)
     }
 
@@ -62,11 +60,11 @@ where
         self.0.properties()
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_input_epsilons(state)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.0.num_output_epsilons(state)
     }
 }
@@ -149,7 +147,7 @@ impl<'a, W, F: Fst<W>, B: Borrow<F>, FI: FactorIterator<W>> FactorWeightFst<W, F
 where
     W: WeightQuantize,
 {
-    pub fn new(fst: B, opts: FactorWeightOptions) -> Result<Self> {
+    pub fn new(fst: B, opts: FactorWeightOptions) -> Result<Self, Error> {
         let isymt = fst.borrow().input_symbols().cloned();
         let osymt = fst.borrow().output_symbols().cloned();
         let fst_op = FactorWeightOp::new(fst, opts)?;
@@ -159,9 +157,15 @@ where
     }
 
     /// Turns the Lazy FST into a static one.
-    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
         self.0.compute()
     }
+
+    /// State of the input FST from which `state` has been created. `None` for the
+    /// states added to factor final weights. The state must already have been visited.
+    pub(crate) fn original_state(&self, state: StateId) -> Option<StateId> {
+        self.0.op.original_state(state)
+    }
 }
 
 #[cfg(test)]
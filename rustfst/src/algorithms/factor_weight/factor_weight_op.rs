@@ -11,7 +11,7 @@ use crate::fst_properties::mutable_properties::factor_weight_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::Fst;
 use crate::semirings::{Semiring, WeightQuantize};
-use crate::{Tr, Trs, TrsVec};
+use crate::{StateId, Tr, Trs, TrsVec};
 
 pub struct FactorWeightOp<W: Semiring, F: Fst<W>, B: Borrow<F>, FI: FactorIterator<W>> {
     opts: FactorWeightOptions,
@@ -166,4 +166,10 @@ where
             .mode
             .intersects(FactorWeightType::FACTOR_FINAL_WEIGHTS)
     }
+
+    /// State of the input FST from which `state` has been created. `None` for the
+    /// states added to factor final weights.
+    pub(crate) fn original_state(&self, state: StateId) -> Option<StateId> {
+        self.fw_state_table.find_tuple(state).state
+    }
 }
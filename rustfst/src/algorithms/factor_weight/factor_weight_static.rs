@@ -1,17 +1,16 @@
 use std::borrow::Borrow;
 
-use anyhow::Result;
-
 use crate::algorithms::factor_weight::{FactorIterator, FactorWeightFst, FactorWeightOptions};
 use crate::fst_traits::{AllocableFst, Fst, MutableFst};
 use crate::semirings::WeightQuantize;
+use crate::Error;
 
 /// The result of weight factoring is a transducer equivalent to the
 /// input whose path weights have been factored according to the FactorIterator.
 /// States and transitions will be added as necessary. The algorithm is a
 /// generalization to arbitrary weights of the second step of the input
 /// epsilon-normalization algorithm.
-pub fn factor_weight<W, F1, B, F2, FI>(fst_in: B, opts: FactorWeightOptions) -> Result<F2>
+pub fn factor_weight<W, F1, B, F2, FI>(fst_in: B, opts: FactorWeightOptions) -> Result<F2, Error>
 where
     F1: Fst<W>,
     B: Borrow<F1>,
@@ -5,7 +5,7 @@ use anyhow::Result;
 
 use crate::fst_traits::ExpandedFst;
 use crate::semirings::Semiring;
-use crate::{StateId, Tr, Trs, KDELTA};
+use crate::{Error, StateId, Tr, Trs, KDELTA};
 use std::marker::PhantomData;
 
 struct Isomorphism<'a, W: Semiring, F1: ExpandedFst<W>, F2: ExpandedFst<W>> {
@@ -172,7 +172,7 @@ impl IsomorphicConfig {
 ///
 /// In other words, Isomorphic(A, B) is true if and only if the states of A can
 /// be renumbered and the transitions leaving each state reordered so that Equal(A, B) is true.
-pub fn isomorphic<W, F1, F2>(fst_1: &F1, fst_2: &F2) -> Result<bool>
+pub fn isomorphic<W, F1, F2>(fst_1: &F1, fst_2: &F2) -> Result<bool, Error>
 where
     W: Semiring,
     F1: ExpandedFst<W>,
@@ -191,14 +191,14 @@ pub fn isomorphic_with_config<W, F1, F2>(
     fst_1: &F1,
     fst_2: &F2,
     config: IsomorphicConfig,
-) -> Result<bool>
+) -> Result<bool, Error>
 where
     W: Semiring,
     F1: ExpandedFst<W>,
     F2: ExpandedFst<W>,
 {
     let mut iso = Isomorphism::new(fst_1, fst_2, config.delta);
-    iso.isomorphic()
+    Ok(iso.isomorphic()?)
 }
 
 #[cfg(test)]
@@ -3,7 +3,6 @@ use std::iter::{repeat, Map, Repeat, Zip};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use anyhow::Result;
 use itertools::izip;
 use unsafe_unwrap::UnsafeUnwrap;
 
@@ -15,7 +14,7 @@ use crate::fst_traits::{
     AllocableFst, CoreFst, Fst, FstIterData, FstIterator, MutableFst, StateIterator,
 };
 use crate::semirings::Semiring;
-use crate::{StateId, SymbolTable, Trs, TrsVec};
+use crate::{Error, StateId, SymbolTable, Trs, TrsVec};
 
 #[derive(Debug, Clone)]
 pub struct LazyFst<W: Semiring, Op: FstOp<W>, Cache> {
@@ -41,7 +40,7 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
         }
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         match self.cache.get_final_weight(state_id) {
             CacheStatus::Computed(final_weight) => Ok(final_weight),
             CacheStatus::NotComputed => {
@@ -57,17 +56,17 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
         self.final_weight(state_id).unsafe_unwrap()
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         self.cache
             .num_trs(s)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", s))
+            .ok_or_else(|| Error::InvalidStateId(s))
     }
 
     unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
         self.cache.num_trs(s).unsafe_unwrap()
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         match self.cache.get_trs(state_id) {
             CacheStatus::Computed(trs) => Ok(trs),
             CacheStatus::NotComputed => {
@@ -86,16 +85,16 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
         self.op.properties()
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.cache
             .num_input_epsilons(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))
+            .ok_or_else(|| Error::InvalidStateId(state))
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         self.cache
             .num_output_epsilons(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))
+            .ok_or_else(|| Error::InvalidStateId(state))
     }
 }
 
@@ -218,7 +217,7 @@ where
     }
 
     /// Turns the Lazy FST into a static one.
-    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
         let start_state = self.start();
         let mut fst_out = F2::new();
         if start_state.is_none() {
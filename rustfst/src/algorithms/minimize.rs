@@ -3,28 +3,27 @@ use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::marker::PhantomData;
 
 use anyhow::Result;
 use binary_heap_plus::BinaryHeap;
 use stable_bst::TreeMap;
 
-use crate::algorithms::encode::EncodeType;
+use crate::algorithms::connect::connect_with_state_map;
+use crate::algorithms::encode::{decode_with_state_map, EncodeType};
 use crate::algorithms::factor_weight::factor_iterators::GallicFactorLeft;
-use crate::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
+use crate::algorithms::factor_weight::{FactorWeightFst, FactorWeightOptions, FactorWeightType};
 use crate::algorithms::partition::Partition;
+use crate::algorithms::preconditions::{check_fst_properties, check_semiring_properties};
 use crate::algorithms::queues::LifoQueue;
 use crate::algorithms::tr_compares::ILabelCompare;
 use crate::algorithms::tr_mappers::QuantizeMapper;
 use crate::algorithms::tr_unique;
 use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
-use crate::algorithms::Queue;
-use crate::algorithms::{
-    connect,
-    encode::{decode, encode},
-    tr_map, tr_sort, weight_convert, ReweightType,
-};
+use crate::algorithms::{encode::encode, tr_map, tr_sort, weight_convert, ReweightType};
 use crate::algorithms::{push_weights_with_config, reverse, PushWeightsConfig};
+use crate::algorithms::{PreconditionError, Queue};
 use crate::fst_impls::VectorFst;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, Fst, MutableFst};
@@ -34,14 +33,26 @@ use crate::semirings::{
 use crate::EPS_LABEL;
 use crate::KDELTA;
 use crate::NO_STATE_ID;
-use crate::{StateId, Trs};
+use crate::{Error, Label, StateId, Trs};
 use crate::{Tr, KSHORTESTDELTA};
 use itertools::Itertools;
 
+/// Algorithm used to minimize the underlying unweighted acceptor.
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+pub enum MinimizeType {
+    /// Partition refinement: Revuz's algorithm for acyclic inputs and Hopcroft's otherwise.
+    /// Non-deterministic inputs are only reduced by merging the equivalent states.
+    MinimizeHopcroft,
+    /// Brzozowski's algorithm: the reverse is determinized twice. The result is
+    /// deterministic, which makes it suited for non-deterministic acceptors.
+    MinimizeBrzozowski,
+}
+
 #[derive(Clone, Copy, PartialOrd, PartialEq)]
 pub struct MinimizeConfig {
     delta: f32,
     allow_nondet: bool,
+    min_type: MinimizeType,
 }
 
 impl MinimizeConfig {
@@ -49,6 +60,7 @@ impl MinimizeConfig {
         Self {
             delta,
             allow_nondet,
+            min_type: MinimizeType::MinimizeHopcroft,
         }
     }
 
@@ -62,6 +74,10 @@ impl MinimizeConfig {
             ..self
         }
     }
+
+    pub fn with_min_type(self, min_type: MinimizeType) -> Self {
+        Self { min_type, ..self }
+    }
 }
 
 impl Default for MinimizeConfig {
@@ -69,6 +85,7 @@ impl Default for MinimizeConfig {
         Self {
             delta: KSHORTESTDELTA,
             allow_nondet: false,
+            min_type: MinimizeType::MinimizeHopcroft,
         }
     }
 }
@@ -76,7 +93,7 @@ impl Default for MinimizeConfig {
 /// In place minimization of deterministic weighted automata and transducers,
 /// and also non-deterministic ones if they use an idempotent semiring.
 /// For transducers, the algorithm produces a compact factorization of the minimal transducer.
-pub fn minimize<W, F>(ifst: &mut F) -> Result<()>
+pub fn minimize<W, F>(ifst: &mut F) -> Result<(), Error>
 where
     F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
     W: WeaklyDivisibleSemiring + WeightQuantize,
@@ -88,7 +105,26 @@ where
 /// In place minimization of deterministic weighted automata and transducers,
 /// and also non-deterministic ones if they use an idempotent semiring.
 /// For transducers, the algorithm produces a compact factorization of the minimal transducer.
-pub fn minimize_with_config<W, F>(ifst: &mut F, config: MinimizeConfig) -> Result<()>
+pub fn minimize_with_config<W, F>(ifst: &mut F, config: MinimizeConfig) -> Result<(), Error>
+where
+    F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    W::ReverseWeight: WeightQuantize,
+{
+    minimize_with_state_map(ifst, config).map(|_| ())
+}
+
+/// Same as `minimize_with_config` but also returns the state mapping : for each state of the
+/// input FST, the state of the minimized FST it has been merged into or `None` if it has been
+/// removed because it is not on a successful path.
+///
+/// Factoring the weights of a transducer may split a state into several ones, in which case
+/// the one with the smallest id is returned. With `MinimizeBrzozowski`, a state of a
+/// non-deterministic input is mapped to the minimized state reached by the same shortest path.
+pub fn minimize_with_state_map<W, F>(
+    ifst: &mut F,
+    config: MinimizeConfig,
+) -> Result<Vec<Option<StateId>>, Error>
 where
     F: MutableFst<W> + ExpandedFst<W> + AllocableFst<W>,
     W: WeaklyDivisibleSemiring + WeightQuantize,
@@ -96,6 +132,8 @@ where
 {
     let delta = config.delta;
     let allow_nondet = config.allow_nondet;
+    let min_type = config.min_type;
+    let num_states = ifst.num_states();
 
     let props = ifst.compute_and_update_properties(
         FstProperties::ACCEPTOR
@@ -107,10 +145,13 @@ where
     let allow_acyclic_minimization = if props.contains(FstProperties::I_DETERMINISTIC) {
         true
     } else {
-        if !W::properties().contains(SemiringProperties::IDEMPOTENT) {
-            bail!("Cannot minimize a non-deterministic FST over a non-idempotent semiring")
-        } else if !allow_nondet {
-            bail!("Refusing to minimize a non-deterministic FST with allow_nondet = false")
+        check_semiring_properties::<W>("minimize", SemiringProperties::IDEMPOTENT)?;
+        if !allow_nondet {
+            return Err(PreconditionError::MissingFstProperties {
+                algorithm: "minimize",
+                missing: FstProperties::I_DETERMINISTIC,
+            }
+            .into());
         }
 
         false
@@ -132,9 +173,12 @@ where
 
         let encode_table = encode(&mut gfst, EncodeType::EncodeWeightsAndLabels)?;
 
-        acceptor_minimize(&mut gfst, allow_acyclic_minimization)?;
+        let min_map = acceptor_minimize_with_type(&mut gfst, allow_acyclic_minimization, min_type)?;
 
-        decode(&mut gfst, encode_table)?;
+        // The decoding removes the superfinal state added by the encoding, which is not
+        // necessarily the last state of the minimized FST.
+        let decode_map = decode_with_state_map(&mut gfst, &encode_table)?;
+        let mut state_map = compose_state_maps(&min_map, &decode_map);
 
         let factor_opts: FactorWeightOptions = FactorWeightOptions {
             delta: KDELTA,
@@ -145,18 +189,31 @@ where
             increment_final_olabel: false,
         };
 
-        let fwfst: VectorFst<_> =
-            factor_weight::<_, VectorFst<GallicWeightLeft<W>>, _, _, GallicFactorLeft<W>>(
+        let lazy_fwfst =
+            FactorWeightFst::<_, VectorFst<GallicWeightLeft<W>>, _, GallicFactorLeft<W>>::new(
                 &gfst,
                 factor_opts,
             )?;
+        let fwfst: VectorFst<_> = lazy_fwfst.compute()?;
+
+        // The computation preserves the state ids of the lazy FST.
+        let mut factor_map = vec![None; gfst.num_states()];
+        for s in 0..fwfst.num_states() {
+            if let Some(orig_state) = lazy_fwfst.original_state(s) {
+                if factor_map[orig_state].is_none() {
+                    factor_map[orig_state] = Some(s);
+                }
+            }
+        }
 
         let mut from_gallic = FromGallicConverter {
             superfinal_label: EPS_LABEL,
         };
         *ifst = weight_convert(&fwfst, &mut from_gallic)?;
 
-        Ok(())
+        // Drop the states added by the weight pushing and the encoding.
+        state_map.truncate(num_states);
+        Ok(compose_state_maps(&state_map, &factor_map))
     } else if props.contains(FstProperties::WEIGHTED) {
         // Weighted acceptor
         let push_weights_config = PushWeightsConfig::default().with_delta(delta);
@@ -164,11 +221,19 @@ where
         let quantize_mapper = QuantizeMapper::new(delta);
         tr_map(ifst, &quantize_mapper)?;
         let encode_table = encode(ifst, EncodeType::EncodeWeightsAndLabels)?;
-        acceptor_minimize(ifst, allow_acyclic_minimization)?;
-        decode(ifst, encode_table)
+        let min_map = acceptor_minimize_with_type(ifst, allow_acyclic_minimization, min_type)?;
+        let decode_map = decode_with_state_map(ifst, &encode_table)?;
+        let mut state_map = compose_state_maps(&min_map, &decode_map);
+
+        state_map.truncate(num_states);
+        Ok(state_map)
     } else {
         // Unweighted acceptor
-        acceptor_minimize(ifst, allow_acyclic_minimization)
+        Ok(acceptor_minimize_with_type(
+            ifst,
+            allow_acyclic_minimization,
+            min_type,
+        )?)
     }
 }
 
@@ -180,36 +245,74 @@ where
 pub fn acceptor_minimize<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
     ifst: &mut F,
     allow_acyclic_minimization: bool,
-) -> Result<()> {
+) -> Result<(), Error> {
+    acceptor_minimize_with_state_map(ifst, allow_acyclic_minimization).map(|_| ())
+}
+
+/// Same as `acceptor_minimize` but also returns, for each state of the input FST, the state
+/// of the minimized FST it has been merged into or `None` if it has been removed.
+pub fn acceptor_minimize_with_state_map<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
+    ifst: &mut F,
+    allow_acyclic_minimization: bool,
+) -> Result<Vec<Option<StateId>>, Error> {
+    Ok(acceptor_minimize_with_type(
+        ifst,
+        allow_acyclic_minimization,
+        MinimizeType::MinimizeHopcroft,
+    )?)
+}
+
+fn acceptor_minimize_with_type<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
+    ifst: &mut F,
+    allow_acyclic_minimization: bool,
+    min_type: MinimizeType,
+) -> Result<Vec<Option<StateId>>> {
     let props = ifst.compute_and_update_properties(
         FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED | FstProperties::ACYCLIC,
     )?;
-    if !props.contains(FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED) {
-        bail!("FST is not an unweighted acceptor");
-    }
+    check_fst_properties(
+        "acceptor_minimize",
+        props,
+        FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED,
+    )?;
 
-    connect(ifst)?;
+    let connect_map = connect_with_state_map(ifst)?;
 
     if ifst.num_states() == 0 {
-        return Ok(());
+        return Ok(connect_map);
     }
 
-    if allow_acyclic_minimization && props.contains(FstProperties::ACYCLIC) {
-        // Acyclic minimization
-        tr_sort(ifst, ILabelCompare {});
-        let minimizer = AcyclicMinimizer::new(ifst)?;
-        merge_states(minimizer.get_partition(), ifst)?;
-    } else {
-        let p = cyclic_minimize(ifst)?;
-        merge_states(p, ifst)?;
-    }
+    let min_map = match min_type {
+        MinimizeType::MinimizeHopcroft => {
+            if allow_acyclic_minimization && props.contains(FstProperties::ACYCLIC) {
+                // Acyclic minimization
+                tr_sort(ifst, ILabelCompare {});
+                let minimizer = AcyclicMinimizer::new(ifst)?;
+                merge_states(minimizer.get_partition(), ifst)?
+            } else {
+                let p = cyclic_minimize(ifst)?;
+                merge_states(p, ifst)?
+            }
+        }
+        MinimizeType::MinimizeBrzozowski => brzozowski_minimize(ifst)?,
+    };
 
     tr_unique(ifst);
 
-    Ok(())
+    Ok(compose_state_maps(&connect_map, &min_map))
 }
 
-fn merge_states<W: Semiring, F: MutableFst<W>>(partition: Partition, fst: &mut F) -> Result<()> {
+fn compose_state_maps(
+    first: &[Option<StateId>],
+    second: &[Option<StateId>],
+) -> Vec<Option<StateId>> {
+    first.iter().map(|s| s.and_then(|s| second[s])).collect()
+}
+
+fn merge_states<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
+    partition: Partition,
+    fst: &mut F,
+) -> Result<Vec<Option<StateId>>> {
     let mut state_map = vec![None; partition.num_classes()];
 
     for (i, s) in state_map
@@ -220,6 +323,10 @@ fn merge_states<W: Semiring, F: MutableFst<W>>(partition: Partition, fst: &mut F
         *s = partition.iter(i).next();
     }
 
+    let merge_map: Vec<_> = (0..fst.num_states())
+        .map(|s| state_map[partition.get_class_id(s)])
+        .collect();
+
     for c in 0..partition.num_classes() {
         for s in partition.iter(c) {
             if s == state_map[c].unwrap() {
@@ -249,9 +356,9 @@ fn merge_states<W: Semiring, F: MutableFst<W>>(partition: Partition, fst: &mut F
 
     fst.set_start(state_map[partition.get_class_id(fst.start().unwrap())].unwrap())?;
 
-    connect(fst)?;
+    let connect_map = connect_with_state_map(fst)?;
 
-    Ok(())
+    Ok(compose_state_maps(&merge_map, &connect_map))
 }
 
 // Compute the height (distance) to final state
@@ -577,6 +684,135 @@ fn cyclic_minimize<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<Partiti
     Ok(partition)
 }
 
+/// Deterministic unweighted acceptor built by subset construction. State 0 is the start.
+struct SubsetAcceptor {
+    finals: Vec<bool>,
+    trs: Vec<Vec<(Label, StateId)>>,
+}
+
+/// Subset construction applied to the reverse of the acceptor defined by `start`, `finals`
+/// and `trs`. Only the non-empty subsets reachable from the final states are created.
+fn reverse_determinize(
+    start: StateId,
+    finals: &[bool],
+    trs: &[Vec<(Label, StateId)>],
+) -> SubsetAcceptor {
+    let mut reversed_trs = vec![vec![]; trs.len()];
+    for (s, trs_s) in trs.iter().enumerate() {
+        for &(label, nextstate) in trs_s {
+            reversed_trs[nextstate].push((label, s));
+        }
+    }
+
+    let mut res = SubsetAcceptor {
+        finals: vec![],
+        trs: vec![],
+    };
+    let initial_subset: Vec<StateId> = (0..finals.len()).filter(|s| finals[*s]).collect();
+    if initial_subset.is_empty() {
+        return res;
+    }
+
+    let mut subsets = vec![initial_subset.clone()];
+    let mut subset_ids = HashMap::new();
+    subset_ids.insert(initial_subset, 0);
+
+    let mut cur = 0;
+    while cur < subsets.len() {
+        let mut next_subsets: BTreeMap<Label, BTreeSet<StateId>> = BTreeMap::new();
+        for &s in &subsets[cur] {
+            for &(label, prevstate) in &reversed_trs[s] {
+                next_subsets.entry(label).or_default().insert(prevstate);
+            }
+        }
+        let is_final = subsets[cur].binary_search(&start).is_ok();
+
+        let mut cur_trs = Vec::with_capacity(next_subsets.len());
+        for (label, subset) in next_subsets {
+            let subset: Vec<StateId> = subset.into_iter().collect();
+            let id = match subset_ids.entry(subset) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let id = subsets.len();
+                    subsets.push(e.key().clone());
+                    e.insert(id);
+                    id
+                }
+            };
+            cur_trs.push((label, id));
+        }
+
+        res.finals.push(is_final);
+        res.trs.push(cur_trs);
+        cur += 1;
+    }
+
+    res
+}
+
+/// Brzozowski's minimization of a connected unweighted acceptor : determinizing the reverse
+/// of the determinized reverse produces the minimal deterministic acceptor.
+fn brzozowski_minimize<W: Semiring, F: MutableFst<W> + ExpandedFst<W>>(
+    fst: &mut F,
+) -> Result<Vec<Option<StateId>>> {
+    let start = fst
+        .start()
+        .ok_or_else(|| format_err!("Brzozowski minimization requires a start state"))?;
+    let num_states = fst.num_states();
+    let mut finals = Vec::with_capacity(num_states);
+    let mut trs = Vec::with_capacity(num_states);
+    for s in 0..num_states {
+        finals.push(fst.is_final(s)?);
+        trs.push(
+            fst.get_trs(s)?
+                .trs()
+                .iter()
+                .map(|tr| (tr.ilabel, tr.nextstate))
+                .collect_vec(),
+        );
+    }
+
+    let reversed = reverse_determinize(start, &finals, &trs);
+    let minimal = reverse_determinize(0, &reversed.finals, &reversed.trs);
+
+    // Every state of the connected input is reached by a prefix of an accepted string, which
+    // is also read by the minimal acceptor.
+    let mut state_map = vec![None; num_states];
+    state_map[start] = Some(0);
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(s) = queue.pop_front() {
+        let min_state = state_map[s].unwrap();
+        for &(label, nextstate) in &trs[s] {
+            if state_map[nextstate].is_some() {
+                continue;
+            }
+            if let Some(&(_, min_nextstate)) =
+                minimal.trs[min_state].iter().find(|(l, _)| *l == label)
+            {
+                state_map[nextstate] = Some(min_nextstate);
+                queue.push_back(nextstate);
+            }
+        }
+    }
+
+    fst.del_all_states();
+    fst.add_states(minimal.trs.len());
+    if !minimal.trs.is_empty() {
+        fst.set_start(0)?;
+    }
+    for (s, (is_final, trs_s)) in minimal.finals.into_iter().zip(minimal.trs).enumerate() {
+        if is_final {
+            fst.set_final(s, W::one())?;
+        }
+        for (label, nextstate) in trs_s {
+            fst.add_tr(s, Tr::new(label, label, W::one(), nextstate))?;
+        }
+    }
+
+    Ok(state_map)
+}
+
 struct TrsIterCollected<W: Semiring, T: Trs<W>> {
     idx: usize,
     trs: T,
@@ -619,10 +855,196 @@ impl TrIterCompare {
 
 #[cfg(test)]
 mod tests {
+    use crate::fst_properties::FstProperties;
     use crate::prelude::*;
     use algorithms::determinize::*;
+    use anyhow::Result;
     use proptest::prelude::*;
 
+    #[test]
+    fn test_acceptor_minimize_state_map() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst.add_tr(0, Tr::new(2, 2, TropicalWeight::one(), 2))?;
+        fst.add_tr(0, Tr::new(4, 4, TropicalWeight::one(), 4))?;
+        fst.add_tr(1, Tr::new(3, 3, TropicalWeight::one(), 3))?;
+        fst.add_tr(2, Tr::new(3, 3, TropicalWeight::one(), 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let state_map = acceptor_minimize_with_state_map(&mut fst, true)?;
+
+        assert_eq!(fst.num_states(), 3);
+        assert_eq!(state_map.len(), 5);
+        assert_eq!(state_map[0], fst.start());
+        assert!(state_map[1].is_some());
+        assert_eq!(state_map[1], state_map[2]);
+        assert!(fst.is_final(state_map[3].unwrap())?);
+        assert_eq!(state_map[4], None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_state_map_transducer() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(3, 2, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 2.0, 3))?;
+        fst.add_tr(2, Tr::new(4, 4, 2.0, 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let state_map = minimize_with_state_map(&mut fst, MinimizeConfig::default())?;
+
+        assert_eq!(state_map.len(), 4);
+        assert_eq!(state_map[0], fst.start());
+        assert!(state_map[1].is_some());
+        assert_eq!(state_map[1], state_map[2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_brzozowski_non_deterministic() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 2))?;
+        fst.add_tr(1, Tr::new(2, 2, TropicalWeight::one(), 3))?;
+        fst.add_tr(2, Tr::new(3, 3, TropicalWeight::one(), 3))?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let mut expected = VectorFst::<TropicalWeight>::new();
+        expected.add_states(3);
+        expected.set_start(0)?;
+        expected.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        expected.add_tr(1, Tr::new(2, 2, TropicalWeight::one(), 2))?;
+        expected.add_tr(1, Tr::new(3, 3, TropicalWeight::one(), 2))?;
+        expected.set_final(2, TropicalWeight::one())?;
+
+        // Merging the equivalent states can't reduce this acceptor.
+        let mut hopcroft_fst = fst.clone();
+        let config = MinimizeConfig::default().with_allow_nondet(true);
+        minimize_with_config(&mut hopcroft_fst, config)?;
+        assert_eq!(hopcroft_fst.num_states(), 4);
+
+        let config = config.with_min_type(MinimizeType::MinimizeBrzozowski);
+        let state_map = minimize_with_state_map(&mut fst, config)?;
+
+        assert!(isomorphic(&fst, &expected)?);
+        assert_eq!(state_map[0], fst.start());
+        assert!(state_map[1].is_some());
+        assert_eq!(state_map[1], state_map[2]);
+        assert!(fst.is_final(state_map[3].unwrap())?);
+        Ok(())
+    }
+
+    /// Minimizes a deterministic FST with Brzozowski's algorithm, checks that the result is the
+    /// one of Hopcroft's algorithm and that the state mapping points to valid states.
+    fn minimize_brzozowski_checked(
+        fst: &VectorFst<TropicalWeight>,
+    ) -> Result<(VectorFst<TropicalWeight>, Vec<Option<StateId>>)> {
+        let mut hopcroft_fst = fst.clone();
+        minimize(&mut hopcroft_fst)?;
+
+        let mut brzozowski_fst = fst.clone();
+        let config = MinimizeConfig::default().with_min_type(MinimizeType::MinimizeBrzozowski);
+        let state_map = minimize_with_state_map(&mut brzozowski_fst, config)?;
+
+        assert!(isomorphic(&brzozowski_fst, &hopcroft_fst)?);
+        assert_eq!(state_map.len(), fst.num_states());
+        assert_eq!(state_map[0], brzozowski_fst.start());
+        for min_state in state_map.iter() {
+            assert!(min_state.unwrap() < brzozowski_fst.num_states());
+        }
+        Ok((brzozowski_fst, state_map))
+    }
+
+    #[test]
+    fn test_minimize_brzozowski_weighted_acceptor() -> Result<()> {
+        // The superfinal state of the encoding is not the last state of the minimized FST.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.set_final(0, 2.0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(1, Tr::new(2, 2, 1.0, 2))?;
+        fst.set_final(2, 0.5)?;
+
+        let (min_fst, state_map) = minimize_brzozowski_checked(&fst)?;
+
+        assert_eq!(min_fst.num_states(), 3);
+        assert!(min_fst.is_final(state_map[0].unwrap())?);
+        assert!(!min_fst.is_final(state_map[1].unwrap())?);
+        assert!(min_fst.is_final(state_map[2].unwrap())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_brzozowski_transducer() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.set_final(0, 3.0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(3, 2, 1.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 2.0, 3))?;
+        fst.add_tr(2, Tr::new(4, 4, 2.0, 4))?;
+        fst.set_final(3, 0.5)?;
+        fst.set_final(4, 0.5)?;
+
+        let (_, state_map) = minimize_brzozowski_checked(&fst)?;
+
+        assert_eq!(state_map[1], state_map[2]);
+        assert_eq!(state_map[3], state_map[4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize_non_deterministic_preconditions() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 0))?;
+        fst.set_final(1, TropicalWeight::one())?;
+
+        let err = minimize(&mut fst).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingFstProperties {
+                    algorithm: "minimize",
+                    missing: FstProperties::I_DETERMINISTIC
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+
+        let mut log_fst = VectorFst::<LogWeight>::new();
+        log_fst.add_states(2);
+        log_fst.set_start(0)?;
+        log_fst.add_tr(0, Tr::new(1, 1, LogWeight::one(), 1))?;
+        log_fst.add_tr(0, Tr::new(1, 1, LogWeight::one(), 0))?;
+        log_fst.set_final(1, LogWeight::one())?;
+
+        let config = MinimizeConfig::default().with_allow_nondet(true);
+        let err = minimize_with_config(&mut log_fst, config).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingSemiringProperties {
+                    algorithm: "minimize",
+                    missing: SemiringProperties::IDEMPOTENT
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig {
             fork: true,
@@ -1,18 +1,32 @@
 pub use self::{
     add_super_final_state::add_super_final_state,
     all_pairs_shortest_distance::all_pairs_shortest_distance,
+    astar_shortest_path::{
+        astar_shortest_fst_path, astar_shortest_path, reverse_distance_heuristic, AStarConfig,
+    },
     condense::condense,
     connect::connect,
+    decoder::{decode, DecodeResult, DecoderConfig},
     fst_convert::{fst_convert, fst_convert_from_ref},
     inversion::invert,
     isomorphic::{isomorphic, isomorphic_with_config, IsomorphicConfig},
-    minimize::{acceptor_minimize, minimize, minimize_with_config, MinimizeConfig},
-    optimize::optimize,
+    minimize::{
+        acceptor_minimize, acceptor_minimize_with_state_map, minimize, minimize_with_config,
+        minimize_with_state_map, MinimizeConfig, MinimizeType,
+    },
+    nbest_strings::{nbest_strings, nbest_strings_with_config, NBestStringsConfig},
+    normalize::{is_stochastic, normalize},
+    optimize::{
+        optimize, optimize_with_config, OptimizeConfig, OptimizeEncodeType, OptimizeStage,
+        OptimizeStageSize,
+    },
+    preconditions::PreconditionError,
     projection::{project, ProjectType},
     push::{
         push, push_weights, push_weights_with_config, push_with_config, PushConfig, PushType,
         PushWeightsConfig,
     },
+    push_fst::PushFst,
     queue::{Queue, QueueType},
     relabel_pairs::relabel_pairs,
     reverse::reverse,
@@ -20,10 +34,18 @@ pub use self::{
     rm_final_epsilon::rm_final_epsilon,
     shortest_distance::{shortest_distance, shortest_distance_with_config, ShortestDistanceConfig},
     shortest_path::{shortest_path, shortest_path_with_config, ShortestPathConfig},
+    shortest_paths_iter::{ShortestPathsIterator, ShortestPathsIteratorConfig},
+    state_map::{state_map, StateMapper},
+    state_map_fst::StateMapFst,
     state_sort::state_sort,
+    symbol_table_ops::{
+        merge_symbol_tables, prune_symbol_table, relabel_by_symbols, UnknownSymbolPolicy,
+    },
     top_sort::top_sort,
     tr_map::{tr_map, FinalTr, MapFinalAction, TrMapper},
+    tr_map_fst::{InvertFst, ProjectFst, RelabelFst, TrMapFst},
     tr_sort::tr_sort,
+    tr_sort_fst::TrSortFst,
     tr_sum::tr_sum,
     tr_unique::tr_unique,
     weight_convert::{weight_convert, WeightConverter},
@@ -31,11 +53,13 @@ pub use self::{
 
 mod add_super_final_state;
 mod all_pairs_shortest_distance;
+mod astar_shortest_path;
 pub mod closure;
 pub mod compose;
 pub mod concat;
 mod condense;
 mod connect;
+mod decoder;
 pub mod determinize;
 pub(crate) mod dfs_visit;
 pub mod encode;
@@ -44,10 +68,15 @@ mod fst_convert;
 mod inversion;
 mod isomorphic;
 mod minimize;
+mod nbest_strings;
+mod normalize;
 mod optimize;
 mod partition;
+pub mod pdt;
+pub(crate) mod preconditions;
 mod projection;
 mod push;
+mod push_fst;
 mod queue;
 mod relabel_pairs;
 pub mod replace;
@@ -57,10 +86,16 @@ pub mod rm_epsilon;
 mod rm_final_epsilon;
 mod shortest_distance;
 mod shortest_path;
+mod shortest_paths_iter;
+mod state_map;
+mod state_map_fst;
 mod state_sort;
+mod symbol_table_ops;
 mod top_sort;
 mod tr_map;
+mod tr_map_fst;
 mod tr_sort;
+mod tr_sort_fst;
 mod tr_sum;
 pub(crate) mod tr_unique;
 pub mod union;
@@ -75,6 +110,9 @@ pub mod tr_filters;
 /// Module that provides structures implementing the `TrMapper` trait.
 pub mod tr_mappers;
 
+/// Module that provides structures implementing the `StateMapper` trait.
+pub mod state_mappers;
+
 pub(crate) mod visitors;
 
 /// Module that provides structures implementing the `WeightConverter` trait.
@@ -0,0 +1,220 @@
+use anyhow::Result;
+
+use crate::algorithms::determinize::{determinize_with_config, DeterminizeConfig};
+use crate::algorithms::rm_epsilon::rm_epsilon;
+use crate::algorithms::{
+    fst_convert_from_ref, project, shortest_path_with_config, weight_convert, FinalTr,
+    MapFinalAction, ProjectType, ShortestPathConfig, WeightConverter,
+};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{
+    CostWeight, Semiring, Tropical64Weight, WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::{Error, Tr, KDELTA};
+
+/// Configuration of the n-best extraction of `nbest_strings`.
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub struct NBestStringsConfig {
+    nshortest: usize,
+    delta: f32,
+}
+
+impl Default for NBestStringsConfig {
+    fn default() -> Self {
+        Self {
+            nshortest: 1,
+            delta: KDELTA,
+        }
+    }
+}
+
+impl NBestStringsConfig {
+    pub fn new(nshortest: usize, delta: f32) -> Self {
+        Self { nshortest, delta }
+    }
+
+    pub fn with_nshortest(self, nshortest: usize) -> Self {
+        Self { nshortest, ..self }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+struct ToCostConverter {}
+
+impl<W: CostWeight> WeightConverter<W, Tropical64Weight> for ToCostConverter {
+    fn tr_map(&mut self, tr: &Tr<W>) -> Result<Tr<Tropical64Weight>> {
+        Ok(Tr::new(
+            tr.ilabel,
+            tr.olabel,
+            Tropical64Weight::new(tr.weight.to_cost()),
+            tr.nextstate,
+        ))
+    }
+
+    fn final_tr_map(&mut self, final_tr: &FinalTr<W>) -> Result<FinalTr<Tropical64Weight>> {
+        Ok(FinalTr {
+            ilabel: final_tr.ilabel,
+            olabel: final_tr.olabel,
+            weight: Tropical64Weight::new(final_tr.weight.to_cost()),
+        })
+    }
+
+    fn final_action(&self) -> MapFinalAction {
+        MapFinalAction::MapNoSuperfinal
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        inprops
+    }
+}
+
+struct FromCostConverter {}
+
+impl<W: CostWeight> WeightConverter<Tropical64Weight, W> for FromCostConverter {
+    fn tr_map(&mut self, tr: &Tr<Tropical64Weight>) -> Result<Tr<W>> {
+        Ok(Tr::new(
+            tr.ilabel,
+            tr.olabel,
+            W::from_cost(*tr.weight.value()),
+            tr.nextstate,
+        ))
+    }
+
+    fn final_tr_map(&mut self, final_tr: &FinalTr<Tropical64Weight>) -> Result<FinalTr<W>> {
+        Ok(FinalTr {
+            ilabel: final_tr.ilabel,
+            olabel: final_tr.olabel,
+            weight: W::from_cost(*final_tr.weight.value()),
+        })
+    }
+
+    fn final_action(&self) -> MapFinalAction {
+        MapFinalAction::MapNoSuperfinal
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        inprops
+    }
+}
+
+/// Computes the `nshortest` most probable distinct output strings of an FST, summing the
+/// weights of all the paths producing each of them. The result is an acceptor on the output
+/// strings.
+///
+/// The FST is projected on its output labels, its epsilons are removed and it is determinized
+/// before the n best paths are extracted. The semiring must therefore support the weak
+/// division and the quantization used by the determinization (`WeaklyDivisibleSemiring`,
+/// `WeightQuantize`, with the left semiring property), and be mapped to tropical costs with
+/// `CostWeight` to rank the strings. It doesn't need the path property, so `LogWeight` and
+/// `ProbabilityWeight` are supported. See `nbest_strings_with_config` to set the quantization
+/// delta.
+pub fn nbest_strings<W, F1, F2>(ifst: &F1, nshortest: usize) -> Result<F2, Error>
+where
+    W: CostWeight + WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    nbest_strings_with_config(
+        ifst,
+        NBestStringsConfig::default().with_nshortest(nshortest),
+    )
+}
+
+/// Computes the n most probable distinct output strings of an FST, with the sum of the weights
+/// of all the paths producing each of them. Unlike `shortest_path`, this works on semirings that
+/// don't have the path property like `LogWeight` or `ProbabilityWeight`.
+///
+/// The FST is projected on its output labels, its epsilons are removed and it is determinized,
+/// so that each string is read by a single path holding its total weight. The n best paths of
+/// this acceptor are then extracted according to the costs of the weights. The result is an
+/// acceptor on the output strings. As the determinization, this may not terminate if the
+/// projected FST doesn't have the twins property.
+pub fn nbest_strings_with_config<W, F1, F2>(
+    ifst: &F1,
+    config: NBestStringsConfig,
+) -> Result<F2, Error>
+where
+    W: CostWeight + WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    if config.nshortest == 0 {
+        return Ok(F2::new());
+    }
+    let mut fst: VectorFst<W> = fst_convert_from_ref(ifst);
+    project(&mut fst, ProjectType::ProjectOutput);
+    rm_epsilon(&mut fst)?;
+    let det_config = DeterminizeConfig::default().with_delta(config.delta);
+    let det_fst: VectorFst<W> = determinize_with_config(&fst, det_config)?;
+
+    let cost_fst: VectorFst<Tropical64Weight> = weight_convert(&det_fst, &mut ToCostConverter {})?;
+    let sp_config = ShortestPathConfig::default()
+        .with_nshortest(config.nshortest)
+        .with_delta(config.delta);
+    let best_fst: VectorFst<Tropical64Weight> = shortest_path_with_config(&cost_fst, sp_config)?;
+    let mut ofst: F2 = weight_convert(&best_fst, &mut FromCostConverter {})?;
+    ofst.set_symts_from_fst(ifst);
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::{LogWeight, ProbabilityWeight};
+    use crate::EPS_LABEL;
+
+    #[test]
+    fn test_nbest_strings_sums_paths() -> Result<()> {
+        // "a" is read by two paths of cost 1.0 each, "b" by a single path of cost 0.8.
+        let mut fst = VectorFst::<LogWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s2, LogWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, LogWeight::new(1.0), s2))?;
+        fst.add_tr(s0, Tr::new(2, 1, LogWeight::new(0.5), s1))?;
+        fst.add_tr(s1, Tr::new(3, EPS_LABEL, LogWeight::new(0.5), s2))?;
+        fst.add_tr(s0, Tr::new(4, 2, LogWeight::new(0.8), s2))?;
+
+        let res: VectorFst<LogWeight> = nbest_strings(&fst, 1)?;
+        let paths: Vec<_> = res.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].olabels, vec![1]);
+        let summed = LogWeight::new(1.0).plus(LogWeight::new(1.0))?;
+        assert!(paths[0].weight.approx_equal(summed, KDELTA));
+
+        let res: VectorFst<LogWeight> = nbest_strings(&fst, 3)?;
+        let mut paths: Vec<_> = res.paths_iter().map(|p| p.olabels).collect();
+        paths.sort();
+        assert_eq!(paths, vec![vec![1], vec![2]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_nbest_strings_probability() -> Result<()> {
+        let mut fst = VectorFst::<ProbabilityWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, ProbabilityWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, ProbabilityWeight::new(0.3), s1))?;
+        fst.add_tr(s0, Tr::new(2, 1, ProbabilityWeight::new(0.3), s1))?;
+        fst.add_tr(s0, Tr::new(3, 2, ProbabilityWeight::new(0.4), s1))?;
+
+        let res: VectorFst<ProbabilityWeight> = nbest_strings(&fst, 1)?;
+        let paths: Vec<_> = res.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].olabels, vec![1]);
+        assert!(paths[0]
+            .weight
+            .approx_equal(ProbabilityWeight::new(0.6), KDELTA));
+        Ok(())
+    }
+}
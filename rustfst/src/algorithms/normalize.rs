@@ -0,0 +1,136 @@
+use crate::algorithms::state_mappers::NormalizeMapper;
+use crate::algorithms::{push_weights_with_config, state_map, PushWeightsConfig, ReweightType};
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring};
+use crate::{Error, Trs};
+
+/// Turns the FST into a stochastic FST: at each state, the sum of the weights of the
+/// outgoing transitions and of the final weight is equal to `one()`.
+///
+/// - `ReweightType::ReweightToInitial` normalizes globally: the weights are pushed towards the
+///   initial state and the total weight is removed. The weight of each path is divided by the
+///   sum of the weights of all the paths, so a count-based FST becomes a probability
+///   distribution over its paths.
+/// - `ReweightType::ReweightToFinal` normalizes locally: the weights leaving each state are
+///   divided by their sum, independently of the other states.
+///
+/// The states from which no final state can be reached are left unchanged.
+pub fn normalize<W, F>(fst: &mut F, reweight_type: ReweightType) -> Result<(), Error>
+where
+    W: WeaklyDivisibleSemiring,
+    F: MutableFst<W>,
+{
+    match reweight_type {
+        ReweightType::ReweightToInitial => push_weights_with_config(
+            fst,
+            ReweightType::ReweightToInitial,
+            PushWeightsConfig::default().with_remove_total_weight(true),
+        ),
+        ReweightType::ReweightToFinal => state_map(fst, &NormalizeMapper {}),
+    }
+}
+
+/// Checks that the FST is stochastic: at each state, the sum of the weights of the outgoing
+/// transitions and of the final weight is equal to `one()`, up to `delta`.
+pub fn is_stochastic<W, F>(fst: &F, delta: f32) -> Result<bool, Error>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+{
+    for state in 0..fst.num_states() {
+        let mut sum = fst.final_weight(state)?.unwrap_or_else(W::zero);
+        for tr in fst.get_trs(state)?.trs() {
+            sum.plus_assign(&tr.weight)?;
+        }
+        if !sum.approx_equal(W::one(), delta) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{LogWeight, ProbabilityWeight, TropicalWeight};
+    use crate::{Tr, KDELTA};
+
+    // Two paths with counts 1 * 3 and 1 * 1.
+    fn build_counts<W: Semiring>(to_weight: impl Fn(f32) -> W) -> Result<VectorFst<W>> {
+        let mut fst = VectorFst::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 1, to_weight(1.0), s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, to_weight(1.0), s2))?;
+        fst.set_final(s1, to_weight(3.0))?;
+        fst.set_final(s2, to_weight(1.0))?;
+        Ok(fst)
+    }
+
+    fn expected<W: Semiring>(
+        to_weight: impl Fn(f32) -> W,
+        w1: f32,
+        w2: f32,
+    ) -> Result<VectorFst<W>> {
+        let mut fst = VectorFst::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 1, to_weight(w1), s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, to_weight(w2), s2))?;
+        fst.set_final(s1, W::one())?;
+        fst.set_final(s2, W::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_normalize_probability() -> Result<()> {
+        let to_weight = ProbabilityWeight::new;
+        let fst = build_counts(to_weight)?;
+        assert!(!is_stochastic(&fst, KDELTA)?);
+
+        let mut local = fst.clone();
+        normalize(&mut local, ReweightType::ReweightToFinal)?;
+        assert!(is_stochastic(&local, KDELTA)?);
+        assert_eq!(local, expected(to_weight, 0.5, 0.5)?);
+
+        let mut global = fst;
+        normalize(&mut global, ReweightType::ReweightToInitial)?;
+        assert!(is_stochastic(&global, KDELTA)?);
+        assert_eq!(global, expected(to_weight, 0.75, 0.25)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_log() -> Result<()> {
+        let to_weight = |p: f32| LogWeight::new(-p.ln());
+        let fst = build_counts(to_weight)?;
+        assert!(!is_stochastic(&fst, KDELTA)?);
+
+        let mut global = fst;
+        normalize(&mut global, ReweightType::ReweightToInitial)?;
+        assert!(is_stochastic(&global, 1e-4)?);
+        assert!(global.approx_equal(&expected(to_weight, 0.75, 0.25)?, 1e-4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_tropical() -> Result<()> {
+        let fst = build_counts(TropicalWeight::new)?;
+        assert!(!is_stochastic(&fst, KDELTA)?);
+
+        // The best transition of each state gets the weight one.
+        let mut local = fst;
+        normalize(&mut local, ReweightType::ReweightToFinal)?;
+        assert!(is_stochastic(&local, KDELTA)?);
+        assert_eq!(local, expected(TropicalWeight::new, 0.0, 0.0)?);
+        Ok(())
+    }
+}
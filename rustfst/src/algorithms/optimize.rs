@@ -1,134 +1,359 @@
+use crate::algorithms::determinize::{determinize_with_config, DeterminizeConfig, DeterminizeType};
 use crate::algorithms::encode::EncodeType;
-use crate::algorithms::encode::EncodeType::*;
 use crate::algorithms::*;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, MutableFst};
 use crate::semirings::{SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize};
-use crate::Semiring;
-use anyhow::Result;
+use crate::{Error, Semiring};
 
-pub fn optimize<
-    W: Semiring + WeaklyDivisibleSemiring + WeightQuantize,
-    F: MutableFst<W> + AllocableFst<W>,
->(
-    fst: &mut F,
-) -> Result<()>
-where
-    W::ReverseWeight: WeightQuantize,
-{
-    if fst.properties().contains(FstProperties::ACCEPTOR) {
-        optimize_acceptor(fst)
-    } else {
-        optimize_transducer(fst)
+/// Encoding applied to the FST before its determinization and minimization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptimizeEncodeType {
+    /// The encoding is chosen from the properties of the FST and of the semiring : the labels
+    /// of non-deterministic transducers are encoded, and so are the weights when the semiring
+    /// is idempotent and the FST has weighted cycles.
+    EncodeAuto,
+    /// The FST is never encoded. Transducers are determinized with the configured
+    /// determinization type.
+    EncodeNone,
+    /// The FST is always encoded with this encoding.
+    Encode(EncodeType),
+}
+
+/// Stage of the optimization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptimizeStage {
+    RmEpsilon,
+    TrSum,
+    Encode,
+    Determinize,
+    Minimize,
+    Decode,
+    PushWeights,
+}
+
+/// Size of the FST after a stage of the optimization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizeStageSize {
+    pub stage: OptimizeStage,
+    pub num_states: usize,
+    pub num_trs: usize,
+}
+
+impl OptimizeStageSize {
+    fn new<W: Semiring, F: MutableFst<W>>(stage: OptimizeStage, fst: &F) -> Self {
+        Self {
+            stage,
+            num_states: fst.num_states(),
+            num_trs: (0..fst.num_states())
+                .map(|s| unsafe { fst.num_trs_unchecked(s) })
+                .sum(),
+        }
     }
 }
 
-fn determinize<
-    W: Semiring + WeaklyDivisibleSemiring + WeightQuantize,
-    F: MutableFst<W> + AllocableFst<W>,
->(
-    fst: &mut F,
-) -> Result<()> {
-    *fst = determinize::determinize(fst)?;
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizeConfig {
+    rm_epsilon: bool,
+    tr_sum: bool,
+    encode_type: OptimizeEncodeType,
+    det_type: Option<DeterminizeType>,
+    minimize: bool,
+    push_weights: Option<ReweightType>,
+    safe: bool,
+}
+
+impl OptimizeConfig {
+    /// Whether to remove the epsilon transitions.
+    pub fn with_rm_epsilon(self, rm_epsilon: bool) -> Self {
+        Self { rm_epsilon, ..self }
+    }
+
+    /// Whether to sum the weights of the transitions with the same labels and destination.
+    pub fn with_tr_sum(self, tr_sum: bool) -> Self {
+        Self { tr_sum, ..self }
+    }
+
+    pub fn with_encode_type(self, encode_type: OptimizeEncodeType) -> Self {
+        Self {
+            encode_type,
+            ..self
+        }
+    }
+
+    /// Determinization type, `None` to skip the determinization.
+    pub fn with_det_type(self, det_type: Option<DeterminizeType>) -> Self {
+        Self { det_type, ..self }
+    }
+
+    pub fn with_minimize(self, minimize: bool) -> Self {
+        Self { minimize, ..self }
+    }
+
+    /// Direction in which the weights are pushed at the end, `None` to not push them.
+    pub fn with_push_weights(self, push_weights: Option<ReweightType>) -> Self {
+        Self {
+            push_weights,
+            ..self
+        }
+    }
+
+    /// In safe mode, the determinization is skipped when the FST is cyclic and not known to
+    /// be functional, i.e. neither an acceptor nor input deterministic, as it might not terminate.
+    /// An FST whose labels are encoded is an acceptor, so it is always determinized.
+    pub fn with_safe(self, safe: bool) -> Self {
+        Self { safe, ..self }
+    }
+}
+
+impl Default for OptimizeConfig {
+    fn default() -> Self {
+        Self {
+            rm_epsilon: true,
+            tr_sum: true,
+            encode_type: OptimizeEncodeType::EncodeAuto,
+            det_type: Some(DeterminizeType::DeterminizeFunctional),
+            minimize: true,
+            push_weights: None,
+            safe: false,
+        }
+    }
 }
 
-fn encode_deter_mini_decode<
+/// Optimizes an FST by removing its epsilon transitions, summing its transitions and then
+/// determinizing and minimizing it, encoding it beforehand when needed.
+pub fn optimize<W, F>(fst: &mut F) -> Result<(), Error>
+where
     W: Semiring + WeaklyDivisibleSemiring + WeightQuantize,
     F: MutableFst<W> + AllocableFst<W>,
->(
-    fst: &mut F,
-    encoder: EncodeType,
-) -> Result<()>
-where
     W::ReverseWeight: WeightQuantize,
 {
-    let table = encode::encode(fst, encoder)?;
-    determinize(fst)?;
-    minimize(fst)?;
-    encode::decode(fst, table)
+    optimize_with_config(fst, OptimizeConfig::default()).map(|_| ())
 }
 
-fn optimize_transducer<
-    W: Semiring + WeaklyDivisibleSemiring + WeightQuantize,
-    F: MutableFst<W> + AllocableFst<W>,
->(
+/// Optimizes an FST running the stages enabled in the config. Returns the size of the FST
+/// after each stage that has been run.
+///
+/// The determinization is skipped for already deterministic FSTs, and for cyclic FSTs over
+/// a non-idempotent semiring. The minimization requires a deterministic FST unless the
+/// semiring is idempotent.
+pub fn optimize_with_config<W, F>(
     fst: &mut F,
-) -> Result<()>
+    config: OptimizeConfig,
+) -> Result<Vec<OptimizeStageSize>, Error>
 where
+    W: Semiring + WeaklyDivisibleSemiring + WeightQuantize,
+    F: MutableFst<W> + AllocableFst<W>,
     W::ReverseWeight: WeightQuantize,
 {
-    if !fst.properties().contains(FstProperties::NO_EPSILONS) {
+    let mut sizes = vec![];
+
+    if config.rm_epsilon && !fst.properties().contains(FstProperties::NO_EPSILONS) {
         rm_epsilon::rm_epsilon(fst)?;
+        sizes.push(OptimizeStageSize::new(OptimizeStage::RmEpsilon, fst));
+    }
+
+    if config.tr_sum {
+        tr_sum(fst);
+        sizes.push(OptimizeStageSize::new(OptimizeStage::TrSum, fst));
     }
 
-    tr_sum(fst);
+    let props = fst.properties();
+    let idempotent = W::properties().contains(SemiringProperties::IDEMPOTENT);
+    let deterministic = props.contains(FstProperties::I_DETERMINISTIC);
 
-    if !W::properties().contains(SemiringProperties::IDEMPOTENT) {
-        if !fst.properties().contains(FstProperties::I_DETERMINISTIC) {
-            if fst.properties().contains(FstProperties::ACYCLIC) {
-                encode_deter_mini_decode(fst, EncodeLabels)?;
+    let mut run_determinize = config.det_type.is_some()
+        && !deterministic
+        && (idempotent || props.contains(FstProperties::ACYCLIC));
+    let mut run_minimize = config.minimize && (deterministic || run_determinize || idempotent);
+
+    let mut encode_type = if run_determinize || run_minimize {
+        match config.encode_type {
+            OptimizeEncodeType::EncodeAuto => {
+                let encode_weights = idempotent
+                    && !deterministic
+                    && !props.intersects(
+                        FstProperties::ACYCLIC
+                            | FstProperties::UNWEIGHTED
+                            | FstProperties::UNWEIGHTED_CYCLES,
+                    );
+                let encode_labels = !deterministic && !props.contains(FstProperties::ACCEPTOR);
+                if encode_weights || encode_labels {
+                    Some(EncodeType::from_bools(encode_weights, encode_labels)?)
+                } else {
+                    None
+                }
             }
-        } else {
-            minimize(fst)?;
+            OptimizeEncodeType::EncodeNone => None,
+            OptimizeEncodeType::Encode(encode_type) => Some(encode_type),
         }
     } else {
-        if !fst.properties().contains(FstProperties::I_DETERMINISTIC) {
-            if !fst.properties().intersects(
-                FstProperties::ACYCLIC
-                    | FstProperties::UNWEIGHTED
-                    | FstProperties::UNWEIGHTED_CYCLES,
-            ) {
-                encode_deter_mini_decode(fst, EncodeWeightsAndLabels)?;
-                tr_sum(fst);
-            } else {
-                encode_deter_mini_decode(fst, EncodeLabels)?;
+        None
+    };
+
+    // Once its labels are encoded, the FST is an acceptor and therefore functional.
+    let labels_encoded = encode_type.map_or(false, |e| e.encode_labels());
+    if run_determinize && config.safe && !labels_encoded {
+        let props = fst.compute_and_update_properties(
+            FstProperties::ACYCLIC | FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC,
+        )?;
+        if !props.contains(FstProperties::ACYCLIC)
+            && !props.intersects(FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC)
+        {
+            run_determinize = false;
+            run_minimize = config.minimize && (deterministic || idempotent);
+            if !run_minimize {
+                encode_type = None;
             }
-        } else {
-            minimize(fst)?;
         }
     }
-    Ok(())
-}
 
-fn optimize_acceptor<
-    W: Semiring + WeaklyDivisibleSemiring + WeightQuantize,
-    F: MutableFst<W> + AllocableFst<W>,
->(
-    fst: &mut F,
-) -> Result<()>
-where
-    W::ReverseWeight: WeightQuantize,
-{
-    if !fst.properties().contains(FstProperties::NO_EPSILONS) {
-        rm_epsilon::rm_epsilon(fst)?;
-    }
-    tr_sum(fst);
-    if !W::properties().contains(SemiringProperties::IDEMPOTENT) {
-        if !fst.properties().contains(FstProperties::I_DETERMINISTIC) {
-            if fst.properties().contains(FstProperties::ACYCLIC) {
-                determinize(fst)?;
-                minimize(fst)?;
-            }
-        } else {
-            minimize(fst)?;
+    let encode_table = match encode_type {
+        Some(encode_type) => {
+            let table = encode::encode(fst, encode_type)?;
+            sizes.push(OptimizeStageSize::new(OptimizeStage::Encode, fst));
+            Some(table)
         }
-    } else {
-        if !fst.properties().contains(FstProperties::I_DETERMINISTIC) {
-            if !fst.properties().intersects(
-                FstProperties::ACYCLIC
-                    | FstProperties::UNWEIGHTED
-                    | FstProperties::UNWEIGHTED_CYCLES,
-            ) {
-                encode_deter_mini_decode(fst, EncodeWeights)?;
-                tr_sum(fst)
-            } else {
-                determinize(fst)?;
-                minimize(fst)?;
-            }
-        } else {
-            minimize(fst)?;
+        None => None,
+    };
+
+    if run_determinize {
+        let det_config = DeterminizeConfig::default().with_det_type(config.det_type.unwrap());
+        *fst = determinize_with_config(fst, det_config)?;
+        sizes.push(OptimizeStageSize::new(OptimizeStage::Determinize, fst));
+    }
+
+    if run_minimize {
+        let min_config = MinimizeConfig::default().with_allow_nondet(idempotent);
+        minimize_with_config(fst, min_config)?;
+        sizes.push(OptimizeStageSize::new(OptimizeStage::Minimize, fst));
+    }
+
+    if let Some(table) = encode_table {
+        encode::decode(fst, table)?;
+        sizes.push(OptimizeStageSize::new(OptimizeStage::Decode, fst));
+
+        let weights_encoded = encode_type != Some(EncodeType::EncodeLabels);
+        if config.tr_sum && weights_encoded {
+            tr_sum(fst);
+            sizes.push(OptimizeStageSize::new(OptimizeStage::TrSum, fst));
         }
     }
-    Ok(())
+
+    if let Some(reweight_type) = config.push_weights {
+        push_weights(fst, reweight_type)?;
+        sizes.push(OptimizeStageSize::new(OptimizeStage::PushWeights, fst));
+    }
+
+    Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    // Cyclic transducer whose input "1" has the outputs "2" and "3".
+    fn build_non_functional() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 3, 2.0, 2))?;
+        fst.add_tr(1, Tr::new(4, 4, 1.0, 0))?;
+        fst.add_tr(2, Tr::new(4, 4, 1.0, 0))?;
+        fst.set_final(0, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_optimize_with_config_reports_stages() -> Result<()> {
+        let mut fst = build_non_functional()?;
+
+        let sizes = optimize_with_config(&mut fst, OptimizeConfig::default())?;
+
+        // The states 1 and 2 are merged once the labels and the weights are encoded.
+        let mut expected = VectorFst::<TropicalWeight>::new();
+        expected.add_states(2);
+        expected.set_start(0)?;
+        expected.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        expected.add_tr(0, Tr::new(1, 3, 2.0, 1))?;
+        expected.add_tr(1, Tr::new(4, 4, 1.0, 0))?;
+        expected.set_final(0, TropicalWeight::one())?;
+        assert!(isomorphic(&fst, &expected)?);
+
+        let stage_size = |stage, num_states, num_trs| OptimizeStageSize {
+            stage,
+            num_states,
+            num_trs,
+        };
+        // The final weight is encoded as a transition to a superfinal state.
+        assert_eq!(
+            sizes,
+            vec![
+                stage_size(OptimizeStage::TrSum, 3, 4),
+                stage_size(OptimizeStage::Encode, 4, 5),
+                stage_size(OptimizeStage::Determinize, 4, 5),
+                stage_size(OptimizeStage::Minimize, 3, 4),
+                stage_size(OptimizeStage::Decode, 2, 3),
+                stage_size(OptimizeStage::TrSum, 2, 3),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_with_config_safe() -> Result<()> {
+        let mut fst = build_non_functional()?;
+        let config = OptimizeConfig::default()
+            .with_encode_type(OptimizeEncodeType::EncodeNone)
+            .with_safe(true);
+
+        let sizes = optimize_with_config(&mut fst, config)?;
+
+        assert!(sizes.iter().all(|s| s.stage != OptimizeStage::Determinize));
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_with_config_safe_encoded_labels() -> Result<()> {
+        // Once its labels are encoded, the cyclic transducer is a functional acceptor.
+        let mut fst = build_non_functional()?;
+        let config = OptimizeConfig::default()
+            .with_encode_type(OptimizeEncodeType::Encode(EncodeType::EncodeLabels))
+            .with_safe(true);
+
+        let sizes = optimize_with_config(&mut fst, config)?;
+
+        let stages: Vec<_> = sizes.iter().map(|s| s.stage).collect();
+        assert!(stages.contains(&OptimizeStage::Encode));
+        assert!(stages.contains(&OptimizeStage::Determinize));
+
+        // Same with the automatic encoding.
+        let mut fst = build_non_functional()?;
+        let sizes = optimize_with_config(&mut fst, OptimizeConfig::default().with_safe(true))?;
+        assert!(sizes.iter().any(|s| s.stage == OptimizeStage::Determinize));
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_with_config_no_stage() -> Result<()> {
+        let mut fst = build_non_functional()?;
+        let config = OptimizeConfig::default()
+            .with_rm_epsilon(false)
+            .with_tr_sum(false)
+            .with_det_type(None)
+            .with_minimize(false);
+
+        let sizes = optimize_with_config(&mut fst, config)?;
+
+        assert!(sizes.is_empty());
+        assert_eq!(fst, build_non_functional()?);
+        Ok(())
+    }
 }
@@ -0,0 +1,108 @@
+use anyhow::Result;
+
+use crate::algorithms::compose::{compose_with_config, ComposeConfig};
+use crate::algorithms::pdt::Pdt;
+use crate::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
+use crate::algorithms::{fst_convert_from_ref, tr_sort};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Error, Label, Tr};
+
+/// Adds a self-loop labeled with each parenthesis at every state of `fst` so that the
+/// parentheses of the PDT are matched without moving in `fst`.
+fn add_paren_loops<W, F>(fst: &F, parens: &[(Label, Label)]) -> Result<VectorFst<W>>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+{
+    let mut ofst: VectorFst<W> = fst_convert_from_ref(fst);
+    for s in 0..ofst.num_states() {
+        for (open, close) in parens {
+            ofst.add_tr(s, Tr::new(*open, *open, W::one(), s))?;
+            ofst.add_tr(s, Tr::new(*close, *close, W::one(), s))?;
+        }
+    }
+    Ok(ofst)
+}
+
+/// Composes a PDT with an FST. The result is a PDT with the same parentheses. The output labels
+/// of the PDT are matched with the input labels of `fst` and the parentheses are left untouched,
+/// they must not be used as labels in `fst`. The matchers and the compose filter are chosen with
+/// `config`.
+pub fn compose<W, F1, F2, F3>(
+    pdt: &Pdt<W, F1>,
+    fst: &F2,
+    config: ComposeConfig,
+) -> Result<Pdt<W, F3>, Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    let mut fst = add_paren_loops(fst, &pdt.parens)?;
+    tr_sort(&mut fst, ILabelCompare {});
+    let res: F3 = compose_with_config::<_, F1, VectorFst<W>, _, _, _>(&pdt.fst, &fst, config)?;
+    Pdt::new(res, pdt.parens.clone())
+}
+
+/// Composes an FST with a PDT. The result is a PDT with the same parentheses. The output labels
+/// of `fst` are matched with the input labels of the PDT and the parentheses are left
+/// untouched, they must not be used as labels in `fst`.
+pub fn compose_fst_pdt<W, F1, F2, F3>(
+    fst: &F1,
+    pdt: &Pdt<W, F2>,
+    config: ComposeConfig,
+) -> Result<Pdt<W, F3>, Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    let mut fst = add_paren_loops(fst, &pdt.parens)?;
+    tr_sort(&mut fst, OLabelCompare {});
+    let res: F3 = compose_with_config::<_, VectorFst<W>, F2, _, _, _>(&fst, &pdt.fst, config)?;
+    Pdt::new(res, pdt.parens.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::transducer;
+
+    #[test]
+    fn test_pdt_compose_keeps_parens() -> Result<()> {
+        // a ( b ) where ( and ) are 10 and 11.
+        let mut pdt_fst: VectorFst<TropicalWeight> =
+            transducer(&[1, 10, 2, 11], &[1, 10, 2, 11], TropicalWeight::one());
+        tr_sort(&mut pdt_fst, OLabelCompare {});
+        let pdt = Pdt::new(pdt_fst, vec![(10, 11)])?;
+        let fst: VectorFst<TropicalWeight> = transducer(&[1, 2], &[3, 4], TropicalWeight::new(1.0));
+
+        let res: Pdt<_, VectorFst<_>> = compose(&pdt, &fst, ComposeConfig::default())?;
+        assert_eq!(res.parens, vec![(10, 11)]);
+
+        let paths: Vec<_> = res.fst.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![1, 10, 2, 11]);
+        assert_eq!(paths[0].olabels, vec![3, 10, 4, 11]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(1.0));
+
+        let mut fst_left: VectorFst<TropicalWeight> =
+            transducer(&[5, 6], &[1, 2], TropicalWeight::one());
+        tr_sort(&mut fst_left, OLabelCompare {});
+        let mut pdt_right = pdt.clone();
+        tr_sort(&mut pdt_right.fst, ILabelCompare {});
+        let res: Pdt<_, VectorFst<_>> =
+            compose_fst_pdt(&fst_left, &pdt_right, ComposeConfig::default())?;
+        let paths: Vec<_> = res.fst.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![5, 10, 6, 11]);
+        assert_eq!(paths[0].olabels, vec![1, 10, 2, 11]);
+        Ok(())
+    }
+}
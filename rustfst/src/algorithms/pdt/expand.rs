@@ -0,0 +1,217 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::algorithms::connect;
+use crate::algorithms::pdt::{Paren, ParenMap, Pdt};
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Error, StateId, Tr, Trs, EPS_LABEL};
+
+/// Configuration of the expansion of a PDT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdtExpandConfig {
+    /// Maximum number of open parentheses on the stack. The open parentheses that would exceed
+    /// it are not followed.
+    pub max_depth: usize,
+    /// Whether the parentheses are kept on the transitions or replaced by epsilons.
+    pub keep_parentheses: bool,
+    /// Whether the non accessible and non coaccessible states are removed.
+    pub connect: bool,
+}
+
+impl PdtExpandConfig {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            keep_parentheses: false,
+            connect: true,
+        }
+    }
+
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    pub fn with_keep_parentheses(self, keep_parentheses: bool) -> Self {
+        Self {
+            keep_parentheses,
+            ..self
+        }
+    }
+
+    pub fn with_connect(self, connect: bool) -> Self {
+        Self { connect, ..self }
+    }
+}
+
+/// Stacks of open parentheses, stored as a tree where each stack points to the stack below its
+/// top. The empty stack has id 0.
+struct ParenStacks {
+    // (parent, top paren, depth) of each stack.
+    stacks: Vec<(usize, usize, usize)>,
+    ids: HashMap<(usize, usize), usize>,
+}
+
+impl ParenStacks {
+    fn new() -> Self {
+        Self {
+            stacks: vec![(0, 0, 0)],
+            ids: HashMap::new(),
+        }
+    }
+
+    fn depth(&self, stack_id: usize) -> usize {
+        self.stacks[stack_id].2
+    }
+
+    fn top(&self, stack_id: usize) -> Option<usize> {
+        if stack_id == 0 {
+            None
+        } else {
+            Some(self.stacks[stack_id].1)
+        }
+    }
+
+    fn pop(&self, stack_id: usize) -> usize {
+        self.stacks[stack_id].0
+    }
+
+    fn push(&mut self, stack_id: usize, paren: usize) -> usize {
+        let depth = self.depth(stack_id) + 1;
+        let stacks = &mut self.stacks;
+        *self.ids.entry((stack_id, paren)).or_insert_with(|| {
+            stacks.push((stack_id, paren, depth));
+            stacks.len() - 1
+        })
+    }
+}
+
+/// Expands a PDT into an FST by keeping track of the stack of open parentheses in the states.
+/// Only the paths where the parentheses are balanced and where the stack never holds more than
+/// `config.max_depth` open parentheses are kept. The result is exact if the PDT accepts no path
+/// deeper than that, which is always the case if the PDT has no cyclic calls.
+pub fn expand<W, F1, F2>(pdt: &Pdt<W, F1>, config: PdtExpandConfig) -> Result<F2, Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let paren_map = ParenMap::new(&pdt.parens)?;
+    let ifst = &pdt.fst;
+    let mut ofst = F2::new();
+    ofst.set_symts_from_fst(ifst);
+    let start = match ifst.start() {
+        Some(s) => s,
+        None => return Ok(ofst),
+    };
+
+    let mut stacks = ParenStacks::new();
+    let mut states: HashMap<(StateId, usize), StateId> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let ostart = ofst.add_state();
+    ofst.set_start(ostart)?;
+    states.insert((start, 0), ostart);
+    queue.push_back((start, 0, ostart));
+
+    while let Some((s, stack_id, os)) = queue.pop_front() {
+        if stack_id == 0 {
+            if let Some(final_weight) = ifst.final_weight(s)? {
+                ofst.set_final(os, final_weight)?;
+            }
+        }
+        for tr in ifst.get_trs(s)?.trs() {
+            let (next_stack, is_paren) = match paren_map.get(tr.ilabel) {
+                None => (stack_id, false),
+                Some(Paren::Open(i)) => {
+                    if stacks.depth(stack_id) >= config.max_depth {
+                        continue;
+                    }
+                    (stacks.push(stack_id, i), true)
+                }
+                Some(Paren::Close(i)) => {
+                    if stacks.top(stack_id) != Some(i) {
+                        continue;
+                    }
+                    (stacks.pop(stack_id), true)
+                }
+            };
+            let nextstate = match states.entry((tr.nextstate, next_stack)) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    let n = ofst.add_state();
+                    e.insert(n);
+                    queue.push_back((tr.nextstate, next_stack, n));
+                    n
+                }
+            };
+            let (ilabel, olabel) = if is_paren && !config.keep_parentheses {
+                (EPS_LABEL, EPS_LABEL)
+            } else {
+                (tr.ilabel, tr.olabel)
+            };
+            ofst.add_tr(os, Tr::new(ilabel, olabel, tr.weight.clone(), nextstate))?;
+        }
+    }
+
+    if config.connect {
+        connect(&mut ofst)?;
+    }
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::TropicalWeight;
+
+    // a^n b^n with n >= 1 : S -> a ( S ) b | a b
+    fn anbn() -> Result<Pdt<TropicalWeight, VectorFst<TropicalWeight>>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s3, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(1.0), s1))?;
+        fst.add_tr(s1, Tr::new(10, 10, TropicalWeight::one(), s0))?;
+        fst.add_tr(s1, Tr::new(2, 2, TropicalWeight::new(1.0), s3))?;
+        // Return of the recursive call.
+        fst.add_tr(s3, Tr::new(11, 11, TropicalWeight::one(), s2))?;
+        fst.add_tr(s2, Tr::new(2, 2, TropicalWeight::new(1.0), s3))?;
+        Ok(Pdt::new(fst, vec![(10, 11)])?)
+    }
+
+    #[test]
+    fn test_pdt_expand_depth_bound() -> Result<()> {
+        let pdt = anbn()?;
+        let fst: VectorFst<TropicalWeight> = expand(&pdt, PdtExpandConfig::new(2))?;
+        let mut paths: Vec<_> = fst.paths_iter().map(|p| p.ilabels).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![vec![1, 1, 1, 2, 2, 2], vec![1, 1, 2, 2], vec![1, 2],]
+        );
+
+        // An unbalanced close parenthesis is never followed.
+        let fst: VectorFst<TropicalWeight> = expand(&pdt, PdtExpandConfig::new(0))?;
+        let paths: Vec<_> = fst.paths_iter().map(|p| p.ilabels).collect();
+        assert_eq!(paths, vec![vec![1, 2]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pdt_expand_keep_parentheses() -> Result<()> {
+        let pdt = anbn()?;
+        let config = PdtExpandConfig::new(1).with_keep_parentheses(true);
+        let fst: VectorFst<TropicalWeight> = expand(&pdt, config)?;
+        let mut paths: Vec<_> = fst.paths_iter().map(|p| p.ilabels).collect();
+        paths.sort();
+        assert_eq!(paths, vec![vec![1, 2], vec![1, 10, 1, 2, 11, 2]]);
+        Ok(())
+    }
+}
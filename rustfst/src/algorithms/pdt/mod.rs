@@ -0,0 +1,76 @@
+//! Pushdown transducers (PDT).
+//!
+//! A PDT is represented as an FST together with a list of parenthesis pairs. The transitions
+//! labeled with the parentheses must be balanced along a successful path, which makes it
+//! possible to represent context-free languages, for instance the grammars with cyclic
+//! dependencies that can't be expanded by `replace`. The parentheses are read on the input
+//! labels of the transitions.
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Error, Label, EPS_LABEL};
+
+pub use compose::{compose, compose_fst_pdt};
+pub use expand::{expand, PdtExpandConfig};
+pub use replace::replace;
+pub use shortest_path::shortest_path;
+
+mod compose;
+mod expand;
+mod replace;
+mod shortest_path;
+
+/// Pushdown transducer : an FST and its list of `(open, close)` parenthesis pairs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pdt<W: Semiring, F: Fst<W>> {
+    pub fst: F,
+    pub parens: Vec<(Label, Label)>,
+    w: PhantomData<W>,
+}
+
+impl<W: Semiring, F: Fst<W>> Pdt<W, F> {
+    /// Creates a PDT. The parentheses must be non-epsilon labels and can't be used twice.
+    pub fn new(fst: F, parens: Vec<(Label, Label)>) -> Result<Self, Error> {
+        ParenMap::new(&parens)?;
+        Ok(Self {
+            fst,
+            parens,
+            w: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Paren {
+    Open(usize),
+    Close(usize),
+}
+
+/// Maps the parenthesis labels to the index of their pair.
+#[derive(Debug, Clone)]
+pub(crate) struct ParenMap(HashMap<Label, Paren>);
+
+impl ParenMap {
+    pub(crate) fn new(parens: &[(Label, Label)]) -> Result<Self> {
+        let mut map = HashMap::with_capacity(2 * parens.len());
+        for (i, (open, close)) in parens.iter().enumerate() {
+            if *open == EPS_LABEL || *close == EPS_LABEL {
+                bail!("Pdt: Epsilon can't be used as a parenthesis")
+            }
+            if map.insert(*open, Paren::Open(i)).is_some()
+                || map.insert(*close, Paren::Close(i)).is_some()
+            {
+                bail!("Pdt: The parenthesis labels must be distinct")
+            }
+        }
+        Ok(Self(map))
+    }
+
+    pub(crate) fn get(&self, label: Label) -> Option<Paren> {
+        self.0.get(&label).cloned()
+    }
+}
@@ -0,0 +1,260 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+use crate::algorithms::pdt::Pdt;
+use crate::algorithms::replace::utils::{epsilon_on_input, epsilon_on_output};
+use crate::algorithms::replace::{ReplaceFstOptions, ReplaceLabelType};
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Error, Label, StateId, Tr, Trs, EPS_LABEL};
+
+/// Converts a grammar given as a list of `(non-terminal, FST)` pairs into a pushdown
+/// transducer instead of expanding it. This works for any grammar, including the ones that are
+/// not regular.
+///
+/// Each rule is copied once in the output FST. Each call of a non-terminal is replaced by a
+/// transition labeled with an open parenthesis going to the start of the called rule, and by
+/// transitions labeled with the matching close parenthesis going from its final states to the
+/// destination of the call. Only the final states of the root rule are final. The labels of the
+/// call and return transitions are set as in `ReplaceFst` according to `opts`, on extra
+/// transitions before the open parenthesis and after the close parenthesis.
+///
+/// The parentheses are numbered from `start_paren_label`, or from the largest label of the
+/// rules + 1 if it is `None`.
+pub fn replace<W, F1, F2, B>(
+    fst_list: Vec<(Label, B)>,
+    opts: &ReplaceFstOptions,
+    start_paren_label: Option<Label>,
+) -> Result<Pdt<W, F2>, Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+    B: Borrow<F1>,
+{
+    let call_label_type = if opts.call_output_label == Some(EPS_LABEL) {
+        ReplaceLabelType::Neither
+    } else {
+        opts.call_label_type
+    };
+    let return_label_type = if opts.return_label == EPS_LABEL {
+        ReplaceLabelType::Neither
+    } else {
+        opts.return_label_type
+    };
+
+    let nonterminals: HashMap<Label, usize> = fst_list
+        .iter()
+        .enumerate()
+        .map(|(i, (label, _))| (*label, i))
+        .collect();
+    let root_idx = match nonterminals.get(&opts.root) {
+        Some(idx) => *idx,
+        None => {
+            return Err(Error::Other(format_err!(
+                "PdtReplace: No FST corresponding to root label {} in the input tuple vector",
+                opts.root
+            )))
+        }
+    };
+
+    let mut offsets = Vec::with_capacity(fst_list.len());
+    let mut num_states = 0;
+    let mut max_label = 0;
+    for (label, fst) in fst_list.iter() {
+        let fst = fst.borrow();
+        offsets.push(num_states);
+        num_states += fst.num_states();
+        max_label = max_label.max(*label);
+        for s in 0..fst.num_states() {
+            for tr in fst.get_trs(s)?.trs() {
+                max_label = max_label.max(tr.ilabel).max(tr.olabel);
+            }
+        }
+    }
+    let mut next_paren = start_paren_label.unwrap_or(max_label + 1);
+
+    let mut ofst = F2::new();
+    // The symbol tables are the ones of the root rule.
+    let root_fst: &F1 = fst_list[root_idx].1.borrow();
+    if let Some(symt) = root_fst.input_symbols() {
+        ofst.set_input_symbols(symt.clone());
+    }
+    if let Some(symt) = root_fst.output_symbols() {
+        ofst.set_output_symbols(symt.clone());
+    }
+    let root_start = match root_fst.start() {
+        Some(s) => s,
+        None => return Pdt::new(ofst, vec![]),
+    };
+    ofst.add_states(num_states);
+    ofst.set_start(offsets[root_idx] + root_start)?;
+
+    let return_ilabel = if epsilon_on_input(return_label_type) {
+        EPS_LABEL
+    } else {
+        opts.return_label
+    };
+    let return_olabel = if epsilon_on_output(return_label_type) {
+        EPS_LABEL
+    } else {
+        opts.return_label
+    };
+
+    let mut parens = vec![];
+    for (i, (_, fst)) in fst_list.iter().enumerate() {
+        let fst = fst.borrow();
+        for s in 0..fst.num_states() {
+            let state = offsets[i] + s;
+            if i == root_idx {
+                if let Some(final_weight) = fst.final_weight(s)? {
+                    ofst.set_final(state, final_weight)?;
+                }
+            }
+            for tr in fst.get_trs(s)?.trs() {
+                let callee = if tr.olabel == EPS_LABEL {
+                    None
+                } else {
+                    nonterminals.get(&tr.olabel).cloned()
+                };
+                let j = match callee {
+                    Some(j) => j,
+                    None => {
+                        let mut new_tr = tr.clone();
+                        new_tr.nextstate += offsets[i];
+                        ofst.add_tr(state, new_tr)?;
+                        continue;
+                    }
+                };
+                let callee_fst = fst_list[j].1.borrow();
+                let callee_start = match callee_fst.start() {
+                    Some(s) => s,
+                    // Nothing can be read from an empty rule.
+                    None => continue,
+                };
+                let (open_paren, close_paren) = (next_paren, next_paren + 1);
+                next_paren += 2;
+                parens.push((open_paren, close_paren));
+
+                let call_ilabel = if epsilon_on_input(call_label_type) {
+                    EPS_LABEL
+                } else {
+                    tr.ilabel
+                };
+                let call_olabel = if epsilon_on_output(call_label_type) {
+                    EPS_LABEL
+                } else {
+                    opts.call_output_label.unwrap_or(tr.olabel)
+                };
+                let callee_start = offsets[j] + callee_start;
+                if call_ilabel == EPS_LABEL && call_olabel == EPS_LABEL {
+                    ofst.add_tr(
+                        state,
+                        Tr::new(open_paren, open_paren, tr.weight.clone(), callee_start),
+                    )?;
+                } else {
+                    let call_state = ofst.add_state();
+                    ofst.add_tr(
+                        state,
+                        Tr::new(call_ilabel, call_olabel, tr.weight.clone(), call_state),
+                    )?;
+                    ofst.add_tr(
+                        call_state,
+                        Tr::new(open_paren, open_paren, W::one(), callee_start),
+                    )?;
+                }
+
+                let mut return_state: StateId = offsets[i] + tr.nextstate;
+                if return_ilabel != EPS_LABEL || return_olabel != EPS_LABEL {
+                    let close_state = ofst.add_state();
+                    ofst.add_tr(
+                        close_state,
+                        Tr::new(return_ilabel, return_olabel, W::one(), return_state),
+                    )?;
+                    return_state = close_state;
+                }
+                for f in 0..callee_fst.num_states() {
+                    if let Some(final_weight) = callee_fst.final_weight(f)? {
+                        ofst.add_tr(
+                            offsets[j] + f,
+                            Tr::new(close_paren, close_paren, final_weight, return_state),
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    Pdt::new(ofst, parens)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::transducer;
+    use crate::SymbolTable;
+    use std::sync::Arc;
+
+    fn symt(symbol: &str) -> Arc<SymbolTable> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbol(symbol);
+        Arc::new(symt)
+    }
+
+    #[test]
+    fn test_pdt_replace_call_labels() -> Result<()> {
+        // 1 : a 2, 2 : b
+        let root: VectorFst<TropicalWeight> = transducer(&[10, 2], &[10, 2], TropicalWeight::one());
+        let rule: VectorFst<TropicalWeight> = transducer(&[11], &[11], TropicalWeight::one());
+
+        let mut opts = ReplaceFstOptions::new(1, false);
+        opts.return_label_type = ReplaceLabelType::Both;
+        opts.return_label = 20;
+        let pdt: Pdt<_, VectorFst<_>> =
+            replace::<_, VectorFst<_>, _, _>(vec![(1, root), (2, rule)], &opts, Some(30))?;
+
+        assert_eq!(pdt.parens, vec![(30, 31)]);
+        // One extra state for the call label and one for the return label.
+        assert_eq!(pdt.fst.num_states(), 3 + 2 + 2);
+
+        // The call keeps its input label before the open parenthesis.
+        let call_trs = pdt.fst.get_trs(1)?;
+        assert_eq!(call_trs.trs().len(), 1);
+        let call_tr = &call_trs.trs()[0];
+        assert_eq!((call_tr.ilabel, call_tr.olabel), (2, EPS_LABEL));
+        let open_tr = &pdt.fst.get_trs(call_tr.nextstate)?.trs()[0];
+        assert_eq!((open_tr.ilabel, open_tr.nextstate), (30, 3));
+
+        // The return label is read after the close parenthesis.
+        let close_tr = &pdt.fst.get_trs(4)?.trs()[0];
+        assert_eq!(close_tr.ilabel, 31);
+        let return_tr = &pdt.fst.get_trs(close_tr.nextstate)?.trs()[0];
+        assert_eq!((return_tr.ilabel, return_tr.olabel), (20, 20));
+        assert_eq!(return_tr.nextstate, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pdt_replace_root_symbol_tables() -> Result<()> {
+        let mut rule: VectorFst<TropicalWeight> = transducer(&[11], &[11], TropicalWeight::one());
+        rule.set_input_symbols(symt("a"));
+        let mut root: VectorFst<TropicalWeight> =
+            transducer(&[10, 2], &[10, 2], TropicalWeight::one());
+        root.set_input_symbols(symt("b"));
+        root.set_output_symbols(symt("c"));
+
+        // The root rule is not the first one of the list.
+        let opts = ReplaceFstOptions::new(1, false);
+        let pdt: Pdt<_, VectorFst<_>> =
+            replace::<_, VectorFst<_>, _, _>(vec![(2, rule), (1, root)], &opts, None)?;
+
+        assert_eq!(pdt.fst.input_symbols(), Some(&symt("b")));
+        assert_eq!(pdt.fst.output_symbols(), Some(&symt("c")));
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::pdt::{Paren, ParenMap, Pdt};
+use crate::algorithms::preconditions::check_semiring_properties;
+use crate::algorithms::shortest_path::natural_less;
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{Error, StateId, Tr, Trs};
+
+/// A state of the search : the entry state of the current call and a state reached from it with
+/// balanced parentheses.
+type Key = (StateId, StateId);
+
+#[derive(Debug, Clone, Copy)]
+enum Back {
+    Start,
+    Tr {
+        prev: Key,
+        tr_idx: usize,
+    },
+    Call {
+        prev: Key,
+        open_tr_idx: usize,
+        close_state: StateId,
+        close_tr_idx: usize,
+    },
+}
+
+struct PdtShortestPath<'a, W: Semiring, F: ExpandedFst<W>> {
+    fst: &'a F,
+    paren_map: ParenMap,
+    dist: HashMap<Key, (W, Back)>,
+    queue: VecDeque<Key>,
+    enqueued: HashSet<Key>,
+    // Open parentheses reaching each entry : (caller key, paren, open transition).
+    callers: HashMap<StateId, Vec<(Key, usize, usize)>>,
+    // Close parentheses leaving each entry : (paren, close state, close transition).
+    exits: HashMap<StateId, Vec<(usize, StateId, usize)>>,
+}
+
+impl<'a, W: Semiring, F: ExpandedFst<W>> PdtShortestPath<'a, W, F> {
+    fn relax(&mut self, key: Key, weight: W, back: Back) -> Result<()> {
+        match self.dist.entry(key) {
+            Entry::Occupied(mut e) => {
+                if !natural_less(&weight, &e.get().0)? {
+                    return Ok(());
+                }
+                e.insert((weight, back));
+            }
+            Entry::Vacant(e) => {
+                e.insert((weight, back));
+            }
+        };
+        if self.enqueued.insert(key) {
+            self.queue.push_back(key);
+        }
+        Ok(())
+    }
+
+    fn tr(&self, state: StateId, tr_idx: usize) -> Result<Tr<W>> {
+        Ok(self.fst.get_trs(state)?.trs()[tr_idx].clone())
+    }
+
+    /// Relaxes the state following a call : `caller` reads the open parenthesis `open_tr_idx`,
+    /// reaches `close_state` with balanced parentheses and reads the close parenthesis.
+    fn relax_call(
+        &mut self,
+        caller: Key,
+        open_tr_idx: usize,
+        close_state: StateId,
+        close_tr_idx: usize,
+    ) -> Result<()> {
+        let open_tr = self.tr(caller.1, open_tr_idx)?;
+        let close_tr = self.tr(close_state, close_tr_idx)?;
+        let (caller_weight, inner_weight) = match (
+            self.dist.get(&caller),
+            self.dist.get(&(open_tr.nextstate, close_state)),
+        ) {
+            (Some(c), Some(i)) => (c.0.clone(), i.0.clone()),
+            _ => return Ok(()),
+        };
+        let weight = caller_weight
+            .times(&open_tr.weight)?
+            .times(&inner_weight)?
+            .times(&close_tr.weight)?;
+        self.relax(
+            (caller.0, close_tr.nextstate),
+            weight,
+            Back::Call {
+                prev: caller,
+                open_tr_idx,
+                close_state,
+                close_tr_idx,
+            },
+        )
+    }
+
+    fn process(&mut self, key: Key) -> Result<()> {
+        let (entry, state) = key;
+        let weight = self.dist[&key].0.clone();
+        let trs = self.fst.get_trs(state)?;
+        for (tr_idx, tr) in trs.trs().iter().enumerate() {
+            match self.paren_map.get(tr.ilabel) {
+                None => self.relax(
+                    (entry, tr.nextstate),
+                    weight.times(&tr.weight)?,
+                    Back::Tr { prev: key, tr_idx },
+                )?,
+                Some(Paren::Open(paren)) => {
+                    let callee = tr.nextstate;
+                    let callers = self.callers.entry(callee).or_default();
+                    if !callers.contains(&(key, paren, tr_idx)) {
+                        callers.push((key, paren, tr_idx));
+                    }
+                    self.relax((callee, callee), W::one(), Back::Start)?;
+                    let exits = self.exits.get(&callee).cloned().unwrap_or_default();
+                    for (exit_paren, close_state, close_tr_idx) in exits {
+                        if exit_paren == paren {
+                            self.relax_call(key, tr_idx, close_state, close_tr_idx)?;
+                        }
+                    }
+                }
+                Some(Paren::Close(paren)) => {
+                    let exits = self.exits.entry(entry).or_default();
+                    if !exits.contains(&(paren, state, tr_idx)) {
+                        exits.push((paren, state, tr_idx));
+                    }
+                    let callers = self.callers.get(&entry).cloned().unwrap_or_default();
+                    for (caller, caller_paren, open_tr_idx) in callers {
+                        if caller_paren == paren {
+                            self.relax_call(caller, open_tr_idx, state, tr_idx)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the transitions of the best path reaching `key`, in order.
+    fn path(&self, key: Key) -> Result<Vec<Tr<W>>> {
+        enum Item {
+            Key(Key),
+            Tr(StateId, usize),
+        }
+        let mut trs = vec![];
+        let mut stack = vec![Item::Key(key)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Item::Tr(state, tr_idx) => trs.push(self.tr(state, tr_idx)?),
+                Item::Key(key) => match self.dist[&key].1 {
+                    Back::Start => {}
+                    Back::Tr { prev, tr_idx } => {
+                        stack.push(Item::Key(prev));
+                        stack.push(Item::Tr(prev.1, tr_idx));
+                    }
+                    Back::Call {
+                        prev,
+                        open_tr_idx,
+                        close_state,
+                        close_tr_idx,
+                    } => {
+                        let callee = self.tr(prev.1, open_tr_idx)?.nextstate;
+                        stack.push(Item::Key(prev));
+                        stack.push(Item::Tr(prev.1, open_tr_idx));
+                        stack.push(Item::Key((callee, close_state)));
+                        stack.push(Item::Tr(close_state, close_tr_idx));
+                    }
+                },
+            }
+        }
+        trs.reverse();
+        Ok(trs)
+    }
+}
+
+/// Computes the shortest balanced path of a PDT : the best path from the start state to a final
+/// state along which the parentheses are balanced. The result is a linear FST which keeps the
+/// parentheses on its transitions, it has no state if the PDT has no balanced successful path.
+///
+/// The semiring must have the path property.
+pub fn shortest_path<W, F1, F2>(pdt: &Pdt<W, F1>) -> Result<F2, Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    check_semiring_properties::<W>("pdt_shortest_path", SemiringProperties::PATH)?;
+    let mut ofst = F2::new();
+    ofst.set_symts_from_fst(&pdt.fst);
+    let start = match pdt.fst.start() {
+        Some(s) => s,
+        None => return Ok(ofst),
+    };
+
+    let mut sp = PdtShortestPath {
+        fst: &pdt.fst,
+        paren_map: ParenMap::new(&pdt.parens)?,
+        dist: HashMap::new(),
+        queue: VecDeque::new(),
+        enqueued: HashSet::new(),
+        callers: HashMap::new(),
+        exits: HashMap::new(),
+    };
+    sp.relax((start, start), W::one(), Back::Start)?;
+    while let Some(key) = sp.queue.pop_front() {
+        sp.enqueued.remove(&key);
+        sp.process(key)?;
+    }
+
+    let mut best: Option<(StateId, W)> = None;
+    for s in 0..pdt.fst.num_states() {
+        if let (Some(final_weight), Some((weight, _))) =
+            (pdt.fst.final_weight(s)?, sp.dist.get(&(start, s)))
+        {
+            let weight = weight.times(final_weight)?;
+            let is_better = match &best {
+                Some((_, best_weight)) => natural_less(&weight, best_weight)?,
+                None => true,
+            };
+            if is_better {
+                best = Some((s, weight));
+            }
+        }
+    }
+    let final_state = match best {
+        Some((s, _)) => s,
+        None => return Ok(ofst),
+    };
+
+    let trs = sp.path((start, final_state))?;
+    let mut state = ofst.add_state();
+    ofst.set_start(state)?;
+    for tr in trs {
+        let nextstate = ofst.add_state();
+        ofst.add_tr(state, Tr::new(tr.ilabel, tr.olabel, tr.weight, nextstate))?;
+        state = nextstate;
+    }
+    ofst.set_final(state, pdt.fst.final_weight(final_state)?.unwrap())?;
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::{LogWeight, TropicalWeight};
+
+    #[test]
+    fn test_pdt_shortest_path_balanced() -> Result<()> {
+        // The cheapest path "a (" has no matching close parenthesis.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        let s4 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s4, TropicalWeight::new(0.5))?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(1.0), s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, TropicalWeight::new(5.0), s4))?;
+        fst.add_tr(s1, Tr::new(10, 10, TropicalWeight::new(0.0), s2))?;
+        fst.add_tr(s2, Tr::new(3, 3, TropicalWeight::new(1.0), s3))?;
+        fst.add_tr(s3, Tr::new(13, 13, TropicalWeight::new(0.0), s4))?;
+        fst.add_tr(s3, Tr::new(11, 11, TropicalWeight::new(2.0), s4))?;
+        let pdt = Pdt::new(fst, vec![(10, 11), (12, 13)])?;
+
+        let res: VectorFst<TropicalWeight> = shortest_path(&pdt)?;
+        let paths: Vec<_> = res.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![1, 10, 3, 11]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(4.5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pdt_shortest_path_recursive() -> Result<()> {
+        // S -> a ( S ) b | c, where each recursion level costs 1.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        let s3 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s3, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(1.0), s1))?;
+        fst.add_tr(s1, Tr::new(10, 10, TropicalWeight::one(), s0))?;
+        fst.add_tr(s0, Tr::new(3, 3, TropicalWeight::new(10.0), s3))?;
+        fst.add_tr(s3, Tr::new(11, 11, TropicalWeight::one(), s2))?;
+        fst.add_tr(s2, Tr::new(2, 2, TropicalWeight::one(), s3))?;
+        let pdt = Pdt::new(fst, vec![(10, 11)])?;
+
+        let res: VectorFst<TropicalWeight> = shortest_path(&pdt)?;
+        let paths: Vec<_> = res.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![3]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(10.0));
+
+        // The rule is called both by itself and by a root rule : R -> ( S ).
+        let mut fst = pdt.fst.clone();
+        fst.delete_final_weight(s3)?;
+        let s4 = fst.add_state();
+        let s5 = fst.add_state();
+        fst.set_start(s4)?;
+        fst.set_final(s5, TropicalWeight::one())?;
+        fst.add_tr(s4, Tr::new(12, 12, TropicalWeight::one(), s0))?;
+        fst.add_tr(s3, Tr::new(13, 13, TropicalWeight::one(), s5))?;
+        let pdt = Pdt::new(fst, vec![(10, 11), (12, 13)])?;
+
+        let res: VectorFst<TropicalWeight> = shortest_path(&pdt)?;
+        let paths: Vec<_> = res.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].ilabels, vec![12, 3, 13]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(10.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pdt_shortest_path_requires_path_property() -> Result<()> {
+        let pdt = Pdt::new(VectorFst::<LogWeight>::new(), vec![])?;
+        assert!(shortest_path::<_, _, VectorFst<LogWeight>>(&pdt).is_err());
+        Ok(())
+    }
+}
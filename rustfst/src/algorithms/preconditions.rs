@@ -0,0 +1,149 @@
+use std::fmt;
+
+use crate::fst_properties::{known_properties, FstProperties};
+use crate::semirings::{Semiring, SemiringProperties};
+
+/// Error returned when the input of an algorithm doesn't have the properties it requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreconditionError {
+    /// The FST is known not to have the `missing` properties.
+    MissingFstProperties {
+        algorithm: &'static str,
+        missing: FstProperties,
+    },
+    /// It is not known whether the FST has the `unknown` properties. They can be computed
+    /// beforehand with `compute_and_update_properties`.
+    UnknownFstProperties {
+        algorithm: &'static str,
+        unknown: FstProperties,
+    },
+    /// The semiring doesn't have the `missing` properties.
+    MissingSemiringProperties {
+        algorithm: &'static str,
+        missing: SemiringProperties,
+    },
+}
+
+impl PreconditionError {
+    /// Name of the algorithm whose precondition is not satisfied.
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            PreconditionError::MissingFstProperties { algorithm, .. } => *algorithm,
+            PreconditionError::UnknownFstProperties { algorithm, .. } => *algorithm,
+            PreconditionError::MissingSemiringProperties { algorithm, .. } => *algorithm,
+        }
+    }
+}
+
+impl fmt::Display for PreconditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreconditionError::MissingFstProperties { algorithm, missing } => write!(
+                f,
+                "{} : the FST doesn't have the required properties {:?}",
+                algorithm, missing
+            ),
+            PreconditionError::UnknownFstProperties { algorithm, unknown } => write!(
+                f,
+                "{} : the required properties {:?} of the FST are not known",
+                algorithm, unknown
+            ),
+            PreconditionError::MissingSemiringProperties { algorithm, missing } => write!(
+                f,
+                "{} : the semiring doesn't have the required properties {:?}",
+                algorithm, missing
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PreconditionError {}
+
+/// Checks that `props`, the properties of an FST, contain all the `required` ones.
+pub(crate) fn check_fst_properties(
+    algorithm: &'static str,
+    props: FstProperties,
+    required: FstProperties,
+) -> Result<(), PreconditionError> {
+    let missing = required & !props;
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let known_false = missing & known_properties(props);
+    if known_false.is_empty() {
+        Err(PreconditionError::UnknownFstProperties {
+            algorithm,
+            unknown: missing,
+        })
+    } else {
+        Err(PreconditionError::MissingFstProperties {
+            algorithm,
+            missing: known_false,
+        })
+    }
+}
+
+/// Checks that the semiring `W` has all the `required` properties.
+pub(crate) fn check_semiring_properties<W: Semiring>(
+    algorithm: &'static str,
+    required: SemiringProperties,
+) -> Result<(), PreconditionError> {
+    let missing = required & !W::properties();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(PreconditionError::MissingSemiringProperties { algorithm, missing })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::semirings::{LogWeight, TropicalWeight};
+
+    use super::*;
+
+    #[test]
+    fn test_check_fst_properties() {
+        let props = FstProperties::ACCEPTOR | FstProperties::NOT_I_DETERMINISTIC;
+        assert_eq!(
+            check_fst_properties("test", props, FstProperties::ACCEPTOR),
+            Ok(())
+        );
+        assert_eq!(
+            check_fst_properties(
+                "test",
+                props,
+                FstProperties::ACCEPTOR | FstProperties::I_DETERMINISTIC
+            ),
+            Err(PreconditionError::MissingFstProperties {
+                algorithm: "test",
+                missing: FstProperties::I_DETERMINISTIC
+            })
+        );
+        assert_eq!(
+            check_fst_properties("test", props, FstProperties::ACYCLIC),
+            Err(PreconditionError::UnknownFstProperties {
+                algorithm: "test",
+                unknown: FstProperties::ACYCLIC
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_semiring_properties() {
+        assert_eq!(
+            check_semiring_properties::<TropicalWeight>("test", SemiringProperties::PATH),
+            Ok(())
+        );
+        assert_eq!(
+            check_semiring_properties::<LogWeight>(
+                "test",
+                SemiringProperties::PATH | SemiringProperties::SEMIRING
+            ),
+            Err(PreconditionError::MissingSemiringProperties {
+                algorithm: "test",
+                missing: SemiringProperties::PATH
+            })
+        );
+    }
+}
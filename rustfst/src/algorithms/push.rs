@@ -12,13 +12,13 @@ use crate::algorithms::{
     ShortestDistanceConfig,
 };
 use crate::fst_impls::VectorFst;
-use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, MutableFst};
 use crate::semirings::{DivideType, Semiring};
 use crate::semirings::{
     GallicWeightLeft, GallicWeightRight, StringWeightLeft, StringWeightRight,
     WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::KDELTA;
+use crate::{Error, KDELTA};
 
 bitflags! {
     /// Configuration to control the behaviour of the pushing algorithm.
@@ -65,7 +65,7 @@ impl PushWeightsConfig {
     }
 }
 
-pub fn push_weights<W, F>(fst: &mut F, reweight_type: ReweightType) -> Result<()>
+pub fn push_weights<W, F>(fst: &mut F, reweight_type: ReweightType) -> Result<(), Error>
 where
     F: MutableFst<W>,
     W: WeaklyDivisibleSemiring,
@@ -82,7 +82,7 @@ pub fn push_weights_with_config<W, F>(
     fst: &mut F,
     reweight_type: ReweightType,
     config: PushWeightsConfig,
-) -> Result<()>
+) -> Result<(), Error>
 where
     F: MutableFst<W>,
     W: WeaklyDivisibleSemiring,
@@ -110,10 +110,10 @@ where
     Ok(())
 }
 
-fn compute_total_weight<W, F>(fst: &F, dist: &[W], reverse: bool) -> Result<W>
+pub(crate) fn compute_total_weight<W, F>(fst: &F, dist: &[W], reverse: bool) -> Result<W>
 where
     W: Semiring,
-    F: ExpandedFst<W>,
+    F: CoreFst<W>,
 {
     if reverse {
         if let Some(start) = fst.start() {
@@ -229,7 +229,7 @@ macro_rules! m_labels_pushing {
 
 #[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
 pub struct PushConfig {
-    delta: f32,
+    pub(crate) delta: f32,
 }
 
 impl Default for PushConfig {
@@ -248,7 +248,11 @@ impl PushConfig {
     }
 }
 
-pub fn push<W, F1, F2>(ifst: &F1, reweight_type: ReweightType, push_type: PushType) -> Result<F2>
+pub fn push<W, F1, F2>(
+    ifst: &F1,
+    reweight_type: ReweightType,
+    push_type: PushType,
+) -> Result<F2, Error>
 where
     F1: ExpandedFst<W>,
     F2: ExpandedFst<W> + MutableFst<W> + AllocableFst<W>,
@@ -265,7 +269,7 @@ pub fn push_with_config<W, F1, F2>(
     reweight_type: ReweightType,
     push_type: PushType,
     config: PushConfig,
-) -> Result<F2>
+) -> Result<F2, Error>
 where
     F1: ExpandedFst<W>,
     F2: ExpandedFst<W> + MutableFst<W> + AllocableFst<W>,
@@ -0,0 +1,1180 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+use unsafe_unwrap::UnsafeUnwrap;
+
+use crate::algorithms::factor_weight::factor_iterators::{GallicFactorLeft, GallicFactorRight};
+use crate::algorithms::factor_weight::FactorIterator;
+use crate::algorithms::lazy::{FstOp, LazyFst, SimpleHashMapCache, StateTable};
+use crate::algorithms::push::compute_total_weight;
+use crate::algorithms::queues::FifoQueue;
+use crate::algorithms::shortest_distance::ShortestDistanceState;
+use crate::algorithms::tr_filters::AnyTrFilter;
+use crate::algorithms::tr_mappers::RmWeightMapper;
+use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
+use crate::algorithms::{FinalTr, PushConfig, PushType, ReweightType, WeightConverter};
+use crate::fst_properties::mutable_properties::reweight_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::{
+    DivideType, GallicWeightLeft, GallicWeightRight, ReverseBack, Semiring, StringWeightLeft,
+    StringWeightRight, WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::{Error, StateId, SymbolTable, Tr, Trs, TrsVec, EPS_LABEL};
+
+/// View of an FST whose transitions and final weights are converted when they are read, used
+/// to compute the potentials without copying the FST.
+struct WeightView<'a, W, G, F, C, CF> {
+    fst: &'a F,
+    convert_tr: C,
+    convert_final_weight: CF,
+    w: PhantomData<(W, G)>,
+}
+
+impl<'a, W, G, F, C, CF> WeightView<'a, W, G, F, C, CF>
+where
+    W: Semiring,
+    G: Semiring,
+    F: Fst<W>,
+    C: Fn(&Tr<W>) -> Result<Tr<G>>,
+    CF: Fn(W) -> Result<G>,
+{
+    fn new(fst: &'a F, convert_tr: C, convert_final_weight: CF) -> Self {
+        Self {
+            fst,
+            convert_tr,
+            convert_final_weight,
+            w: PhantomData,
+        }
+    }
+
+    /// Converted transition `idx` of `state`.
+    fn tr(&self, state: StateId, idx: usize) -> Result<Tr<G>> {
+        let trs = self.fst.get_trs(state)?;
+        match trs.trs().get(idx) {
+            Some(tr) => (self.convert_tr)(tr),
+            None => bail!("State {} has no transition {}", state, idx),
+        }
+    }
+}
+
+impl<'a, W, G, F, C, CF> CoreFst<G> for WeightView<'a, W, G, F, C, CF>
+where
+    W: Semiring,
+    G: Semiring,
+    F: Fst<W>,
+    C: Fn(&Tr<W>) -> Result<Tr<G>>,
+    CF: Fn(W) -> Result<G>,
+{
+    type TRS = TrsVec<G>;
+
+    fn start(&self) -> Option<StateId> {
+        self.fst.start()
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<G>, Error> {
+        Ok(self
+            .fst
+            .final_weight(state)?
+            .map(&self.convert_final_weight)
+            .transpose()?)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state: StateId) -> Option<G> {
+        self.final_weight(state).unsafe_unwrap()
+    }
+
+    fn num_trs(&self, state: StateId) -> Result<usize, Error> {
+        Ok(self.fst.get_trs(state)?.len())
+    }
+
+    unsafe fn num_trs_unchecked(&self, state: StateId) -> usize {
+        self.num_trs(state).unsafe_unwrap()
+    }
+
+    fn get_trs(&self, state: StateId) -> Result<Self::TRS, Error> {
+        let trs = self
+            .fst
+            .get_trs(state)?
+            .trs()
+            .iter()
+            .map(&self.convert_tr)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    unsafe fn get_trs_unchecked(&self, state: StateId) -> Self::TRS {
+        self.get_trs(state).unsafe_unwrap()
+    }
+
+    fn properties(&self) -> FstProperties {
+        FstProperties::empty()
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize, Error> {
+        let trs = self.get_trs(state)?;
+        Ok(trs.iter().filter(|tr| tr.ilabel == EPS_LABEL).count())
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize, Error> {
+        let trs = self.get_trs(state)?;
+        Ok(trs.iter().filter(|tr| tr.olabel == EPS_LABEL).count())
+    }
+}
+
+/// Reversed view of a `WeightView` with a superinitial state 0, as built by `reverse`. Only
+/// the origins of the transitions entering each state are stored.
+struct ReverseView<'a, W, G, F, C, CF> {
+    view: &'a WeightView<'a, W, G, F, C, CF>,
+    final_states: Vec<StateId>,
+    // Origin state and index of the transitions entering each state.
+    incoming: Vec<Vec<(StateId, usize)>>,
+}
+
+impl<'a, W, G, F, C, CF> ReverseView<'a, W, G, F, C, CF>
+where
+    W: Semiring,
+    G: Semiring,
+    F: Fst<W>,
+    C: Fn(&Tr<W>) -> Result<Tr<G>>,
+    CF: Fn(W) -> Result<G>,
+{
+    fn new(view: &'a WeightView<'a, W, G, F, C, CF>, num_states: usize) -> Result<Self> {
+        let mut final_states = vec![];
+        let mut incoming = vec![vec![]; num_states];
+        for state in 0..num_states {
+            if view.fst.is_final(state)? {
+                final_states.push(state);
+            }
+            for (idx, tr) in view.fst.get_trs(state)?.trs().iter().enumerate() {
+                match incoming.get_mut(tr.nextstate) {
+                    Some(origins) => origins.push((state, idx)),
+                    None => bail!(Error::InvalidStateId(tr.nextstate)),
+                }
+            }
+        }
+        Ok(Self {
+            view,
+            final_states,
+            incoming,
+        })
+    }
+
+    fn origins(&self, state: StateId) -> Result<&[(StateId, usize)], Error> {
+        match state.checked_sub(1).and_then(|s| self.incoming.get(s)) {
+            Some(origins) => Ok(origins.as_slice()),
+            None => Err(Error::InvalidStateId(state)),
+        }
+    }
+}
+
+impl<'a, W, G, F, C, CF> CoreFst<G::ReverseWeight> for ReverseView<'a, W, G, F, C, CF>
+where
+    W: Semiring,
+    G: Semiring,
+    F: Fst<W>,
+    C: Fn(&Tr<W>) -> Result<Tr<G>>,
+    CF: Fn(W) -> Result<G>,
+{
+    type TRS = TrsVec<G::ReverseWeight>;
+
+    fn start(&self) -> Option<StateId> {
+        Some(0)
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<G::ReverseWeight>, Error> {
+        if state == 0 {
+            return Ok(None);
+        }
+        self.origins(state)?;
+        if self.view.start() == Some(state - 1) {
+            Ok(Some(G::ReverseWeight::one()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    unsafe fn final_weight_unchecked(&self, state: StateId) -> Option<G::ReverseWeight> {
+        self.final_weight(state).unsafe_unwrap()
+    }
+
+    fn num_trs(&self, state: StateId) -> Result<usize, Error> {
+        if state == 0 {
+            Ok(self.final_states.len())
+        } else {
+            Ok(self.origins(state)?.len())
+        }
+    }
+
+    unsafe fn num_trs_unchecked(&self, state: StateId) -> usize {
+        self.num_trs(state).unsafe_unwrap()
+    }
+
+    fn get_trs(&self, state: StateId) -> Result<Self::TRS, Error> {
+        let mut trs = vec![];
+        if state == 0 {
+            for &s in &self.final_states {
+                if let Some(final_weight) = self.view.final_weight(s)? {
+                    trs.push(Tr::new(
+                        EPS_LABEL,
+                        EPS_LABEL,
+                        final_weight.reverse()?,
+                        s + 1,
+                    ));
+                }
+            }
+        } else {
+            for &(s, idx) in self.origins(state)? {
+                let tr = self.view.tr(s, idx)?;
+                trs.push(Tr::new(tr.ilabel, tr.olabel, tr.weight.reverse()?, s + 1));
+            }
+        }
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    unsafe fn get_trs_unchecked(&self, state: StateId) -> Self::TRS {
+        self.get_trs(state).unsafe_unwrap()
+    }
+
+    fn properties(&self) -> FstProperties {
+        FstProperties::empty()
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize, Error> {
+        let trs = self.get_trs(state)?;
+        Ok(trs.iter().filter(|tr| tr.ilabel == EPS_LABEL).count())
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize, Error> {
+        let trs = self.get_trs(state)?;
+        Ok(trs.iter().filter(|tr| tr.olabel == EPS_LABEL).count())
+    }
+}
+
+/// Potentials of the FST read through `view`, as computed by `shortest_distance`. The FST is
+/// not copied : the distances to the final states are computed on a `ReverseView`.
+fn compute_potentials<W, G, F, C, CF>(
+    view: &WeightView<W, G, F, C, CF>,
+    num_states: usize,
+    reverse: bool,
+    delta: f32,
+) -> Result<Vec<G>>
+where
+    W: Semiring,
+    G: Semiring,
+    F: Fst<W>,
+    C: Fn(&Tr<W>) -> Result<Tr<G>>,
+    CF: Fn(W) -> Result<G>,
+{
+    if !reverse {
+        let mut sd_state = ShortestDistanceState::new(
+            num_states,
+            FifoQueue::default(),
+            AnyTrFilter {},
+            false,
+            false,
+            delta,
+        );
+        return sd_state.shortest_distance::<WeightView<W, G, F, C, CF>, _>(None, view);
+    }
+    let rview = ReverseView::new(view, num_states)?;
+    let mut sd_state = ShortestDistanceState::new(
+        num_states + 1,
+        FifoQueue::default(),
+        AnyTrFilter {},
+        false,
+        false,
+        delta,
+    );
+    let rdistance = sd_state.shortest_distance::<ReverseView<W, G, F, C, CF>, _>(None, &rview)?;
+    // The reversed FST has a superinitial state.
+    rdistance.iter().skip(1).map(|d| d.reverse_back()).collect()
+}
+
+/// Whether the start state of `fst` is on no cycle. It is computed with a traversal from the
+/// start state when the properties of `fst` don't tell.
+fn is_initial_acyclic<W: Semiring, F: Fst<W>>(
+    fst: &F,
+    start: StateId,
+    num_states: usize,
+) -> Result<bool> {
+    let props = fst.properties();
+    if props.intersects(FstProperties::INITIAL_ACYCLIC | FstProperties::INITIAL_CYCLIC) {
+        return Ok(props.contains(FstProperties::INITIAL_ACYCLIC));
+    }
+    let mut visited = vec![false; num_states];
+    let mut stack = vec![start];
+    while let Some(state) = stack.pop() {
+        for tr in fst.get_trs(state)?.trs() {
+            if tr.nextstate == start {
+                return Ok(false);
+            }
+            if let Some(seen) = visited.get_mut(tr.nextstate) {
+                if !*seen {
+                    *seen = true;
+                    stack.push(tr.nextstate);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Reweighting of an FST according to a vector of potentials, as done by `reweight`,
+/// followed by the removal of the total weight.
+#[derive(Debug)]
+struct Reweighter<G: Semiring> {
+    potentials: Vec<G>,
+    reweight_type: ReweightType,
+    start: Option<StateId>,
+    // State added to hold the potential of the start state when it has incoming transitions.
+    new_start: Option<StateId>,
+    // Weight multiplied on the left of the transitions and of the final weight of the start
+    // state, or of the transition of the new start state.
+    start_weight: Option<G>,
+    // Total weight dividing on the left the weights of the start state.
+    start_divisor: Option<G>,
+    // Total weight dividing on the right all the final weights.
+    final_divisor: Option<G>,
+}
+
+impl<G: WeaklyDivisibleSemiring> Reweighter<G> {
+    /// `fst` is the FST to reweight, whatever its semiring, and `num_states` its number of
+    /// states.
+    fn new<W: Semiring, F: Fst<W>>(
+        fst: &F,
+        num_states: usize,
+        potentials: Vec<G>,
+        total_weight: Option<G>,
+        reweight_type: ReweightType,
+    ) -> Result<Self> {
+        let start = fst.start();
+        let mut start_weight = None;
+        let mut new_start = None;
+        if let Some(start) = start {
+            let zero = G::zero();
+            let d_start = potentials.get(start).unwrap_or(&zero);
+            if !d_start.is_one() && !d_start.is_zero() {
+                start_weight = Some(match reweight_type {
+                    ReweightType::ReweightToInitial => d_start.clone(),
+                    ReweightType::ReweightToFinal => {
+                        G::one().divide(d_start, DivideType::DivideRight)?
+                    }
+                });
+                if !is_initial_acyclic(fst, start, num_states)? {
+                    new_start = Some(num_states);
+                }
+            }
+        }
+
+        let mut start_divisor = None;
+        let mut final_divisor = None;
+        if let Some(total_weight) = total_weight {
+            if !total_weight.is_one() && !total_weight.is_zero() {
+                match reweight_type {
+                    ReweightType::ReweightToInitial => start_divisor = Some(total_weight),
+                    ReweightType::ReweightToFinal => final_divisor = Some(total_weight),
+                }
+            }
+        }
+
+        Ok(Self {
+            potentials,
+            reweight_type,
+            start,
+            new_start,
+            start_weight,
+            start_divisor,
+            final_divisor,
+        })
+    }
+
+    fn start(&self) -> Option<StateId> {
+        self.new_start.or(self.start)
+    }
+
+    fn is_start_reweighted(&self, state: StateId) -> bool {
+        self.new_start.is_none() && Some(state) == self.start
+    }
+
+    // Removes the total weight from a weight of the start state.
+    fn remove_start_divisor(&self, weight: G) -> Result<G> {
+        match &self.start_divisor {
+            Some(start_divisor) => weight.divide(start_divisor, DivideType::DivideLeft),
+            None => Ok(weight),
+        }
+    }
+
+    /// Transitions of `state` in the reweighted FST, the ones of the input FST being
+    /// converted with `convert`.
+    fn compute_trs<W, F, C>(&self, fst: &F, state: StateId, convert: C) -> Result<Vec<Tr<G>>>
+    where
+        W: Semiring,
+        F: Fst<W>,
+        C: Fn(&Tr<W>) -> Result<Tr<G>>,
+    {
+        if Some(state) == self.new_start {
+            let weight = self.remove_start_divisor(self.start_weight.clone().unwrap())?;
+            let tr = Tr::new(EPS_LABEL, EPS_LABEL, weight, self.start.unwrap());
+            return Ok(vec![tr]);
+        }
+
+        let mut trs = fst
+            .get_trs(state)?
+            .trs()
+            .iter()
+            .map(convert)
+            .collect::<Result<Vec<_>>>()?;
+
+        let zero = G::zero();
+        let d_s = self.potentials.get(state).unwrap_or(&zero);
+        if !d_s.is_zero() {
+            for tr in trs.iter_mut() {
+                let d_ns = self.potentials.get(tr.nextstate).unwrap_or(&zero);
+                if d_ns.is_zero() {
+                    continue;
+                }
+                tr.weight = match self.reweight_type {
+                    ReweightType::ReweightToInitial => {
+                        tr.weight.times(d_ns)?.divide(d_s, DivideType::DivideLeft)?
+                    }
+                    ReweightType::ReweightToFinal => d_s
+                        .times(&tr.weight)?
+                        .divide(d_ns, DivideType::DivideRight)?,
+                };
+            }
+        }
+
+        if self.is_start_reweighted(state) {
+            for tr in trs.iter_mut() {
+                if let Some(start_weight) = &self.start_weight {
+                    tr.weight = start_weight.times(&tr.weight)?;
+                }
+                tr.weight = self.remove_start_divisor(tr.weight.clone())?;
+            }
+        }
+
+        Ok(trs)
+    }
+
+    /// Final weight of `state` in the reweighted FST, the one of the input FST being
+    /// converted with `convert`.
+    fn compute_final_weight<W, F, C>(
+        &self,
+        fst: &F,
+        state: StateId,
+        convert: C,
+    ) -> Result<Option<G>>
+    where
+        W: Semiring,
+        F: Fst<W>,
+        C: Fn(W) -> Result<G>,
+    {
+        if Some(state) == self.new_start {
+            return Ok(None);
+        }
+        let mut final_weight = match fst.final_weight(state)? {
+            Some(final_weight) => convert(final_weight)?,
+            None => return Ok(None),
+        };
+
+        let zero = G::zero();
+        let d_s = self.potentials.get(state).unwrap_or(&zero);
+        match self.reweight_type {
+            ReweightType::ReweightToFinal => final_weight.times_assign(d_s)?,
+            ReweightType::ReweightToInitial => {
+                if !d_s.is_zero() {
+                    final_weight.divide_assign(d_s, DivideType::DivideLeft)?;
+                }
+            }
+        };
+
+        if self.is_start_reweighted(state) {
+            if let Some(start_weight) = &self.start_weight {
+                final_weight = start_weight.times(&final_weight)?;
+            }
+            final_weight = self.remove_start_divisor(final_weight)?;
+        }
+
+        if let Some(final_divisor) = &self.final_divisor {
+            final_weight.divide_assign(final_divisor, DivideType::DivideRight)?;
+        }
+
+        Ok(Some(final_weight))
+    }
+}
+
+/// State of an FST pushed with labels : a state of the reweighted Gallic FST (`None` for
+/// the states created to factor the final weights) with the residual weight not emitted yet.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum PushElement<G: Semiring> {
+    Factored(Option<StateId>, G),
+    Superfinal,
+}
+
+enum PushMode<W: Semiring> {
+    Identity,
+    Weights(Reweighter<W>),
+    LabelsToInitial(
+        Reweighter<GallicWeightLeft<W>>,
+        StateTable<PushElement<GallicWeightLeft<W>>>,
+    ),
+    LabelsToFinal(
+        Reweighter<GallicWeightRight<W>>,
+        StateTable<PushElement<GallicWeightRight<W>>>,
+    ),
+}
+
+macro_rules! m_labels_reweighter {
+    ($ifst: ident, $num_states: ident, $reweight_type: ident, $push_type: ident, $delta: ident, $gallic_weight: ty, $string_weight: ident) => {{
+        let gfst = WeightView::<_, $gallic_weight, _, _, _>::new(
+            $ifst,
+            to_gallic_tr,
+            to_gallic_final_weight,
+        );
+        let reverse = $reweight_type == ReweightType::ReweightToInitial;
+        let gdistance = if $push_type.intersects(PushType::PUSH_WEIGHTS) {
+            compute_potentials(&gfst, $num_states, reverse, $delta)?
+        } else {
+            let guwfst = WeightView::<_, $gallic_weight, _, _, _>::new(
+                $ifst,
+                to_unweighted_gallic_tr,
+                to_unweighted_gallic_final_weight,
+            );
+            compute_potentials(&guwfst, $num_states, reverse, $delta)?
+        };
+        let total_weight = if $push_type
+            .intersects(PushType::REMOVE_COMMON_AFFIX | PushType::REMOVE_TOTAL_WEIGHT)
+        {
+            let mut total_weight = compute_total_weight(&gfst, &gdistance, reverse)?;
+            if !$push_type.intersects(PushType::REMOVE_COMMON_AFFIX) {
+                total_weight.set_value1($string_weight::one());
+            }
+            if !$push_type.intersects(PushType::REMOVE_TOTAL_WEIGHT) {
+                total_weight.set_value2(W::one());
+            }
+            Some(total_weight)
+        } else {
+            None
+        };
+        Reweighter::new($ifst, $num_states, gdistance, total_weight, $reweight_type)?
+    }};
+}
+
+fn to_gallic_tr<W: Semiring, G: Semiring>(tr: &Tr<W>) -> Result<Tr<G>>
+where
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    ToGallicConverter {}.tr_map(tr)
+}
+
+fn to_gallic_final_weight<W: Semiring, G: Semiring>(weight: W) -> Result<G>
+where
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    let final_tr = FinalTr {
+        ilabel: EPS_LABEL,
+        olabel: EPS_LABEL,
+        weight,
+    };
+    Ok(ToGallicConverter {}.final_tr_map(&final_tr)?.weight)
+}
+
+fn to_unweighted_gallic_tr<W: Semiring, G: Semiring>(tr: &Tr<W>) -> Result<Tr<G>>
+where
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    let tr: Tr<W> = RmWeightMapper {}.tr_map(tr)?;
+    to_gallic_tr(&tr)
+}
+
+fn to_unweighted_gallic_final_weight<W: Semiring, G: Semiring>(weight: W) -> Result<G>
+where
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    let final_tr = FinalTr {
+        ilabel: EPS_LABEL,
+        olabel: EPS_LABEL,
+        weight,
+    };
+    let final_tr: FinalTr<W> = RmWeightMapper {}.final_tr_map(&final_tr)?;
+    to_gallic_final_weight(final_tr.weight)
+}
+
+/// Final weight of an element of the factored Gallic FST.
+fn factored_final_weight<W, F, G>(
+    fst: &F,
+    reweighter: &Reweighter<G>,
+    state: Option<StateId>,
+    residual: G,
+) -> Result<Option<G>>
+where
+    W: Semiring,
+    F: Fst<W>,
+    G: WeaklyDivisibleSemiring,
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    match state {
+        None => Ok(Some(residual)),
+        Some(s) => reweighter
+            .compute_final_weight(fst, s, to_gallic_final_weight)?
+            .map(|final_weight| residual.times(final_weight))
+            .transpose(),
+    }
+}
+
+/// Transitions of a state of the pushed FST : the transitions of the reweighted Gallic FST
+/// are factored by `FI` to emit at most one output label each, then converted back to `W`.
+fn labels_compute_trs<W, F, G, FI>(
+    fst: &F,
+    reweighter: &Reweighter<G>,
+    state_table: &StateTable<PushElement<G>>,
+    delta: f32,
+    state: StateId,
+) -> Result<TrsVec<W>>
+where
+    W: Semiring,
+    F: Fst<W>,
+    G: WeaklyDivisibleSemiring + WeightQuantize,
+    FI: FactorIterator<G>,
+    ToGallicConverter: WeightConverter<W, G>,
+    FromGallicConverter: WeightConverter<G, W>,
+{
+    let (elt_state, residual) = match state_table.find_tuple(state) {
+        PushElement::Factored(elt_state, residual) => (elt_state, residual),
+        PushElement::Superfinal => return Ok(TrsVec::default()),
+    };
+    let mut from_gallic = FromGallicConverter {
+        superfinal_label: EPS_LABEL,
+    };
+    let mut trs = vec![];
+
+    if let Some(s) = elt_state {
+        for tr in reweighter.compute_trs(fst, s, to_gallic_tr)? {
+            let weight = residual.times(&tr.weight)?;
+            let factor_it = FI::new(weight.clone());
+            if factor_it.done() {
+                let dest = state_table.find_id(PushElement::Factored(Some(tr.nextstate), G::one()));
+                trs.push(from_gallic.tr_map(&Tr::new(tr.ilabel, tr.olabel, weight, dest))?);
+            } else {
+                for (p_f, p_s) in factor_it {
+                    let dest = state_table.find_id(PushElement::Factored(
+                        Some(tr.nextstate),
+                        p_s.quantize(delta)?,
+                    ));
+                    trs.push(from_gallic.tr_map(&Tr::new(tr.ilabel, tr.olabel, p_f, dest))?);
+                }
+            }
+        }
+    }
+
+    if let Some(final_weight) = factored_final_weight(fst, reweighter, elt_state, residual)? {
+        let factor_it = FI::new(final_weight.clone());
+        if !factor_it.done() {
+            for (p_f, p_s) in factor_it {
+                let dest = state_table.find_id(PushElement::Factored(None, p_s.quantize(delta)?));
+                trs.push(from_gallic.tr_map(&Tr::new(EPS_LABEL, EPS_LABEL, p_f, dest))?);
+            }
+        } else if !final_weight.is_zero() {
+            let final_tr = from_gallic.final_tr_map(&FinalTr {
+                ilabel: EPS_LABEL,
+                olabel: EPS_LABEL,
+                weight: final_weight,
+            })?;
+            if final_tr.olabel != EPS_LABEL {
+                let dest = state_table.find_id(PushElement::Superfinal);
+                trs.push(Tr::new(
+                    final_tr.ilabel,
+                    final_tr.olabel,
+                    final_tr.weight,
+                    dest,
+                ));
+            }
+        }
+    }
+
+    Ok(TrsVec(Arc::new(trs)))
+}
+
+fn labels_compute_final_weight<W, F, G, FI>(
+    fst: &F,
+    reweighter: &Reweighter<G>,
+    state_table: &StateTable<PushElement<G>>,
+    state: StateId,
+) -> Result<Option<W>>
+where
+    W: Semiring,
+    F: Fst<W>,
+    G: WeaklyDivisibleSemiring,
+    FI: FactorIterator<G>,
+    ToGallicConverter: WeightConverter<W, G>,
+    FromGallicConverter: WeightConverter<G, W>,
+{
+    let (elt_state, residual) = match state_table.find_tuple(state) {
+        PushElement::Factored(elt_state, residual) => (elt_state, residual),
+        PushElement::Superfinal => return Ok(Some(W::one())),
+    };
+    let final_weight = match factored_final_weight(fst, reweighter, elt_state, residual)? {
+        Some(final_weight) => final_weight,
+        None => return Ok(None),
+    };
+    if final_weight.is_zero() || !FI::new(final_weight.clone()).done() {
+        return Ok(None);
+    }
+    let mut from_gallic = FromGallicConverter {
+        superfinal_label: EPS_LABEL,
+    };
+    let final_tr = from_gallic.final_tr_map(&FinalTr {
+        ilabel: EPS_LABEL,
+        olabel: EPS_LABEL,
+        weight: final_weight,
+    })?;
+    if final_tr.olabel == EPS_LABEL {
+        Ok(Some(final_tr.weight))
+    } else {
+        // Emitted on a transition to the superfinal state.
+        Ok(None)
+    }
+}
+
+pub struct PushFstOp<W: Semiring, F: Fst<W>, B: Borrow<F>> {
+    fst: B,
+    mode: PushMode<W>,
+    delta: f32,
+    properties: FstProperties,
+    f: PhantomData<F>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> Debug for PushFstOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match &self.mode {
+            PushMode::Identity => "Identity",
+            PushMode::Weights(_) => "Weights",
+            PushMode::LabelsToInitial(_, _) => "LabelsToInitial",
+            PushMode::LabelsToFinal(_, _) => "LabelsToFinal",
+        };
+        write!(
+            f,
+            "PushFstOp {{ mode : {}, delta : {:?}, fst : {:?} }}",
+            mode,
+            self.delta,
+            self.fst.borrow()
+        )
+    }
+}
+
+impl<W, F, B> PushFstOp<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F>,
+    <W as Semiring>::ReverseWeight: 'static,
+{
+    pub fn new(
+        fst: B,
+        reweight_type: ReweightType,
+        push_type: PushType,
+        config: PushConfig,
+    ) -> Result<Self> {
+        let delta = config.delta;
+        let ifst = fst.borrow();
+        let inprops = ifst.properties();
+        let (mode, properties) = if push_type.intersects(PushType::PUSH_LABELS) {
+            let num_states = ifst.states_iter().count();
+            let mode = match reweight_type {
+                ReweightType::ReweightToInitial => PushMode::LabelsToInitial(
+                    m_labels_reweighter!(
+                        ifst,
+                        num_states,
+                        reweight_type,
+                        push_type,
+                        delta,
+                        GallicWeightLeft<W>,
+                        StringWeightLeft
+                    ),
+                    StateTable::new(),
+                ),
+                ReweightType::ReweightToFinal => PushMode::LabelsToFinal(
+                    m_labels_reweighter!(
+                        ifst,
+                        num_states,
+                        reweight_type,
+                        push_type,
+                        delta,
+                        GallicWeightRight<W>,
+                        StringWeightRight
+                    ),
+                    StateTable::new(),
+                ),
+            };
+            (mode, FstProperties::empty())
+        } else if push_type.intersects(PushType::PUSH_WEIGHTS) {
+            let num_states = ifst.states_iter().count();
+            let reverse = reweight_type == ReweightType::ReweightToInitial;
+            let view = WeightView::new(ifst, |tr: &Tr<W>| Ok(tr.clone()), Ok);
+            let potentials = compute_potentials(&view, num_states, reverse, delta)?;
+            let total_weight = if push_type.intersects(PushType::REMOVE_TOTAL_WEIGHT) {
+                Some(compute_total_weight(ifst, &potentials, reverse)?)
+            } else {
+                None
+            };
+            let reweighter =
+                Reweighter::new(ifst, num_states, potentials, total_weight, reweight_type)?;
+            let properties = if reweighter.new_start.is_some() {
+                FstProperties::empty()
+            } else {
+                reweight_properties(inprops)
+            };
+            (PushMode::Weights(reweighter), properties)
+        } else {
+            (PushMode::Identity, inprops)
+        };
+        Ok(Self {
+            fst,
+            mode,
+            delta,
+            properties,
+            f: PhantomData,
+        })
+    }
+}
+
+impl<W, F, B> FstOp<W> for PushFstOp<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        let start = match &self.mode {
+            PushMode::Identity => self.fst.borrow().start(),
+            PushMode::Weights(reweighter) => reweighter.start(),
+            PushMode::LabelsToInitial(reweighter, state_table) => reweighter
+                .start()
+                .map(|s| state_table.find_id(PushElement::Factored(Some(s), Semiring::one()))),
+            PushMode::LabelsToFinal(reweighter, state_table) => reweighter
+                .start()
+                .map(|s| state_table.find_id(PushElement::Factored(Some(s), Semiring::one()))),
+        };
+        Ok(start)
+    }
+
+    fn compute_trs(&self, state: StateId) -> Result<TrsVec<W>> {
+        let fst = self.fst.borrow();
+        match &self.mode {
+            PushMode::Identity => Ok(TrsVec(Arc::new(fst.get_trs(state)?.to_vec()))),
+            PushMode::Weights(reweighter) => {
+                let trs = reweighter.compute_trs(fst, state, |tr| Ok(tr.clone()))?;
+                Ok(TrsVec(Arc::new(trs)))
+            }
+            PushMode::LabelsToInitial(reweighter, state_table) => {
+                labels_compute_trs::<_, _, _, GallicFactorLeft<W>>(
+                    fst,
+                    reweighter,
+                    state_table,
+                    self.delta,
+                    state,
+                )
+            }
+            PushMode::LabelsToFinal(reweighter, state_table) => {
+                labels_compute_trs::<_, _, _, GallicFactorRight<W>>(
+                    fst,
+                    reweighter,
+                    state_table,
+                    self.delta,
+                    state,
+                )
+            }
+        }
+    }
+
+    fn compute_final_weight(&self, state: StateId) -> Result<Option<W>> {
+        let fst = self.fst.borrow();
+        match &self.mode {
+            PushMode::Identity => Ok(fst.final_weight(state)?),
+            PushMode::Weights(reweighter) => reweighter.compute_final_weight(fst, state, Ok),
+            PushMode::LabelsToInitial(reweighter, state_table) => {
+                labels_compute_final_weight::<_, _, _, GallicFactorLeft<W>>(
+                    fst,
+                    reweighter,
+                    state_table,
+                    state,
+                )
+            }
+            PushMode::LabelsToFinal(reweighter, state_table) => {
+                labels_compute_final_weight::<_, _, _, GallicFactorRight<W>>(
+                    fst,
+                    reweighter,
+                    state_table,
+                    state,
+                )
+            }
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
+
+/// Delayed version of `push_with_config`. The potentials are computed with
+/// `shortest_distance` when the FST is created, then the transitions of a state are
+/// reweighted, and factored to emit at most one output label each when pushing labels,
+/// only when the state is expanded. The input can be any FST, including a lazy one : it is
+/// read through views converting its weights and is never copied, only the potentials
+/// are kept.
+pub struct PushFst<W: Semiring, F: Fst<W>, B: Borrow<F>>(
+    LazyFst<W, PushFstOp<W, F, B>, SimpleHashMapCache<W>>,
+);
+
+impl<W, F, B> CoreFst<W> for PushFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B> StateIterator<'a> for PushFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type Iter = <LazyFst<W, PushFstOp<W, F, B>, SimpleHashMapCache<W>> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B> FstIterator<'a, W> for PushFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+{
+    type FstIter =
+        <LazyFst<W, PushFstOp<W, F, B>, SimpleHashMapCache<W>> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B> Fst<W> for PushFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B> Debug for PushFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B> PushFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: Fst<W>,
+    B: Borrow<F>,
+    <W as Semiring>::ReverseWeight: 'static,
+{
+    pub fn new(fst: B, reweight_type: ReweightType, push_type: PushType) -> Result<Self, Error> {
+        Self::new_with_config(fst, reweight_type, push_type, PushConfig::default())
+    }
+
+    pub fn new_with_config(
+        fst: B,
+        reweight_type: ReweightType,
+        push_type: PushType,
+        config: PushConfig,
+    ) -> Result<Self, Error> {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = PushFstOp::new(fst, reweight_type, push_type, config)?;
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        Ok(PushFst(lazy_fst))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
+        self.0.compute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::tr_mappers::IdentityTrMapper;
+    use crate::algorithms::{isomorphic, push_with_config, TrMapFst};
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{LogWeight, TropicalWeight};
+
+    fn build_fst<W: Semiring<Type = f32>>() -> Result<VectorFst<W>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, W::new(1.0), 1))?;
+        fst.add_tr(0, Tr::new(3, 2, W::new(2.0), 2))?;
+        fst.add_tr(1, Tr::new(4, 5, W::new(1.5), 3))?;
+        fst.add_tr(2, Tr::new(4, 5, W::new(0.5), 3))?;
+        fst.add_tr(2, Tr::new(6, 0, W::new(0.5), 0))?;
+        fst.set_final(3, W::new(2.0))?;
+        fst.set_final(1, W::new(3.0))?;
+        Ok(fst)
+    }
+
+    fn check_push<W>(fst: &VectorFst<W>) -> Result<()>
+    where
+        W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+        <W as Semiring>::ReverseWeight: 'static,
+    {
+        let weights = PushType::PUSH_WEIGHTS;
+        let labels = PushType::PUSH_LABELS;
+        let remove = PushType::REMOVE_TOTAL_WEIGHT | PushType::REMOVE_COMMON_AFFIX;
+        let push_types = vec![
+            PushType::empty(),
+            weights,
+            weights | PushType::REMOVE_TOTAL_WEIGHT,
+            labels,
+            labels | remove,
+            weights | labels,
+            weights | labels | remove,
+        ];
+        for reweight_type in &[
+            ReweightType::ReweightToInitial,
+            ReweightType::ReweightToFinal,
+        ] {
+            for push_type in &push_types {
+                let ref_fst: VectorFst<_> =
+                    push_with_config(fst, *reweight_type, *push_type, PushConfig::default())?;
+                let lazy_fst: PushFst<_, VectorFst<_>, _> =
+                    PushFst::new(fst, *reweight_type, *push_type)?;
+                let computed: VectorFst<_> = lazy_fst.compute()?;
+                if push_type.intersects(PushType::PUSH_LABELS) {
+                    assert!(isomorphic(&computed, &ref_fst)?);
+                } else {
+                    assert_eq!(computed, ref_fst);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_fst_lazy_input() -> Result<()> {
+        let fst = build_fst::<TropicalWeight>()?;
+        let lazy_input = TrMapFst::new(fst.clone(), IdentityTrMapper {});
+        for reweight_type in &[
+            ReweightType::ReweightToInitial,
+            ReweightType::ReweightToFinal,
+        ] {
+            for push_type in &[
+                PushType::PUSH_WEIGHTS | PushType::REMOVE_TOTAL_WEIGHT,
+                PushType::PUSH_LABELS | PushType::REMOVE_COMMON_AFFIX,
+            ] {
+                let ref_fst: VectorFst<_> =
+                    push_with_config(&fst, *reweight_type, *push_type, PushConfig::default())?;
+                let lazy_fst: PushFst<_, TrMapFst<_, VectorFst<_>, _, _>, _> =
+                    PushFst::new(&lazy_input, *reweight_type, *push_type)?;
+                let computed: VectorFst<_> = lazy_fst.compute()?;
+                assert!(isomorphic(&computed, &ref_fst)?);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_fst_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<PushFst<TropicalWeight, VectorFst<_>, VectorFst<_>>>();
+    }
+
+    #[test]
+    fn test_push_fst_tropical() -> Result<()> {
+        check_push(&build_fst::<TropicalWeight>()?)
+    }
+
+    #[test]
+    fn test_push_fst_log() -> Result<()> {
+        check_push(&build_fst::<LogWeight>()?)
+    }
+}
@@ -5,9 +5,9 @@ use anyhow::{bail, format_err, Context, Result};
 
 use crate::fst_traits::MutableFst;
 use crate::semirings::Semiring;
-use crate::StateId;
+use crate::{Error, StateId};
 
-fn iterator_to_hashmap<I>(pairs: I) -> Result<HashMap<StateId, StateId>>
+pub(crate) fn iterator_to_hashmap<I>(pairs: I) -> Result<HashMap<StateId, StateId>>
 where
     I: IntoIterator<Item = (StateId, StateId)>,
 {
@@ -45,7 +45,7 @@ where
 /// # Ok(())
 /// # }
 /// ```
-pub fn relabel_pairs<W, F, I, J>(fst: &mut F, ipairs: I, opairs: J) -> Result<()>
+pub fn relabel_pairs<W, F, I, J>(fst: &mut F, ipairs: I, opairs: J) -> Result<(), Error>
 where
     W: Semiring,
     F: MutableFst<W>,
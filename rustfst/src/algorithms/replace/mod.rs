@@ -1,9 +1,14 @@
-pub(crate) mod config;
+mod config;
 mod replace;
 mod replace_fst;
 pub(crate) mod replace_fst_op;
+mod replace_pdt;
+mod replace_util;
 pub(crate) mod state_table;
 pub(crate) mod utils;
 
-pub use replace::replace;
+pub use config::{ReplaceFstOptions, ReplaceLabelType};
+pub use replace::{replace, replace_with_config, ReplaceConfig, ReplaceOutput};
 pub use replace_fst::ReplaceFst;
+pub use replace_pdt::replace_pdt;
+pub use replace_util::ReplaceUtil;
@@ -1,11 +1,60 @@
 use std::borrow::Borrow;
 
-use anyhow::Result;
-
-use crate::algorithms::replace::ReplaceFst;
-use crate::fst_traits::{AllocableFst, Fst, MutableFst};
+use crate::algorithms::replace::replace_util::RuleDependencies;
+use crate::algorithms::replace::{replace_pdt, ReplaceFst};
+use crate::fst_traits::{AllocableFst, ExpandedFst, Fst, MutableFst};
 use crate::semirings::Semiring;
-use crate::Label;
+use crate::{Error, Label};
+
+/// Configuration of `replace_with_config`.
+#[derive(PartialOrd, PartialEq, Debug, Clone, Copy)]
+pub struct ReplaceConfig {
+    /// Label of the root FST.
+    pub root: Label,
+    /// If true, the call and return transitions are labeled with epsilons.
+    pub epsilon_on_replace: bool,
+    /// If true, a grammar with cyclic dependencies is converted to a pushdown transducer
+    /// with `replace_pdt` instead of returning an error.
+    pub pdt_on_cyclic: bool,
+    /// First parenthesis label of the pushdown transducer. If `None`, the largest label
+    /// of the rules + 1 is used.
+    pub start_paren_label: Option<Label>,
+}
+
+impl ReplaceConfig {
+    pub fn new(root: Label, epsilon_on_replace: bool) -> Self {
+        Self {
+            root,
+            epsilon_on_replace,
+            pdt_on_cyclic: false,
+            start_paren_label: None,
+        }
+    }
+
+    pub fn with_pdt_on_cyclic(self, pdt_on_cyclic: bool) -> Self {
+        Self {
+            pdt_on_cyclic,
+            ..self
+        }
+    }
+
+    pub fn with_start_paren_label(self, start_paren_label: Label) -> Self {
+        Self {
+            start_paren_label: Some(start_paren_label),
+            ..self
+        }
+    }
+}
+
+/// Output of `replace_with_config`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplaceOutput<F> {
+    /// The grammar is regular and has been expanded.
+    Fst(F),
+    /// The grammar has cyclic dependencies : pushdown transducer and its list
+    /// of `(open, close)` parenthesis pairs.
+    Pdt(F, Vec<(Label, Label)>),
+}
 
 /// Recursively replaces trs in the root FSTs with other FSTs.
 ///
@@ -20,6 +69,9 @@ use crate::Label;
 /// Note that input argument is a vector of pairs. These correspond to the tuple
 /// of non-terminal Label and corresponding FST.
 ///
+/// An error is returned if the grammar has cyclic dependencies, as its expansion would be
+/// infinite. Use `replace_with_config` to get a pushdown transducer instead.
+///
 /// # Example
 ///
 /// ## Root Fst
@@ -46,13 +98,84 @@ pub fn replace<W, F1, F2, B>(
     fst_list: Vec<(Label, B)>,
     root: Label,
     epsilon_on_replace: bool,
-) -> Result<F2>
+) -> Result<F2, Error>
 where
     F1: Fst<W>,
     W: Semiring,
     F2: MutableFst<W> + AllocableFst<W>,
     B: Borrow<F1>,
 {
+    let dependencies = RuleDependencies::new::<F1, _>(&fst_list, root)?;
+    if dependencies.cyclic_dependencies() {
+        return Err(Error::ResourceLimit(
+            "Replace: The grammar has cyclic dependencies and can't be expanded to an FST"
+                .to_string(),
+        ));
+    }
     let fst = ReplaceFst::new(fst_list, root, epsilon_on_replace)?;
     fst.compute()
 }
+
+/// Same as `replace` but, if `pdt_on_cyclic` is set in the config, a grammar with cyclic
+/// dependencies is converted to a pushdown transducer with `replace_pdt` instead of
+/// returning an error.
+pub fn replace_with_config<W, F1, F2, B>(
+    fst_list: Vec<(Label, B)>,
+    config: ReplaceConfig,
+) -> Result<ReplaceOutput<F2>, Error>
+where
+    F1: ExpandedFst<W>,
+    W: Semiring,
+    F2: MutableFst<W> + AllocableFst<W>,
+    B: Borrow<F1>,
+{
+    let dependencies = RuleDependencies::new::<F1, _>(&fst_list, config.root)?;
+    if dependencies.cyclic_dependencies() {
+        if !config.pdt_on_cyclic {
+            return Err(Error::ResourceLimit(
+                "Replace: The grammar has cyclic dependencies and can't be expanded to an FST"
+                    .to_string(),
+            ));
+        }
+        let (pdt, parens) =
+            replace_pdt::<W, F1, F2, B>(fst_list, config.root, config.start_paren_label)?;
+        return Ok(ReplaceOutput::Pdt(pdt, parens));
+    }
+    let fst = ReplaceFst::new(fst_list, config.root, config.epsilon_on_replace)?;
+    Ok(ReplaceOutput::Fst(fst.compute()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::transducer;
+
+    #[test]
+    fn test_replace_with_config_pdt_on_cyclic() -> Result<()> {
+        // 1 : 1 2, 2 : 3 2
+        let fst_list: Vec<(Label, VectorFst<TropicalWeight>)> = vec![
+            (1, transducer(&[1, 2], &[1, 2], TropicalWeight::one())),
+            (2, transducer(&[3, 2], &[3, 2], TropicalWeight::one())),
+        ];
+
+        let config = ReplaceConfig::new(1, true);
+        let res: Result<ReplaceOutput<VectorFst<_>>, Error> =
+            replace_with_config(fst_list.clone(), config);
+        match res {
+            Err(Error::ResourceLimit(_)) => {}
+            _ => panic!("Expected an Error::ResourceLimit"),
+        }
+
+        let res: ReplaceOutput<VectorFst<_>> =
+            replace_with_config(fst_list, config.with_pdt_on_cyclic(true))?;
+        match res {
+            ReplaceOutput::Pdt(_, parens) => assert_eq!(parens, vec![(4, 5), (6, 7)]),
+            ReplaceOutput::Fst(_) => panic!("Expected a PDT"),
+        }
+        Ok(())
+    }
+}
@@ -102,11 +102,12 @@ impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FstOp<W> for ReplaceFstOp<W, F, B> {
     fn compute_final_weight(&self, state: usize) -> Result<Option<W>> {
         let tuple = self.state_table.tuple_table.find_tuple(state);
         if tuple.prefix_id == 0 {
-            self.fst_array
+            Ok(self
+                .fst_array
                 .get(tuple.fst_id.unwrap())
                 .unwrap()
                 .borrow()
-                .final_weight(tuple.fst_state.unwrap())
+                .final_weight(tuple.fst_state.unwrap())?)
         } else {
             Ok(None)
         }
@@ -0,0 +1,81 @@
+use std::borrow::Borrow;
+
+use crate::algorithms::pdt;
+use crate::algorithms::replace::ReplaceFstOptions;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Error, Label};
+
+/// Converts a grammar given as a list of `(non-terminal, FST)` pairs into a pushdown
+/// transducer (PDT) instead of expanding it. This works for any grammar, including the ones
+/// that are not regular. The call and return transitions are labeled with epsilons.
+///
+/// See [`pdt::replace`](../pdt/fn.replace.html). Returns the PDT and its list of
+/// `(open, close)` parenthesis pairs.
+pub fn replace_pdt<W, F1, F2, B>(
+    fst_list: Vec<(Label, B)>,
+    root: Label,
+    start_paren_label: Option<Label>,
+) -> Result<(F2, Vec<(Label, Label)>), Error>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+    B: Borrow<F1>,
+{
+    let pdt = pdt::replace::<W, F1, F2, B>(
+        fst_list,
+        &ReplaceFstOptions::new(root, true),
+        start_paren_label,
+    )?;
+    Ok((pdt.fst, pdt.parens))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::utils::transducer;
+    use crate::{Tr, Trs};
+
+    #[test]
+    fn test_replace_pdt_recursive_grammar() -> Result<()> {
+        // 1 : a 2, 2 : b 2 | c
+        let root: VectorFst<TropicalWeight> = transducer(&[10, 2], &[10, 2], TropicalWeight::one());
+        let mut rule: VectorFst<TropicalWeight> = VectorFst::new();
+        let s0 = rule.add_state();
+        let s1 = rule.add_state();
+        let s2 = rule.add_state();
+        rule.set_start(s0)?;
+        rule.set_final(s2, TropicalWeight::one())?;
+        rule.add_tr(s0, Tr::new(11, 11, TropicalWeight::one(), s1))?;
+        rule.add_tr(s1, Tr::new(2, 2, TropicalWeight::one(), s2))?;
+        rule.add_tr(s0, Tr::new(12, 12, TropicalWeight::one(), s2))?;
+
+        let (pdt, parens): (VectorFst<_>, _) =
+            replace_pdt::<_, VectorFst<_>, _, _>(vec![(1, root), (2, rule)], 1, None)?;
+
+        // One pair of parentheses per call.
+        assert_eq!(parens, vec![(13, 14), (15, 16)]);
+        assert_eq!(pdt.num_states(), 3 + 3);
+        assert_eq!(pdt.start(), Some(0));
+        // Only the final state of the root rule is final.
+        assert!(pdt.is_final(2)?);
+        assert!(!pdt.is_final(5)?);
+
+        // The final state of the called rule returns to both call sites.
+        let close_trs = pdt.get_trs(5)?;
+        let mut close_labels: Vec<_> = close_trs
+            .trs()
+            .iter()
+            .map(|tr| (tr.ilabel, tr.nextstate))
+            .collect();
+        close_labels.sort();
+        assert_eq!(close_labels, vec![(14, 2), (16, 5)]);
+        Ok(())
+    }
+}
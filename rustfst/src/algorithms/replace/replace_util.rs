@@ -0,0 +1,369 @@
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::algorithms::dfs_visit::dfs_visit;
+use crate::algorithms::replace::ReplaceFst;
+use crate::algorithms::tr_filters::AnyTrFilter;
+use crate::algorithms::visitors::SccVisitor;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+use crate::{Error, Label, StateId, Tr, Trs, EPS_LABEL, NO_LABEL};
+
+/// Dependencies between the rules of a grammar given as a list of `(non-terminal, FST)` pairs.
+pub(crate) struct RuleDependencies<W: Semiring> {
+    /// State `i` is the `i`-th rule. There is one transition labeled with the non-terminal
+    /// for each call of rule `j` in rule `i`. The start state is the root rule and a rule is
+    /// final if its FST has a final state.
+    pub(crate) graph: VectorFst<W>,
+    /// SCC of each rule, numbered in topological order.
+    pub(crate) scc: Vec<i32>,
+    /// Is the rule in a cycle of the dependency graph ?
+    pub(crate) cyclic: Vec<bool>,
+    /// Can the rule be reached from the root rule ?
+    pub(crate) access: Vec<bool>,
+    /// Can the rule reach a rule with a final state ?
+    pub(crate) coaccess: Vec<bool>,
+}
+
+impl<W: Semiring> RuleDependencies<W> {
+    pub(crate) fn new<F: Fst<W>, B: Borrow<F>>(
+        fst_list: &[(Label, B)],
+        root: Label,
+    ) -> Result<Self> {
+        let nonterminals: HashMap<Label, usize> = fst_list
+            .iter()
+            .enumerate()
+            .map(|(i, (label, _))| (*label, i))
+            .collect();
+        let root_idx = match nonterminals.get(&root) {
+            Some(idx) => *idx,
+            None => bail!(
+                "Replace: No FST corresponding to root label {} in the input tuple vector",
+                root
+            ),
+        };
+
+        let mut graph = VectorFst::new();
+        graph.add_states(fst_list.len());
+        graph.set_start(root_idx)?;
+        for (i, (_, fst)) in fst_list.iter().enumerate() {
+            let fst = fst.borrow();
+            // Only the states accessible in the rule are expanded by the replacement.
+            let mut stack: Vec<StateId> = fst.start().into_iter().collect();
+            let mut visited: HashSet<StateId> = stack.iter().cloned().collect();
+            let mut is_final = false;
+            while let Some(s) = stack.pop() {
+                is_final |= fst.is_final(s)?;
+                for tr in fst.get_trs(s)?.trs() {
+                    if tr.olabel != EPS_LABEL {
+                        if let Some(&j) = nonterminals.get(&tr.olabel) {
+                            if fst_list[j].1.borrow().start().is_some() {
+                                graph.add_tr(i, Tr::new(tr.olabel, tr.olabel, W::one(), j))?;
+                            }
+                        }
+                    }
+                    if visited.insert(tr.nextstate) {
+                        stack.push(tr.nextstate);
+                    }
+                }
+            }
+            if is_final {
+                graph.set_final(i, W::one())?;
+            }
+        }
+
+        let mut visitor = SccVisitor::new(&graph, true, false);
+        dfs_visit(&graph, &mut visitor, &AnyTrFilter {}, false);
+        let scc = visitor.scc.unwrap();
+        let coaccess = visitor.coaccess;
+
+        let mut scc_sizes = HashMap::new();
+        for c in scc.iter() {
+            *scc_sizes.entry(*c).or_insert(0) += 1;
+        }
+        let mut cyclic = Vec::with_capacity(fst_list.len());
+        for i in 0..fst_list.len() {
+            let self_loop = graph.get_trs(i)?.trs().iter().any(|tr| tr.nextstate == i);
+            cyclic.push(self_loop || scc_sizes[&scc[i]] > 1);
+        }
+
+        let mut access = vec![false; fst_list.len()];
+        access[root_idx] = true;
+        let mut stack = vec![root_idx];
+        while let Some(i) = stack.pop() {
+            for tr in graph.get_trs(i)?.trs() {
+                if !access[tr.nextstate] {
+                    access[tr.nextstate] = true;
+                    stack.push(tr.nextstate);
+                }
+            }
+        }
+
+        Ok(Self {
+            graph,
+            scc,
+            cyclic,
+            access,
+            coaccess,
+        })
+    }
+
+    /// Returns true if a rule reachable from the root depends on itself, in which case
+    /// the grammar can't be expanded to a finite FST.
+    pub(crate) fn cyclic_dependencies(&self) -> bool {
+        self.cyclic
+            .iter()
+            .zip(self.access.iter())
+            .any(|(c, a)| *c && *a)
+    }
+}
+
+/// Utility to analyse and transform the rules of a grammar given as a list of
+/// `(non-terminal, FST)` pairs before calling `replace`.
+///
+/// It computes the dependency graph between the rules, detects cyclic dependencies (i.e.
+/// grammars that are not regular and can't be expanded to an FST), expands some of the
+/// non-terminals in place and removes the useless rules.
+///
+/// The partial expansions use epsilon labels on the call and return transitions.
+#[derive(Debug, Clone)]
+pub struct ReplaceUtil<W: Semiring, F: MutableFst<W> + AllocableFst<W>> {
+    fst_list: Vec<(Label, F)>,
+    root: Label,
+    w: PhantomData<W>,
+}
+
+impl<W, F> ReplaceUtil<W, F>
+where
+    W: Semiring,
+    F: MutableFst<W> + AllocableFst<W>,
+{
+    pub fn new(fst_list: Vec<(Label, F)>, root: Label) -> Result<Self> {
+        if !fst_list.iter().any(|(label, _)| *label == root) {
+            bail!(
+                "ReplaceUtil: No FST corresponding to root label {} in the input tuple vector",
+                root
+            )
+        }
+        Ok(Self {
+            fst_list,
+            root,
+            w: PhantomData,
+        })
+    }
+
+    pub fn root(&self) -> Label {
+        self.root
+    }
+
+    pub fn fst_list(&self) -> &[(Label, F)] {
+        &self.fst_list
+    }
+
+    pub fn into_fst_list(self) -> Vec<(Label, F)> {
+        self.fst_list
+    }
+
+    fn dependencies(&self) -> Result<RuleDependencies<W>> {
+        RuleDependencies::new::<F, _>(&self.fst_list, self.root)
+    }
+
+    /// Returns the dependency graph of the rules : state `i` is the `i`-th rule of the list and
+    /// each call of the rule `j` in the rule `i` is a transition from `i` to `j` labeled with
+    /// the non-terminal of `j`. The start state is the root rule and the rules with a final
+    /// state are final.
+    pub fn dependency_graph(&self) -> Result<VectorFst<W>> {
+        Ok(self.dependencies()?.graph)
+    }
+
+    /// Returns the strongly connected component of each rule in the dependency graph.
+    /// The components are numbered in topological order.
+    pub fn dependency_sccs(&self) -> Result<Vec<i32>> {
+        Ok(self.dependencies()?.scc)
+    }
+
+    /// Returns true if the grammar has cyclic dependencies reachable from the root,
+    /// i.e. if it is not regular and can't be expanded with `replace`.
+    pub fn cyclic_dependencies(&self) -> Result<bool> {
+        Ok(self.dependencies()?.cyclic_dependencies())
+    }
+
+    /// Replaces the non-terminals in `labels` by their rule in all the other rules.
+    /// The root and the non-terminals with cyclic dependencies are never replaced.
+    pub fn replace_labels(&mut self, labels: &[Label]) -> Result<()> {
+        let deps = self.dependencies()?;
+        let to_replace: HashSet<usize> = self
+            .fst_list
+            .iter()
+            .enumerate()
+            .filter(|(i, (label, _))| {
+                *label != self.root && !deps.cyclic[*i] && labels.contains(label)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if to_replace.is_empty() {
+            return Ok(());
+        }
+
+        // The rules are expanded bottom-up so that the replaced rules are already expanded.
+        let order = (0..self.fst_list.len()).sorted_by_key(|i| std::cmp::Reverse(deps.scc[*i]));
+        for i in order {
+            let callees = deps
+                .graph
+                .get_trs(i)?
+                .trs()
+                .iter()
+                .map(|tr| tr.nextstate)
+                .filter(|j| to_replace.contains(j))
+                .unique()
+                .collect_vec();
+            if callees.is_empty() {
+                continue;
+            }
+            // NO_LABEL is used as the label of the expanded rule so that its own non-terminal
+            // is not expanded if the rule is recursive.
+            let mut pairs = vec![(NO_LABEL, &self.fst_list[i].1)];
+            pairs.extend(
+                callees
+                    .iter()
+                    .map(|j| (self.fst_list[*j].0, &self.fst_list[*j].1)),
+            );
+            let expanded: F = ReplaceFst::new(pairs, NO_LABEL, true)?.compute()?;
+            self.fst_list[i].1 = expanded;
+        }
+        Ok(())
+    }
+
+    /// Replaces the non-terminals whose rule has at most `max_states` states, `max_trs`
+    /// transitions and `max_nonterminals` calls to other non-terminals.
+    pub fn replace_by_size(
+        &mut self,
+        max_states: usize,
+        max_trs: usize,
+        max_nonterminals: usize,
+    ) -> Result<()> {
+        let deps = self.dependencies()?;
+        let mut labels = vec![];
+        for (i, (label, fst)) in self.fst_list.iter().enumerate() {
+            let num_trs: usize = (0..fst.num_states())
+                .map(|s| fst.num_trs(s))
+                .sum::<Result<usize, Error>>()?;
+            if fst.num_states() <= max_states
+                && num_trs <= max_trs
+                && deps.graph.num_trs(i)? <= max_nonterminals
+            {
+                labels.push(*label);
+            }
+        }
+        self.replace_labels(&labels)
+    }
+
+    /// Removes the rules that can't be reached from the root or that can't reach a final state.
+    pub fn prune_rules(&mut self) -> Result<()> {
+        let deps = self.dependencies()?;
+        let root = self.root;
+        let mut i = 0;
+        self.fst_list.retain(|(label, _)| {
+            let keep = *label == root || (deps.access[i] && deps.coaccess[i]);
+            i += 1;
+            keep
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::replace::replace;
+    use crate::fst_traits::PathsIterator;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::transducer;
+
+    // Root : 1 2 3, where 2 and 3 are non-terminals.
+    // 2 : 4 3
+    // 3 : 5
+    // 6 : 6 (unreachable)
+    fn grammar() -> Vec<(Label, VectorFst<TropicalWeight>)> {
+        vec![
+            (1, transducer(&[1, 2, 3], &[1, 2, 3], TropicalWeight::one())),
+            (2, transducer(&[4, 3], &[4, 3], TropicalWeight::one())),
+            (3, transducer(&[5], &[5], TropicalWeight::one())),
+            (6, transducer(&[6], &[6], TropicalWeight::one())),
+        ]
+    }
+
+    #[test]
+    fn test_replace_util_dependencies() -> Result<()> {
+        let util = ReplaceUtil::new(grammar(), 1)?;
+        let graph = util.dependency_graph()?;
+        assert_eq!(graph.start(), Some(0));
+        assert_eq!(graph.num_trs(0)?, 2);
+        assert_eq!(graph.num_trs(1)?, 1);
+        assert_eq!(graph.num_trs(2)?, 0);
+        assert!(!util.cyclic_dependencies()?);
+
+        let sccs = util.dependency_sccs()?;
+        assert!(sccs[0] < sccs[1]);
+        assert!(sccs[1] < sccs[2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_util_cyclic_dependencies() -> Result<()> {
+        let mut fst_list = grammar();
+        // 3 : 5 2
+        fst_list[2].1 = transducer(&[5, 2], &[5, 2], TropicalWeight::one());
+        let util = ReplaceUtil::new(fst_list.clone(), 1)?;
+        assert!(util.cyclic_dependencies()?);
+
+        let res: Result<VectorFst<_>, Error> = replace(fst_list, 1, true);
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_util_replace_by_size() -> Result<()> {
+        let fst_list = grammar();
+        let expected: VectorFst<_> = replace(fst_list.clone(), 1, true)?;
+
+        let mut util = ReplaceUtil::new(fst_list, 1)?;
+        util.replace_by_size(2, 1, 0)?;
+        // Only 3 is small enough to be replaced.
+        let deps = util.dependency_graph()?;
+        assert_eq!(deps.num_trs(0)?, 1);
+        assert_eq!(deps.num_trs(1)?, 0);
+
+        let replaced: VectorFst<_> = replace(util.into_fst_list(), 1, true)?;
+        let expected_paths: Vec<_> = expected.paths_iter().collect();
+        let replaced_paths: Vec<_> = replaced.paths_iter().collect();
+        assert_eq!(replaced_paths, expected_paths);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_util_prune_rules() -> Result<()> {
+        let mut fst_list = grammar();
+        // 7 has no final state.
+        let mut no_final: VectorFst<TropicalWeight> = VectorFst::new();
+        let s = no_final.add_state();
+        no_final.set_start(s)?;
+        fst_list.push((7, no_final));
+        fst_list[0].1 = transducer(&[1, 2, 7], &[1, 2, 7], TropicalWeight::one());
+
+        let mut util = ReplaceUtil::new(fst_list, 1)?;
+        util.prune_rules()?;
+        let labels = util
+            .fst_list()
+            .iter()
+            .map(|(label, _)| *label)
+            .collect_vec();
+        assert_eq!(labels, vec![1, 2, 3]);
+        Ok(())
+    }
+}
@@ -1,11 +1,9 @@
-use anyhow::Result;
-
 use crate::fst_properties::mutable_properties::reverse_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
 use crate::semirings::Semiring;
 use crate::tr::Tr;
-use crate::{Trs, EPS_LABEL};
+use crate::{Error, Trs, EPS_LABEL};
 
 /// Reverses an FST. The reversed result is written to an output mutable FST.
 /// If A transduces string x to y with weight a, then the reverse of A
@@ -28,7 +26,7 @@ use crate::{Trs, EPS_LABEL};
 ///
 /// ![reverse_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/reverse_out.svg?sanitize=true)
 ///
-pub fn reverse<W, F1, F2>(ifst: &F1) -> Result<F2>
+pub fn reverse<W, F1, F2>(ifst: &F1) -> Result<F2, Error>
 where
     W: Semiring,
     F1: ExpandedFst<W>,
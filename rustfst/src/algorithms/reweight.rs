@@ -1,13 +1,11 @@
-use anyhow::Result;
-
 use crate::fst_properties::mutable_properties::reweight_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
 use crate::semirings::{DivideType, WeaklyDivisibleSemiring};
-use crate::{Tr, EPS_LABEL};
+use crate::{Error, Tr, EPS_LABEL};
 
 /// Different types of reweighting.
-#[derive(PartialOrd, PartialEq, Copy, Clone)]
+#[derive(PartialOrd, PartialEq, Copy, Clone, Debug)]
 pub enum ReweightType {
     /// Reweight toward initial state.
     ReweightToInitial,
@@ -23,7 +21,11 @@ pub enum ReweightType {
 /// of potential q, is reweighted by p^-1 \otimes (w \otimes q) when reweighting
 /// torwards the initial state, and by (p \otimes w) \otimes q^-1 when
 /// reweighting towards the final states.
-pub fn reweight<W, F>(fst: &mut F, potentials: &[W], reweight_type: ReweightType) -> Result<()>
+pub fn reweight<W, F>(
+    fst: &mut F,
+    potentials: &[W],
+    reweight_type: ReweightType,
+) -> Result<(), Error>
 where
     F: MutableFst<W>,
     W: WeaklyDivisibleSemiring,
@@ -12,7 +12,7 @@ use crate::fst_properties::mutable_properties::rmepsilon_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
 use crate::semirings::Semiring;
-use crate::{Trs, EPS_LABEL};
+use crate::{Error, Trs, EPS_LABEL};
 
 /// This operation removes epsilon-transitions (when both the input and
 /// output labels are an epsilon) from a transducer. The result will be an
@@ -63,11 +63,11 @@ use crate::{Trs, EPS_LABEL};
 ///
 /// ![rmepsilon_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/rmepsilon_out.svg?sanitize=true)
 ///
-pub fn rm_epsilon<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<()> {
+pub fn rm_epsilon<W: Semiring, F: MutableFst<W>>(fst: &mut F) -> Result<(), Error> {
     let tr_filter = EpsilonTrFilter {};
     let queue = AutoQueue::new(fst, None, &tr_filter)?;
     let opts = RmEpsilonInternalConfig::new_with_default(queue);
-    rm_epsilon_with_internal_config(fst, opts)
+    Ok(rm_epsilon_with_internal_config(fst, opts)?)
 }
 pub(crate) fn rm_epsilon_with_internal_config<W: Semiring, F: MutableFst<W>, Q: Queue>(
     fst: &mut F,
@@ -167,7 +167,10 @@ pub(crate) fn rm_epsilon_with_internal_config<W: Semiring, F: MutableFst<W>, Q:
         }
     }
 
-    fst.set_properties(rmepsilon_properties(fst.properties(), false));
+    fst.set_properties_with_mask(
+        rmepsilon_properties(fst.properties(), false),
+        FstProperties::all_properties(),
+    );
 
     if weight_threshold != W::zero() || state_threshold != None {
         todo!("Implement Prune!")
@@ -178,3 +181,24 @@ pub(crate) fn rm_epsilon_with_internal_config<W: Semiring, F: MutableFst<W>, Q:
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_rm_epsilon_properties_proptest(
+            mut fst in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            rm_epsilon(&mut fst).unwrap();
+            prop_assert!(properties_compatible(&fst));
+            prop_assert!(fst.properties().contains(FstProperties::NO_EPSILONS));
+        }
+    }
+}
@@ -1,18 +1,30 @@
 use std::collections::HashSet;
 
-use anyhow::Result;
 use unsafe_unwrap::UnsafeUnwrap;
 
-use crate::algorithms::connect;
+use crate::algorithms::connect::connect_with_state_map;
 use crate::algorithms::dfs_visit::dfs_visit;
 use crate::algorithms::tr_filters::AnyTrFilter;
 use crate::algorithms::visitors::SccVisitor;
 use crate::fst_traits::MutableFst;
 use crate::semirings::Semiring;
-use crate::{Trs, EPS_LABEL};
+use crate::{Error, StateId, Trs, EPS_LABEL};
 
 /// Removes final states that have epsilon-only input trs.
-pub fn rm_final_epsilon<W, F>(ifst: &mut F) -> Result<()>
+pub fn rm_final_epsilon<W, F>(ifst: &mut F) -> Result<(), Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    rm_final_epsilon_with_state_map(ifst)?;
+    Ok(())
+}
+
+/// Same as `rm_final_epsilon` but also returns, for each state of the input FST, its id in the
+/// output FST or `None` if it has been removed.
+pub(crate) fn rm_final_epsilon_with_state_map<W, F>(
+    ifst: &mut F,
+) -> Result<Vec<Option<StateId>>, Error>
 where
     W: Semiring,
     F: MutableFst<W>,
@@ -69,7 +81,5 @@ where
         }
     }
 
-    connect(ifst)?;
-
-    Ok(())
+    Ok(connect_with_state_map(ifst)?)
 }
@@ -6,9 +6,9 @@ use crate::algorithms::queues::AutoQueue;
 use crate::algorithms::tr_filters::{AnyTrFilter, TrFilter};
 use crate::algorithms::Queue;
 use crate::fst_impls::VectorFst;
-use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
 use crate::semirings::{ReverseBack, Semiring, SemiringProperties, WeightQuantize};
-use crate::{StateId, Trs, KSHORTESTDELTA};
+use crate::{Error, StateId, Trs, KSHORTESTDELTA};
 use std::borrow::Borrow;
 
 pub(crate) struct ShortestDistanceInternalConfig<W: Semiring, Q: Queue, A: TrFilter<W>> {
@@ -150,7 +150,7 @@ impl<W: Semiring, Q: Queue, A: TrFilter<W>> ShortestDistanceState<W, Q, A> {
         }
     }
 
-    pub fn shortest_distance<F: ExpandedFst<W>, B: Borrow<F>>(
+    pub fn shortest_distance<F: CoreFst<W>, B: Borrow<F>>(
         &mut self,
         source: Option<StateId>,
         fst: B,
@@ -271,7 +271,10 @@ impl ShortestDistanceConfig {
     }
 }
 
-pub fn shortest_distance<W: Semiring, F: ExpandedFst<W>>(fst: &F, reverse: bool) -> Result<Vec<W>> {
+pub fn shortest_distance<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    reverse: bool,
+) -> Result<Vec<W>, Error> {
     shortest_distance_with_config(fst, reverse, ShortestDistanceConfig::default())
 }
 
@@ -312,13 +315,13 @@ pub fn shortest_distance_with_config<W: Semiring, F: ExpandedFst<W>>(
     fst: &F,
     reverse: bool,
     config: ShortestDistanceConfig,
-) -> Result<Vec<W>> {
+) -> Result<Vec<W>, Error> {
     let delta = config.delta;
     if !reverse {
         let tr_filter = AnyTrFilter {};
         let queue = AutoQueue::new(fst, None, &tr_filter)?;
         let config = ShortestDistanceInternalConfig::new_with_default(tr_filter, queue, delta);
-        shortest_distance_with_internal_config(fst, config)
+        Ok(shortest_distance_with_internal_config(fst, config)?)
     } else {
         let tr_filter = AnyTrFilter {};
         let rfst: VectorFst<_> = crate::algorithms::reverse(fst)?;
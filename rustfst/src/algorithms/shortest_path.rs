@@ -4,6 +4,7 @@ use anyhow::Result;
 use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::algorithms::determinize::determinize_with_distance;
+use crate::algorithms::preconditions::{check_fst_properties, check_semiring_properties};
 use crate::algorithms::queues::AutoQueue;
 use crate::algorithms::tr_filters::AnyTrFilter;
 use crate::algorithms::{
@@ -11,13 +12,13 @@ use crate::algorithms::{
 };
 use crate::fst_impls::VectorFst;
 use crate::fst_properties::mutable_properties::shortest_path_properties;
-use crate::fst_properties::FstProperties;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
 use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
 use crate::semirings::{
     ReverseBack, Semiring, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize,
 };
 use crate::Tr;
-use crate::{StateId, Trs, KSHORTESTDELTA};
+use crate::{Error, StateId, Trs, KSHORTESTDELTA};
 use bitflags::_core::fmt::Formatter;
 use std::fmt::Debug;
 
@@ -60,7 +61,7 @@ impl ShortestPathConfig {
     }
 }
 
-pub fn shortest_path<W, FI, FO>(ifst: &FI) -> Result<FO>
+pub fn shortest_path<W, FI, FO>(ifst: &FI) -> Result<FO, Error>
 where
     FI: ExpandedFst<W>,
     FO: MutableFst<W>,
@@ -90,7 +91,10 @@ where
 ///
 /// ![shortestpath_out_n_2](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/shortestpath_out_n_2.svg?sanitize=true)
 ///
-pub fn shortest_path_with_config<W, FI, FO>(ifst: &FI, config: ShortestPathConfig) -> Result<FO>
+pub fn shortest_path_with_config<W, FI, FO>(
+    ifst: &FI,
+    config: ShortestPathConfig,
+) -> Result<FO, Error>
 where
     FI: ExpandedFst<W>,
     FO: MutableFst<W>,
@@ -107,7 +111,17 @@ where
         return Ok(FO::new());
     }
 
+    if ifst.start().is_none() {
+        let mut fst_res = FO::new();
+        fst_res.set_symts_from_fst(ifst);
+        return Ok(fst_res);
+    }
+
     if nshortest == 1 {
+        check_semiring_properties::<W>(
+            "shortest_path",
+            SemiringProperties::PATH | SemiringProperties::RIGHT_SEMIRING,
+        )?;
         let mut parent = vec![];
         let mut f_parent = None;
         let mut distance = vec![];
@@ -118,8 +132,20 @@ where
         return Ok(fst_res);
     }
 
-    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::SEMIRING) {
-        bail!("ShortestPath : Weight need to have the Path property and be distributive")
+    check_semiring_properties::<W>(
+        "shortest_path",
+        SemiringProperties::PATH | SemiringProperties::SEMIRING,
+    )?;
+    if unique {
+        // The reversed FST is determinized as an acceptor.
+        let mut known = FstProperties::empty();
+        let props = compute_fst_properties(
+            ifst,
+            FstProperties::ACCEPTOR | FstProperties::NOT_ACCEPTOR,
+            &mut known,
+            true,
+        )?;
+        check_fst_properties("shortest_path", props, FstProperties::ACCEPTOR)?;
     }
 
     let mut distance =
@@ -178,11 +204,6 @@ where
     let mut f_distance = W::zero();
     distance.clear();
     queue.clear();
-    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::RIGHT_SEMIRING) {
-        bail!(
-            "SingleShortestPath: Weight needs to have the path property and be right distributive"
-        )
-    }
     distance.resize_with(ifst.num_states(), W::zero);
     enqueued.resize(ifst.num_states(), false);
     parent.resize(ifst.num_states(), None);
@@ -506,3 +527,59 @@ where
     );
     Ok(ofst)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::PreconditionError;
+    use crate::semirings::{LogWeight, TropicalWeight};
+
+    use super::*;
+
+    #[test]
+    fn test_shortest_path_unique_transducer() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 2, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(1, 3, 2.0, 1))?;
+        fst.set_final(1, TropicalWeight::one())?;
+
+        let config = ShortestPathConfig::default()
+            .with_nshortest(2)
+            .with_unique(true);
+        let err = shortest_path_with_config::<_, _, VectorFst<_>>(&fst, config).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingFstProperties {
+                    algorithm: "shortest_path",
+                    missing: FstProperties::ACCEPTOR
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_path_without_path_property() -> Result<()> {
+        let mut fst = VectorFst::<LogWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.set_final(1, LogWeight::one())?;
+
+        let err = shortest_path::<_, _, VectorFst<_>>(&fst).unwrap_err();
+        match err {
+            Error::PropertyViolation(e) => assert_eq!(
+                e,
+                PreconditionError::MissingSemiringProperties {
+                    algorithm: "shortest_path",
+                    missing: SemiringProperties::PATH
+                }
+            ),
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
+}
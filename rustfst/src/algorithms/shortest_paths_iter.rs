@@ -0,0 +1,306 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::algorithms::preconditions::check_semiring_properties;
+use crate::algorithms::queues::{natural_less, ShortestFirstQueue};
+use crate::algorithms::{shortest_distance_with_config, Queue, ShortestDistanceConfig};
+use crate::fst_path::FstPath;
+use crate::fst_traits::{CoreFst, ExpandedFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{Error, Label, StateId, Trs, EPS_LABEL, KSHORTESTDELTA};
+
+/// Configuration of the `ShortestPathsIterator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPathsIteratorConfig<W: Semiring> {
+    /// The iteration stops at the first path whose weight is worse than this one.
+    pub weight_threshold: Option<W>,
+    /// The iteration stops once this number of states has been visited.
+    pub max_state_visits: Option<usize>,
+    /// Delta used to compute the shortest distance to the final states.
+    pub delta: f32,
+}
+
+impl<W: Semiring> Default for ShortestPathsIteratorConfig<W> {
+    fn default() -> Self {
+        Self {
+            weight_threshold: None,
+            max_state_visits: None,
+            delta: KSHORTESTDELTA,
+        }
+    }
+}
+
+impl<W: Semiring> ShortestPathsIteratorConfig<W> {
+    pub fn with_weight_threshold(self, weight_threshold: Option<W>) -> Self {
+        Self {
+            weight_threshold,
+            ..self
+        }
+    }
+
+    pub fn with_max_state_visits(self, max_state_visits: Option<usize>) -> Self {
+        Self {
+            max_state_visits,
+            ..self
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+/// Partial path stored as a tree : each item points to the item it extends.
+struct PathItem<W> {
+    // `None` once the final weight has been read.
+    state: Option<StateId>,
+    prev: Option<usize>,
+    ilabel: Label,
+    olabel: Label,
+    weight: W,
+    // Weight of the best successful path extending this one.
+    priority: W,
+}
+
+/// Iterator over the successful paths of an FST by increasing weight w.r.t. the natural order.
+///
+/// The paths are computed on demand : the partial paths are expanded in the order of the best
+/// successful path they can lead to, using the shortest distance from each state to the final
+/// states. The iteration stops when the caller stops pulling, when the weight of the next path
+/// would exceed `weight_threshold` or when `max_state_visits` states have been expanded. The
+/// semiring must have the path property.
+///
+/// Each item is a `Result` : reading the FST (e.g a lazy one) can fail, in which case the error
+/// is returned and the iteration ends.
+pub struct ShortestPathsIterator<'a, W: Semiring, F: ExpandedFst<W>> {
+    fst: &'a F,
+    distance: Vec<W>,
+    items: Rc<RefCell<Vec<PathItem<W>>>>,
+    queue: Box<dyn Queue>,
+    weight_threshold: Option<W>,
+    max_state_visits: Option<usize>,
+    num_state_visits: usize,
+}
+
+impl<'a, W: Semiring, F: ExpandedFst<W>> ShortestPathsIterator<'a, W, F> {
+    pub fn new(fst: &'a F, config: ShortestPathsIteratorConfig<W>) -> Result<Self, Error> {
+        check_semiring_properties::<W>("shortest_paths_iter", SemiringProperties::PATH)?;
+        let distance =
+            shortest_distance_with_config(fst, true, ShortestDistanceConfig::new(config.delta))?;
+        let items: Rc<RefCell<Vec<PathItem<W>>>> = Rc::new(RefCell::new(vec![]));
+        let items_cmp = Rc::clone(&items);
+        // The queue pops the greatest element : the best priority must compare as the greatest.
+        let queue = ShortestFirstQueue::new(move |i1: &StateId, i2: &StateId| {
+            let items = items_cmp.borrow();
+            if natural_less(&items[*i1].priority, &items[*i2].priority).unwrap() {
+                Ordering::Greater
+            } else if natural_less(&items[*i2].priority, &items[*i1].priority).unwrap() {
+                Ordering::Less
+            } else {
+                // Oldest first among equal priorities.
+                i2.cmp(i1)
+            }
+        });
+
+        let mut iter = Self {
+            fst,
+            distance,
+            items,
+            queue: Box::new(queue),
+            weight_threshold: config.weight_threshold,
+            max_state_visits: config.max_state_visits,
+            num_state_visits: 0,
+        };
+        if let Some(start) = fst.start() {
+            iter.push(Some(start), None, EPS_LABEL, EPS_LABEL, W::one())?;
+        }
+        Ok(iter)
+    }
+
+    fn push(
+        &mut self,
+        state: Option<StateId>,
+        prev: Option<usize>,
+        ilabel: Label,
+        olabel: Label,
+        weight: W,
+    ) -> Result<()> {
+        let priority = match state {
+            Some(s) => match self.distance.get(s) {
+                Some(d) if !d.is_zero() => weight.times(d)?,
+                // No final state can be reached.
+                _ => return Ok(()),
+            },
+            None => weight.clone(),
+        };
+        let id = {
+            let mut items = self.items.borrow_mut();
+            items.push(PathItem {
+                state,
+                prev,
+                ilabel,
+                olabel,
+                weight,
+                priority,
+            });
+            items.len() - 1
+        };
+        self.queue.enqueue(id);
+        Ok(())
+    }
+
+    fn path(&self, id: usize) -> FstPath<W> {
+        let items = self.items.borrow();
+        let mut ilabels = vec![];
+        let mut olabels = vec![];
+        let mut current = Some(id);
+        while let Some(i) = current {
+            let item = &items[i];
+            if item.ilabel != EPS_LABEL {
+                ilabels.push(item.ilabel);
+            }
+            if item.olabel != EPS_LABEL {
+                olabels.push(item.olabel);
+            }
+            current = item.prev;
+        }
+        ilabels.reverse();
+        olabels.reverse();
+        FstPath::new(ilabels, olabels, items[id].weight.clone())
+    }
+
+    fn next_path(&mut self) -> Result<Option<FstPath<W>>> {
+        while let Some(id) = self.queue.head() {
+            self.queue.dequeue();
+            let (state, weight) = {
+                let items = self.items.borrow();
+                let item = &items[id];
+                if let Some(threshold) = &self.weight_threshold {
+                    if natural_less(threshold, &item.priority)? {
+                        self.queue.clear();
+                        return Ok(None);
+                    }
+                }
+                (item.state, item.weight.clone())
+            };
+            let state = match state {
+                Some(s) => s,
+                None => return Ok(Some(self.path(id))),
+            };
+
+            if let Some(max_state_visits) = self.max_state_visits {
+                if self.num_state_visits >= max_state_visits {
+                    self.queue.clear();
+                    return Ok(None);
+                }
+            }
+            self.num_state_visits += 1;
+
+            if let Some(final_weight) = self.fst.final_weight(state)? {
+                self.push(
+                    None,
+                    Some(id),
+                    EPS_LABEL,
+                    EPS_LABEL,
+                    weight.times(final_weight)?,
+                )?;
+            }
+            for tr in self.fst.get_trs(state)?.trs() {
+                self.push(
+                    Some(tr.nextstate),
+                    Some(id),
+                    tr.ilabel,
+                    tr.olabel,
+                    weight.times(&tr.weight)?,
+                )?;
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a, W: Semiring, F: ExpandedFst<W>> Iterator for ShortestPathsIterator<'a, W, F> {
+    type Item = Result<FstPath<W>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_path() {
+            Ok(path) => path.map(Ok),
+            Err(e) => {
+                // The iteration ends after an error.
+                self.queue.clear();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{LogWeight, TropicalWeight};
+    use crate::Tr;
+
+    fn fst() -> Result<VectorFst<TropicalWeight>> {
+        // Paths : a (1.0), b c (1.5), b d (3.0) and a cycle e (2.0) on the start state.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s2, TropicalWeight::new(0.5))?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::new(0.5), s2))?;
+        fst.add_tr(s0, Tr::new(2, 2, TropicalWeight::new(0.5), s1))?;
+        fst.add_tr(s0, Tr::new(5, 5, TropicalWeight::new(2.0), s0))?;
+        fst.add_tr(s1, Tr::new(3, 3, TropicalWeight::new(0.5), s2))?;
+        fst.add_tr(s1, Tr::new(4, 4, TropicalWeight::new(2.0), s2))?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_shortest_paths_iterator_order() -> Result<()> {
+        let fst = fst()?;
+        let paths = ShortestPathsIterator::new(&fst, ShortestPathsIteratorConfig::default())?
+            .take(4)
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            paths,
+            vec![
+                FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0)),
+                FstPath::new(vec![2, 3], vec![2, 3], TropicalWeight::new(1.5)),
+                FstPath::new(vec![2, 4], vec![2, 4], TropicalWeight::new(3.0)),
+                FstPath::new(vec![5, 1], vec![5, 1], TropicalWeight::new(3.0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iterator_threshold_and_budget() -> Result<()> {
+        let fst = fst()?;
+        let config = ShortestPathsIteratorConfig::default()
+            .with_weight_threshold(Some(TropicalWeight::new(2.0)));
+        let paths = ShortestPathsIterator::new(&fst, config)?
+            .map(|p| p.map(|p| p.ilabels))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(paths, vec![vec![1], vec![2, 3]]);
+
+        // The start state and s2 are enough for the first path only.
+        let config = ShortestPathsIteratorConfig::default().with_max_state_visits(Some(2));
+        let paths = ShortestPathsIterator::new(&fst, config)?
+            .map(|p| p.map(|p| p.ilabels))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(paths, vec![vec![1]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iterator_requires_path_property() {
+        let fst = VectorFst::<LogWeight>::new();
+        assert!(ShortestPathsIterator::new(&fst, ShortestPathsIteratorConfig::default()).is_err());
+    }
+}
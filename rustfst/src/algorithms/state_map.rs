@@ -0,0 +1,51 @@
+use anyhow::Result;
+
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Error, Tr};
+
+/// The StateMapper interface defines how the transitions and the final weight of a state
+/// are mapped. Unlike a `TrMapper`, it sees all the transitions leaving a state at once, so
+/// it can merge, remove, reorder or reweight them. The destination states must be states of
+/// the FST.
+pub trait StateMapper<W: Semiring> {
+    /// Maps the final weight and the transitions leaving a state.
+    fn map_state(&self, final_weight: &mut Option<W>, trs: &mut Vec<Tr<W>>) -> Result<()>;
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties;
+}
+
+impl<W: Semiring, M: StateMapper<W> + ?Sized> StateMapper<W> for &M {
+    fn map_state(&self, final_weight: &mut Option<W>, trs: &mut Vec<Tr<W>>) -> Result<()> {
+        (**self).map_state(final_weight, trs)
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        (**self).properties(inprops)
+    }
+}
+
+/// Maps every state of the FST using a `StateMapper` object.
+pub fn state_map<W, F, M>(ifst: &mut F, mapper: &M) -> Result<(), Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+    M: StateMapper<W>,
+{
+    let inprops = ifst.properties();
+    for state in 0..ifst.num_states() {
+        let mut trs = ifst.pop_trs(state)?;
+        let mut final_weight = ifst.take_final_weight(state)?;
+        mapper.map_state(&mut final_weight, &mut trs)?;
+        unsafe {
+            // Checked by the calls above.
+            ifst.set_trs_unchecked(state, trs);
+            if let Some(weight) = final_weight {
+                ifst.set_final_unchecked(state, weight);
+            }
+        }
+    }
+    ifst.set_properties_with_mask(mapper.properties(inprops), FstProperties::all_properties());
+    Ok(())
+}
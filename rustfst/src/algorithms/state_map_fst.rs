@@ -0,0 +1,297 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::{FstOp2, LazyFst2, SimpleHashMapCache};
+use crate::algorithms::StateMapper;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::Semiring;
+use crate::{Error, StateId, SymbolTable, Trs, TrsVec};
+
+pub struct StateMapFstOp<W: Semiring, F: Fst<W>, B: Borrow<F>, M: StateMapper<W>> {
+    fst: B,
+    mapper: M,
+    properties: FstProperties,
+    w: PhantomData<W>,
+    f: PhantomData<F>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, M: StateMapper<W>> Debug for StateMapFstOp<W, F, B, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StateMapFstOp {{ fst : {:?} }}", self.fst.borrow())
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, M: StateMapper<W>> StateMapFstOp<W, F, B, M> {
+    pub fn new(fst: B, mapper: M) -> Self {
+        let properties = mapper.properties(fst.borrow().properties());
+        Self {
+            fst,
+            mapper,
+            properties,
+            w: PhantomData,
+            f: PhantomData,
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, M: StateMapper<W>> FstOp2<W>
+    for StateMapFstOp<W, F, B, M>
+{
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start())
+    }
+
+    fn compute_trs_and_final_weight(&self, state: usize) -> Result<(TrsVec<W>, Option<W>)> {
+        let mut trs = self.fst.borrow().get_trs(state)?.trs().to_vec();
+        let mut final_weight = self.fst.borrow().final_weight(state)?;
+        self.mapper.map_state(&mut final_weight, &mut trs)?;
+        Ok((TrsVec(Arc::new(trs)), final_weight))
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
+
+/// Delayed version of `state_map`: the states are mapped when they are expanded.
+pub struct StateMapFst<W: Semiring, F: Fst<W>, B: Borrow<F>, M: StateMapper<W>>(
+    LazyFst2<W, StateMapFstOp<W, F, B, M>, SimpleHashMapCache<W>>,
+);
+
+impl<W, F, B, M> CoreFst<W> for StateMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: StateMapper<W>,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B, M> StateIterator<'a> for StateMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+    M: StateMapper<W> + 'a,
+{
+    type Iter =
+        <LazyFst2<W, StateMapFstOp<W, F, B, M>, SimpleHashMapCache<W>> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B, M> FstIterator<'a, W> for StateMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+    M: StateMapper<W> + 'a,
+{
+    type FstIter = <LazyFst2<W, StateMapFstOp<W, F, B, M>, SimpleHashMapCache<W>> as FstIterator<
+        'a,
+        W,
+    >>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B, M> Fst<W> for StateMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + 'static,
+    M: StateMapper<W> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B, M> Debug for StateMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: StateMapper<W>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B, M> StateMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: StateMapper<W>,
+{
+    pub fn new(fst: B, mapper: M) -> Self {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = StateMapFstOp::new(fst, mapper);
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst2::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        StateMapFst(lazy_fst)
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W>>(&self) -> Result<F2, Error> {
+        self.0.compute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::state_map;
+    use crate::algorithms::state_mappers::{
+        NormalizeMapper, TrSortMapper, TrSumMapper, TrUniqueMapper,
+    };
+    use crate::algorithms::tr_compares::OLabelCompare;
+    use crate::algorithms::{tr_sort, tr_sum, tr_unique};
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{ProbabilityWeight, TropicalWeight};
+    use crate::Tr;
+
+    fn build_fst() -> Result<VectorFst<ProbabilityWeight>> {
+        let mut fst = VectorFst::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(2, 1, 0.25, s1))?;
+        fst.add_tr(s0, Tr::new(1, 3, 0.5, s1))?;
+        fst.add_tr(s0, Tr::new(2, 1, 0.25, s1))?;
+        fst.add_tr(s0, Tr::new(2, 1, 1.0, s1))?;
+        fst.add_tr(s1, Tr::new(1, 1, 0.5, s0))?;
+        fst.set_final(s1, 1.5)?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_state_map_fst_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<StateMapFst<TropicalWeight, VectorFst<_>, VectorFst<_>, TrSumMapper>>();
+    }
+
+    #[test]
+    fn test_state_map_tr_sum_unique_sort() -> Result<()> {
+        let fst = build_fst()?;
+
+        let mut ref_fst = fst.clone();
+        tr_sum(&mut ref_fst);
+        let mut mapped = fst.clone();
+        state_map(&mut mapped, &TrSumMapper {})?;
+        assert_eq!(mapped, ref_fst);
+        let lazy: StateMapFst<_, VectorFst<_>, _, _> = StateMapFst::new(&fst, TrSumMapper {});
+        assert_eq!(lazy.compute::<VectorFst<_>>()?, ref_fst);
+
+        let mut ref_fst = fst.clone();
+        tr_unique(&mut ref_fst);
+        let mut mapped = fst.clone();
+        state_map(&mut mapped, &TrUniqueMapper {})?;
+        assert_eq!(mapped, ref_fst);
+        assert_eq!(mapped.num_trs(0)?, 3);
+
+        let mut ref_fst = fst.clone();
+        tr_sort(&mut ref_fst, OLabelCompare {});
+        let lazy: StateMapFst<_, VectorFst<_>, _, _> =
+            StateMapFst::new(&fst, TrSortMapper::new(OLabelCompare {}));
+        assert_eq!(lazy.compute::<VectorFst<_>>()?, ref_fst);
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_map_normalize() -> Result<()> {
+        let fst = build_fst()?;
+        let mut ref_fst = VectorFst::<ProbabilityWeight>::new();
+        let s0 = ref_fst.add_state();
+        let s1 = ref_fst.add_state();
+        ref_fst.set_start(s0)?;
+        ref_fst.add_tr(s0, Tr::new(2, 1, 0.125, s1))?;
+        ref_fst.add_tr(s0, Tr::new(1, 3, 0.25, s1))?;
+        ref_fst.add_tr(s0, Tr::new(2, 1, 0.125, s1))?;
+        ref_fst.add_tr(s0, Tr::new(2, 1, 0.5, s1))?;
+        ref_fst.add_tr(s1, Tr::new(1, 1, 0.25, s0))?;
+        ref_fst.set_final(s1, 0.75)?;
+
+        let mut mapped = fst.clone();
+        state_map(&mut mapped, &NormalizeMapper {})?;
+        assert_eq!(mapped, ref_fst);
+
+        let lazy: StateMapFst<_, VectorFst<_>, _, _> = StateMapFst::new(&fst, NormalizeMapper {});
+        assert_eq!(lazy.compute::<VectorFst<_>>()?, ref_fst);
+        Ok(())
+    }
+}
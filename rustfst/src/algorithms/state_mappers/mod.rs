@@ -0,0 +1,9 @@
+mod normalize_mapper;
+mod tr_sort_mapper;
+mod tr_sum_mapper;
+mod tr_unique_mapper;
+
+pub use self::normalize_mapper::NormalizeMapper;
+pub use self::tr_sort_mapper::TrSortMapper;
+pub use self::tr_sum_mapper::TrSumMapper;
+pub use self::tr_unique_mapper::TrUniqueMapper;
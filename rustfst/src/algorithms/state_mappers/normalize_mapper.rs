@@ -0,0 +1,34 @@
+use anyhow::Result;
+
+use crate::algorithms::StateMapper;
+use crate::fst_properties::FstProperties;
+use crate::semirings::{DivideType, Semiring, WeaklyDivisibleSemiring};
+use crate::Tr;
+
+/// Mapper that divides the weights of the transitions leaving the state and its final weight
+/// by their sum, so that they sum to `one()`. The states from which nothing can be reached
+/// are left unchanged.
+pub struct NormalizeMapper {}
+
+impl<W: WeaklyDivisibleSemiring> StateMapper<W> for NormalizeMapper {
+    fn map_state(&self, final_weight: &mut Option<W>, trs: &mut Vec<Tr<W>>) -> Result<()> {
+        let mut total = final_weight.clone().unwrap_or_else(W::zero);
+        for tr in trs.iter() {
+            total.plus_assign(&tr.weight)?;
+        }
+        if total.is_zero() {
+            return Ok(());
+        }
+        if let Some(weight) = final_weight {
+            weight.divide_assign(&total, DivideType::DivideLeft)?;
+        }
+        for tr in trs.iter_mut() {
+            tr.weight.divide_assign(&total, DivideType::DivideLeft)?;
+        }
+        Ok(())
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        inprops & FstProperties::weight_invariant_properties()
+    }
+}
@@ -0,0 +1,31 @@
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::algorithms::tr_compares::TrCompare;
+use crate::algorithms::StateMapper;
+use crate::fst_properties::FstProperties;
+use crate::semirings::Semiring;
+use crate::Tr;
+
+/// Mapper that sorts the transitions leaving the state. Same as `tr_sort`.
+pub struct TrSortMapper<C: TrCompare> {
+    c: PhantomData<C>,
+}
+
+impl<C: TrCompare> TrSortMapper<C> {
+    pub fn new(_comp: C) -> Self {
+        Self { c: PhantomData }
+    }
+}
+
+impl<W: Semiring, C: TrCompare> StateMapper<W> for TrSortMapper<C> {
+    fn map_state(&self, _final_weight: &mut Option<W>, trs: &mut Vec<Tr<W>>) -> Result<()> {
+        trs.sort_by(C::compare);
+        Ok(())
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        C::properties(inprops)
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+
+use crate::algorithms::tr_unique::tr_compare;
+use crate::algorithms::StateMapper;
+use crate::fst_properties::FstProperties;
+use crate::semirings::Semiring;
+use crate::Tr;
+
+/// Mapper that plus-sums the weights of the transitions leaving the state with the same
+/// input label, output label and destination state. Same as `tr_sum`.
+pub struct TrSumMapper {}
+
+impl<W: Semiring> StateMapper<W> for TrSumMapper {
+    fn map_state(&self, _final_weight: &mut Option<W>, trs: &mut Vec<Tr<W>>) -> Result<()> {
+        trs.sort_by(tr_compare);
+        let mut summed_trs: Vec<Tr<W>> = Vec::with_capacity(trs.len());
+        for tr in trs.drain(..) {
+            match summed_trs.last_mut() {
+                Some(last)
+                    if last.ilabel == tr.ilabel
+                        && last.olabel == tr.olabel
+                        && last.nextstate == tr.nextstate =>
+                {
+                    last.weight.plus_assign(&tr.weight)?;
+                    continue;
+                }
+                _ => {}
+            }
+            summed_trs.push(tr);
+        }
+        *trs = summed_trs;
+        Ok(())
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        inprops
+            & FstProperties::arcsort_properties()
+            & FstProperties::delete_arcs_properties()
+            & FstProperties::weight_invariant_properties()
+    }
+}
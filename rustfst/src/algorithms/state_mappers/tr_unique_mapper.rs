@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+use crate::algorithms::tr_unique::tr_compare;
+use crate::algorithms::StateMapper;
+use crate::fst_properties::FstProperties;
+use crate::semirings::Semiring;
+use crate::Tr;
+
+/// Mapper that keeps a single instance of the transitions leaving the state with the same
+/// input label, output label, weight and destination state. Same as `tr_unique`.
+pub struct TrUniqueMapper {}
+
+impl<W: Semiring> StateMapper<W> for TrUniqueMapper {
+    fn map_state(&self, _final_weight: &mut Option<W>, trs: &mut Vec<Tr<W>>) -> Result<()> {
+        trs.sort_by(tr_compare);
+        trs.dedup();
+        Ok(())
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        inprops & FstProperties::arcsort_properties() & FstProperties::delete_arcs_properties()
+    }
+}
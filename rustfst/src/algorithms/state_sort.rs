@@ -1,26 +1,25 @@
 use std::mem::swap;
 
-use anyhow::{ensure, Result};
-
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
 use crate::semirings::Semiring;
-use crate::{StateId, Trs};
+use crate::{Error, StateId, Trs};
 
 /// Sorts the input states of an FST. order[i] gives the the state ID after
 /// sorting that corresponds to the state ID i before sorting; it must
 /// therefore be a permutation of the input FST's states ID sequence.
-pub fn state_sort<W, F>(fst: &mut F, order: &[StateId]) -> Result<()>
+pub fn state_sort<W, F>(fst: &mut F, order: &[StateId]) -> Result<(), Error>
 where
     W: Semiring,
     F: MutableFst<W>,
 {
-    ensure!(
-        order.len() == fst.num_states(),
-        "StateSort : Bad order vector size : {}. Expected {}",
-        order.len(),
-        fst.num_states()
-    );
+    if order.len() != fst.num_states() {
+        return Err(Error::Other(format_err!(
+            "StateSort : Bad order vector size : {}. Expected {}",
+            order.len(),
+            fst.num_states()
+        )));
+    }
     if fst.start().is_none() {
         return Ok(());
     }
@@ -75,3 +74,24 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_state_sort_properties_proptest(
+            mut fst in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            let order: Vec<_> = (0..fst.num_states()).rev().collect();
+            state_sort(&mut fst, &order).unwrap();
+            prop_assert!(properties_compatible(&fst));
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::algorithms::{relabel_pairs, ProjectType};
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+use crate::{Error, Label, SymbolTable, Trs, EPS_LABEL};
+
+/// Behaviour of `relabel_by_symbols` when a symbol of the old symbol table is missing
+/// from the new one, or when a label of the FST is missing from the old symbol table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnknownSymbolPolicy {
+    /// Returns an error.
+    Error,
+    /// Maps the missing symbols (and the labels without symbol) to the label of this symbol
+    /// in the new symbol table.
+    MapToSymbol(String),
+    /// Adds the missing symbols at the end of the new symbol table. As they have no symbol,
+    /// the labels of the FST missing from the old symbol table still return an error.
+    AddToTable,
+}
+
+/// Returns the labels used on the transitions of an FST, on the input side for
+/// `ProjectInput` and on the output side for `ProjectOutput`.
+fn fst_labels<W, F>(fst: &F, project_type: ProjectType) -> BTreeSet<Label>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let mut labels = BTreeSet::new();
+    for s in 0..fst.num_states() {
+        for tr in unsafe { fst.get_trs_unchecked(s).trs() } {
+            labels.insert(match project_type {
+                ProjectType::ProjectInput => tr.ilabel,
+                ProjectType::ProjectOutput => tr.olabel,
+            });
+        }
+    }
+    labels
+}
+
+/// Computes the relabeling pairs from `old_symt` to `new_symt`. The symbol table returned is
+/// `new_symt`, completed with the missing symbols if the policy is `AddToTable`.
+/// `fst_labels` are the labels of the FST on this side, to which the policy is also applied
+/// when they are missing from `old_symt`. `side` names the side in the errors.
+fn relabeling_pairs(
+    old_symt: &SymbolTable,
+    new_symt: Arc<SymbolTable>,
+    fst_labels: &BTreeSet<Label>,
+    unknown_policy: &UnknownSymbolPolicy,
+    side: &str,
+) -> Result<(Vec<(Label, Label)>, Arc<SymbolTable>), Error> {
+    let missing_symbol = |symbol: &str| {
+        Error::SymbolTable(format!(
+            "Symbol {} is not present in the new {} symbol table",
+            symbol, side
+        ))
+    };
+    let unknown_label = match unknown_policy {
+        UnknownSymbolPolicy::MapToSymbol(symbol) => Some(
+            new_symt
+                .get_label(symbol)
+                .ok_or_else(|| missing_symbol(symbol))?,
+        ),
+        _ => None,
+    };
+
+    let mut added_symt: Option<SymbolTable> = None;
+    let mut pairs = Vec::with_capacity(old_symt.len());
+    for (old_label, symbol) in old_symt.iter() {
+        let new_label = match new_symt.get_label(symbol) {
+            Some(label) => label,
+            None => match unknown_policy {
+                UnknownSymbolPolicy::Error => return Err(missing_symbol(symbol)),
+                UnknownSymbolPolicy::MapToSymbol(_) => unknown_label.unwrap(),
+                UnknownSymbolPolicy::AddToTable => added_symt
+                    .get_or_insert_with(|| new_symt.as_ref().clone())
+                    .add_symbol(symbol),
+            },
+        };
+        pairs.push((old_label, new_label));
+    }
+
+    for &label in fst_labels {
+        if label == EPS_LABEL || old_symt.get_symbol(label).is_some() {
+            continue;
+        }
+        match unknown_policy {
+            UnknownSymbolPolicy::MapToSymbol(_) => pairs.push((label, unknown_label.unwrap())),
+            _ => {
+                return Err(Error::SymbolTable(format!(
+                    "Label {} is not present in the old {} symbol table",
+                    label, side
+                )))
+            }
+        }
+    }
+
+    let new_symt = added_symt.map(Arc::new).unwrap_or(new_symt);
+    Ok((pairs, new_symt))
+}
+
+/// Relabels an FST from its current symbol tables to new ones, matching the labels by their
+/// symbol. Once relabeled, the new symbol tables are attached to the FST.
+///
+/// Nothing is done on a side if the corresponding new symbol table is `None`. Otherwise,
+/// the FST must have a symbol table on this side.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use anyhow::Result;
+/// # use std::sync::Arc;
+/// # use rustfst::utils::transducer;
+/// # use rustfst::semirings::{Semiring, IntegerWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::Fst;
+/// # use rustfst::algorithms::{relabel_by_symbols, UnknownSymbolPolicy};
+/// # use rustfst::SymbolTable;
+/// # fn main() -> Result<()> {
+/// let mut fst : VectorFst<IntegerWeight> = fst![1 => 2];
+/// fst.set_input_symbols(Arc::new(symt!["a", "b"]));
+/// fst.set_output_symbols(Arc::new(symt!["a", "b"]));
+///
+/// relabel_by_symbols(
+///     &mut fst,
+///     Some(Arc::new(symt!["b", "a"])),
+///     Some(Arc::new(symt!["b"])),
+///     UnknownSymbolPolicy::AddToTable,
+/// )?;
+///
+/// assert_eq!(fst, fst![2 => 1]);
+/// assert_eq!(fst.output_symbols().unwrap().get_symbol(2), Some("a"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn relabel_by_symbols<W, F>(
+    fst: &mut F,
+    new_isymt: Option<Arc<SymbolTable>>,
+    new_osymt: Option<Arc<SymbolTable>>,
+    unknown_policy: UnknownSymbolPolicy,
+) -> Result<(), Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let mut ipairs = vec![];
+    let mut opairs = vec![];
+    let mut isymt = None;
+    let mut osymt = None;
+
+    if let Some(new_isymt) = new_isymt {
+        let old_isymt = fst
+            .input_symbols()
+            .ok_or_else(|| Error::SymbolTable("The FST has no input symbol table".to_string()))?;
+        let labels = fst_labels(fst, ProjectType::ProjectInput);
+        let (pairs, symt) =
+            relabeling_pairs(old_isymt, new_isymt, &labels, &unknown_policy, "input")?;
+        ipairs = pairs;
+        isymt = Some(symt);
+    }
+
+    if let Some(new_osymt) = new_osymt {
+        let old_osymt = fst
+            .output_symbols()
+            .ok_or_else(|| Error::SymbolTable("The FST has no output symbol table".to_string()))?;
+        let labels = fst_labels(fst, ProjectType::ProjectOutput);
+        let (pairs, symt) =
+            relabeling_pairs(old_osymt, new_osymt, &labels, &unknown_policy, "output")?;
+        opairs = pairs;
+        osymt = Some(symt);
+    }
+
+    relabel_pairs(fst, ipairs, opairs)?;
+
+    if let Some(symt) = isymt {
+        fst.set_input_symbols(symt);
+    }
+    if let Some(symt) = osymt {
+        fst.set_output_symbols(symt);
+    }
+
+    Ok(())
+}
+
+/// Merges two symbol tables. All the symbols of `symt_1` keep their label. The symbols of
+/// `symt_2` missing from `symt_1` are added at the end of the merged table.
+///
+/// Returns the merged table and the mapping from the labels of `symt_2` to the labels of the
+/// merged table. Only the labels that changed are present in the mapping which can be used
+/// directly with `relabel_pairs`.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use rustfst::algorithms::merge_symbol_tables;
+/// # use rustfst::SymbolTable;
+/// # fn main() {
+/// let symt_1 = symt!["a", "b"];
+/// let symt_2 = symt!["c", "b"];
+///
+/// let (merged, remap) = merge_symbol_tables(&symt_1, &symt_2);
+///
+/// assert_eq!(merged, symt!["a", "b", "c"]);
+/// assert_eq!(remap.len(), 1);
+/// assert_eq!(remap[&1], 3);
+/// # }
+/// ```
+pub fn merge_symbol_tables(
+    symt_1: &SymbolTable,
+    symt_2: &SymbolTable,
+) -> (SymbolTable, HashMap<Label, Label>) {
+    let mut merged = symt_1.clone();
+    let mut remap = HashMap::new();
+    for (label, symbol) in symt_2.iter() {
+        let new_label = merged.add_symbol(symbol);
+        if new_label != label {
+            remap.insert(label, new_label);
+        }
+    }
+    (merged, remap)
+}
+
+/// Removes from a symbol table of an FST all the symbols not used on its transitions.
+/// Epsilon is always kept.
+///
+/// The labels of a `SymbolTable` are contiguous so the remaining symbols are renumbered
+/// (keeping their relative order) and the FST is relabeled accordingly.
+///
+/// The symbol table pruned is the input one for `ProjectInput` and the output one for
+/// `ProjectOutput`. An error is returned if the FST has no symbol table on this side or
+/// if a label of the FST is missing from it.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use anyhow::Result;
+/// # use std::sync::Arc;
+/// # use rustfst::utils::transducer;
+/// # use rustfst::semirings::{Semiring, IntegerWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::Fst;
+/// # use rustfst::algorithms::{prune_symbol_table, ProjectType};
+/// # use rustfst::SymbolTable;
+/// # fn main() -> Result<()> {
+/// let mut fst : VectorFst<IntegerWeight> = fst![3 => 2];
+/// fst.set_input_symbols(Arc::new(symt!["a", "b", "c"]));
+///
+/// prune_symbol_table(&mut fst, ProjectType::ProjectInput)?;
+///
+/// assert_eq!(fst, fst![1 => 2]);
+/// assert_eq!(fst.input_symbols().unwrap().as_ref(), &symt!["c"]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn prune_symbol_table<W, F>(fst: &mut F, project_type: ProjectType) -> Result<(), Error>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let symt = match project_type {
+        ProjectType::ProjectInput => fst.input_symbols(),
+        ProjectType::ProjectOutput => fst.output_symbols(),
+    }
+    .ok_or_else(|| Error::SymbolTable("The FST has no symbol table to prune".to_string()))?;
+
+    let mut used_labels = fst_labels(fst, project_type);
+    used_labels.insert(EPS_LABEL);
+
+    let mut pruned_symt = SymbolTable::empty();
+    pruned_symt.set_name(symt.name());
+    let mut pairs = Vec::with_capacity(used_labels.len());
+    for label in used_labels {
+        let symbol = symt.get_symbol(label).ok_or_else(|| {
+            Error::SymbolTable(format!("Label {} is missing from the symbol table", label))
+        })?;
+        pairs.push((label, pruned_symt.add_symbol(symbol)));
+    }
+    let pruned_symt = Arc::new(pruned_symt);
+
+    match project_type {
+        ProjectType::ProjectInput => {
+            relabel_pairs(fst, pairs, vec![])?;
+            fst.set_input_symbols(pruned_symt);
+        }
+        ProjectType::ProjectOutput => {
+            relabel_pairs(fst, vec![], pairs)?;
+            fst.set_output_symbols(pruned_symt);
+        }
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::tr::Tr;
+
+    use super::*;
+
+    fn symt_from(symbols: &[&str]) -> Arc<SymbolTable> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbols(symbols.iter().cloned());
+        Arc::new(symt)
+    }
+
+    #[test]
+    fn test_relabel_by_symbols_unknown_policy() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 2, TropicalWeight::one(), s1))?;
+        fst.add_tr(s0, Tr::new(3, 0, TropicalWeight::one(), s1))?;
+        fst.set_input_symbols(symt_from(&["a", "b", "c"]));
+
+        let mut fst_err = fst.clone();
+        let res = relabel_by_symbols(
+            &mut fst_err,
+            Some(symt_from(&["c", "a", "<unk>"])),
+            None,
+            UnknownSymbolPolicy::Error,
+        );
+        assert!(res.is_err());
+
+        relabel_by_symbols(
+            &mut fst,
+            Some(symt_from(&["c", "a", "<unk>"])),
+            None,
+            UnknownSymbolPolicy::MapToSymbol("<unk>".to_string()),
+        )?;
+
+        let ilabels: Vec<_> = fst.get_trs(s0)?.trs().iter().map(|tr| tr.ilabel).collect();
+        let olabels: Vec<_> = fst.get_trs(s0)?.trs().iter().map(|tr| tr.olabel).collect();
+        assert_eq!(ilabels, vec![2, 1]);
+        assert_eq!(olabels, vec![2, 0]);
+        assert_eq!(fst.input_symbols().unwrap().len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relabel_by_symbols_label_missing_from_symt() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        // Label 5 has no symbol in the input symbol table.
+        fst.add_tr(s0, Tr::new(5, 5, TropicalWeight::one(), s1))?;
+        fst.set_input_symbols(symt_from(&["a"]));
+
+        for policy in vec![UnknownSymbolPolicy::Error, UnknownSymbolPolicy::AddToTable] {
+            let mut fst_err = fst.clone();
+            let res =
+                relabel_by_symbols(&mut fst_err, Some(symt_from(&["<unk>", "a"])), None, policy);
+            assert!(res.is_err());
+        }
+
+        relabel_by_symbols(
+            &mut fst,
+            Some(symt_from(&["<unk>", "a"])),
+            None,
+            UnknownSymbolPolicy::MapToSymbol("<unk>".to_string()),
+        )?;
+
+        let ilabels: Vec<_> = fst.get_trs(s0)?.trs().iter().map(|tr| tr.ilabel).collect();
+        assert_eq!(ilabels, vec![2, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relabel_by_symbols_missing_symt() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let res = relabel_by_symbols(
+            &mut fst,
+            None,
+            Some(symt_from(&["a"])),
+            UnknownSymbolPolicy::AddToTable,
+        );
+        match res.unwrap_err() {
+            Error::SymbolTable(msg) => assert_eq!(msg, "The FST has no output symbol table"),
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_symbol_tables() {
+        let symt_1 = symt_from(&["a", "b"]);
+        let symt_2 = symt_from(&["b", "c", "a"]);
+
+        let (merged, remap) = merge_symbol_tables(&symt_1, &symt_2);
+
+        assert_eq!(&merged, symt_from(&["a", "b", "c"]).as_ref());
+        let mut remap: Vec<_> = remap.into_iter().collect();
+        remap.sort();
+        assert_eq!(remap, vec![(1, 2), (2, 3), (3, 1)]);
+    }
+
+    #[test]
+    fn test_prune_symbol_table_output() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s1, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 4, TropicalWeight::one(), s1))?;
+        fst.add_tr(s1, Tr::new(1, 2, TropicalWeight::one(), s1))?;
+        fst.set_output_symbols(symt_from(&["a", "b", "c", "d"]));
+
+        prune_symbol_table(&mut fst, ProjectType::ProjectOutput)?;
+
+        assert_eq!(fst.get_trs(s0)?.trs()[0].olabel, 2);
+        assert_eq!(fst.get_trs(s1)?.trs()[0].olabel, 1);
+        assert_eq!(fst.get_trs(s1)?.trs()[0].ilabel, 1);
+        assert_eq!(
+            fst.output_symbols().unwrap().as_ref(),
+            symt_from(&["b", "d"]).as_ref()
+        );
+
+        let mut fst_no_symt = VectorFst::<TropicalWeight>::new();
+        assert!(prune_symbol_table(&mut fst_no_symt, ProjectType::ProjectInput).is_err());
+
+        Ok(())
+    }
+}
@@ -1,13 +1,11 @@
-use anyhow::Result;
-
 use crate::algorithms::dfs_visit::{dfs_visit, Visitor};
 use crate::algorithms::state_sort;
 use crate::algorithms::tr_filters::AnyTrFilter;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{Fst, MutableFst};
 use crate::semirings::Semiring;
-use crate::StateId;
 use crate::Tr;
+use crate::{Error, StateId};
 
 pub struct TopOrderVisitor {
     pub order: Vec<StateId>,
@@ -73,7 +71,7 @@ impl<'a, W: Semiring, F: 'a + Fst<W>> Visitor<'a, W, F> for TopOrderVisitor {
 ///
 /// ![topsort_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/topsort_out.svg?sanitize=true)
 ///
-pub fn top_sort<W, F>(fst: &mut F) -> Result<()>
+pub fn top_sort<W, F>(fst: &mut F) -> Result<(), Error>
 where
     W: Semiring,
     F: MutableFst<W>,
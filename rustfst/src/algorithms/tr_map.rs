@@ -1,11 +1,11 @@
 use anyhow::Result;
 use std::ops::Deref;
 
-use crate::fst_properties::FstProperties;
+use crate::fst_properties::{known_properties, FstProperties};
 use crate::fst_traits::MutableFst;
 use crate::semirings::Semiring;
 use crate::Tr;
-use crate::{Label, StateId, EPS_LABEL};
+use crate::{Error, Label, StateId, EPS_LABEL};
 
 /// Struct used to map final weights when performing a transition mapping.
 /// It will always be of the form `(EPS_LABEL, EPS_LABEL, final_weight)`
@@ -24,7 +24,7 @@ pub struct FinalTr<W: Semiring> {
 }
 
 /// Determines how final weights are mapped.
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MapFinalAction {
     /// A final weight is mapped into a final weight. An error is raised if this
     /// is not possible.
@@ -75,7 +75,7 @@ impl<S: Semiring, T: TrMapper<S>, TP: Deref<Target = T>> TrMapper<S> for TP {
 }
 
 /// Maps every transition in the FST using an `TrMapper` object.
-pub fn tr_map<W, F, M>(ifst: &mut F, mapper: &M) -> Result<()>
+pub fn tr_map<W, F, M>(ifst: &mut F, mapper: &M) -> Result<(), Error>
 where
     W: Semiring,
     F: MutableFst<W>,
@@ -116,7 +116,9 @@ where
             match final_action {
                 MapFinalAction::MapNoSuperfinal => {
                     if final_tr.ilabel != EPS_LABEL || final_tr.olabel != EPS_LABEL {
-                        bail!("TrMap: Non-zero tr labels for superfinal tr")
+                        return Err(Error::Other(format_err!(
+                            "TrMap: Non-zero tr labels for superfinal tr"
+                        )));
                     }
                     unsafe {
                         ifst.set_final_unchecked(state, final_tr.weight);
@@ -180,7 +182,61 @@ where
         }
     }
 
-    ifst.set_properties_with_mask(mapper.properties(inprops), FstProperties::all_properties());
+    let mut outprops = mapper.properties(inprops);
+    if (0..ifst.num_states()).all(|s| unsafe { ifst.num_trs_unchecked(s) } == 0) {
+        // The properties depending only on the transitions are known whatever the mapper.
+        let no_trs_props = FstProperties::no_trs_properties();
+        outprops = (outprops & !known_properties(no_trs_props)) | no_trs_props;
+    }
+    ifst.set_properties_with_mask(outprops, FstProperties::all_properties());
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    fn check_tr_map<M: TrMapper<TropicalWeight>>(
+        fst: &VectorFst<TropicalWeight>,
+        mapper: &M,
+    ) -> bool {
+        let mut fst = fst.clone();
+        tr_map(&mut fst, mapper).unwrap();
+        properties_compatible(&fst)
+    }
+
+    proptest! {
+        #[test]
+        fn test_tr_map_properties_proptest(fst in any_fst_with_properties::<TropicalWeight>()) {
+            prop_assert!(check_tr_map(&fst, &IdentityTrMapper {}));
+            prop_assert!(check_tr_map(&fst, &InputEpsilonMapper {}));
+            prop_assert!(check_tr_map(&fst, &OutputEpsilonMapper {}));
+            prop_assert!(check_tr_map(&fst, &InvertMapper {}));
+            prop_assert!(check_tr_map(&fst, &ProjectMapper::new(ProjectType::ProjectInput)));
+            prop_assert!(check_tr_map(&fst, &ProjectMapper::new(ProjectType::ProjectOutput)));
+            prop_assert!(check_tr_map(&fst, &RmWeightMapper {}));
+            prop_assert!(check_tr_map(&fst, &PlusMapper::new(1.5)));
+            prop_assert!(check_tr_map(&fst, &TimesMapper::new(1.5)));
+            prop_assert!(check_tr_map(&fst, &QuantizeMapper::new(0.5)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_tr_map_epsilon_mapper_properties_proptest(
+            mut fst in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            tr_map(&mut fst, &InputEpsilonMapper {}).unwrap();
+            prop_assert!(fst.properties().contains(FstProperties::I_LABEL_SORTED));
+            prop_assert!(fst.properties().intersects(
+                FstProperties::I_EPSILONS | FstProperties::NO_I_EPSILONS
+            ));
+        }
+    }
+}
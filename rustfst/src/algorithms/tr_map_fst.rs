@@ -0,0 +1,511 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::{FstOp, LazyFst, SimpleHashMapCache};
+use crate::algorithms::tr_mappers::{InvertMapper, ProjectMapper, RelabelMapper};
+use crate::algorithms::{FinalTr, MapFinalAction, TrMapper};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::Semiring;
+use crate::{Error, StateId, SymbolTable, Tr, Trs, TrsVec, EPS_LABEL};
+
+pub struct TrMapFstOp<W: Semiring, F: Fst<W>, B: Borrow<F>, M: TrMapper<W>> {
+    fst: B,
+    mapper: M,
+    final_action: MapFinalAction,
+    state_ids: Mutex<StateIds>,
+    properties: FstProperties,
+    w: PhantomData<W>,
+    f: PhantomData<F>,
+}
+
+/// Mapping between the states of the input FST and the ones of the output FST, which are
+/// the same except that the states from `superfinal` on are shifted by one.
+#[derive(Debug)]
+struct StateIds {
+    superfinal: Option<StateId>,
+    // Number of output states seen so far : a superfinal state allocated on demand takes
+    // this id, so that the ids already returned don't change.
+    num_states: StateId,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, M: TrMapper<W>> Debug for TrMapFstOp<W, F, B, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TrMapFstOp {{ final_action : {:?}, superfinal : {:?}, fst : {:?} }}",
+            self.final_action,
+            self.superfinal(),
+            self.fst.borrow()
+        )
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, M: TrMapper<W>> TrMapFstOp<W, F, B, M> {
+    pub fn new(fst: B, mapper: M) -> Self {
+        let final_action = mapper.final_action();
+        // A required superfinal state is the state 0. Otherwise, it is allocated when the first
+        // final weight that can't be represented as such is met.
+        let superfinal = if final_action == MapFinalAction::MapRequireSuperfinal {
+            Some(0)
+        } else {
+            None
+        };
+        let state_ids = Mutex::new(StateIds {
+            superfinal,
+            num_states: 0,
+        });
+        let properties = mapper.properties(fst.borrow().properties());
+        Self {
+            fst,
+            mapper,
+            final_action,
+            state_ids,
+            properties,
+            w: PhantomData,
+            f: PhantomData,
+        }
+    }
+
+    fn superfinal(&self) -> Option<StateId> {
+        self.state_ids.lock().unwrap().superfinal
+    }
+
+    /// Returns the superfinal state, allocating it if needed.
+    fn get_or_add_superfinal(&self) -> StateId {
+        let mut state_ids = self.state_ids.lock().unwrap();
+        match state_ids.superfinal {
+            Some(superfinal) => superfinal,
+            None => {
+                let superfinal = state_ids.num_states;
+                state_ids.superfinal = Some(superfinal);
+                state_ids.num_states += 1;
+                superfinal
+            }
+        }
+    }
+
+    fn to_ostate(&self, state: StateId) -> StateId {
+        let mut state_ids = self.state_ids.lock().unwrap();
+        let ostate = match state_ids.superfinal {
+            Some(superfinal) if state >= superfinal => state + 1,
+            _ => state,
+        };
+        state_ids.num_states = state_ids.num_states.max(ostate + 1);
+        ostate
+    }
+
+    fn to_istate(&self, state: StateId) -> StateId {
+        match self.superfinal() {
+            Some(superfinal) if state > superfinal => state - 1,
+            _ => state,
+        }
+    }
+
+    fn mapped_final_tr(&self, state: StateId) -> Result<Option<FinalTr<W>>> {
+        match self.fst.borrow().final_weight(state)? {
+            Some(weight) => {
+                let mut final_tr = FinalTr {
+                    ilabel: EPS_LABEL,
+                    olabel: EPS_LABEL,
+                    weight,
+                };
+                self.mapper.final_tr_map(&mut final_tr)?;
+                Ok(Some(final_tr))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, M: TrMapper<W>> FstOp<W> for TrMapFstOp<W, F, B, M> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start().map(|s| self.to_ostate(s)))
+    }
+
+    fn compute_trs(&self, state: usize) -> Result<TrsVec<W>> {
+        if Some(state) == self.superfinal() {
+            return Ok(TrsVec::default());
+        }
+        let istate = self.to_istate(state);
+        let mut trs = vec![];
+        for tr in self.fst.borrow().get_trs(istate)?.trs() {
+            let mut tr = tr.clone();
+            self.mapper.tr_map(&mut tr)?;
+            tr.nextstate = self.to_ostate(tr.nextstate);
+            trs.push(tr);
+        }
+        if self.final_action != MapFinalAction::MapNoSuperfinal {
+            if let Some(final_tr) = self.mapped_final_tr(istate)? {
+                let has_labels = final_tr.ilabel != EPS_LABEL || final_tr.olabel != EPS_LABEL;
+                let to_superfinal = match self.final_action {
+                    MapFinalAction::MapRequireSuperfinal => {
+                        has_labels || !final_tr.weight.is_zero()
+                    }
+                    _ => has_labels,
+                };
+                if to_superfinal {
+                    trs.push(Tr::new(
+                        final_tr.ilabel,
+                        final_tr.olabel,
+                        final_tr.weight,
+                        self.get_or_add_superfinal(),
+                    ));
+                }
+            }
+        }
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, state: StateId) -> Result<Option<W>> {
+        if Some(state) == self.superfinal() {
+            return Ok(Some(W::one()));
+        }
+        let final_tr = match self.mapped_final_tr(self.to_istate(state))? {
+            Some(final_tr) => final_tr,
+            None => return Ok(None),
+        };
+        let has_labels = final_tr.ilabel != EPS_LABEL || final_tr.olabel != EPS_LABEL;
+        match self.final_action {
+            MapFinalAction::MapNoSuperfinal => {
+                if has_labels {
+                    bail!("TrMapFst: Non-zero tr labels for superfinal tr")
+                }
+                Ok(Some(final_tr.weight))
+            }
+            // The final weight is carried by the transition to the superfinal state.
+            MapFinalAction::MapAllowSuperfinal if has_labels => Ok(None),
+            MapFinalAction::MapAllowSuperfinal => Ok(Some(final_tr.weight)),
+            MapFinalAction::MapRequireSuperfinal => Ok(None),
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
+
+/// Delayed version of `tr_map`: the transitions and final weights of a state are mapped
+/// when the state is expanded, so any FST (including a lazy one) can be mapped without being
+/// copied. When the mapper requires a superfinal state, it is the state 0 and the other states
+/// are shifted by one. When it only allows one, the superfinal state is added the first time a
+/// final weight needs it, after the states already visited, so the state ids may differ from
+/// the ones of `tr_map` (the two FSTs are isomorphic).
+pub struct TrMapFst<W: Semiring, F: Fst<W>, B: Borrow<F>, M: TrMapper<W>>(
+    LazyFst<W, TrMapFstOp<W, F, B, M>, SimpleHashMapCache<W>>,
+);
+
+/// Delayed version of `project`, built with a `ProjectMapper`.
+pub type ProjectFst<W, F, B> = TrMapFst<W, F, B, ProjectMapper>;
+
+/// Delayed version of `invert`, built with an `InvertMapper`.
+pub type InvertFst<W, F, B> = TrMapFst<W, F, B, InvertMapper>;
+
+/// Delayed version of `relabel_pairs`, built with a `RelabelMapper`.
+pub type RelabelFst<W, F, B> = TrMapFst<W, F, B, RelabelMapper>;
+
+impl<W, F, B, M> CoreFst<W> for TrMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: TrMapper<W>,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B, M> StateIterator<'a> for TrMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+    M: TrMapper<W> + 'a,
+{
+    type Iter =
+        <LazyFst<W, TrMapFstOp<W, F, B, M>, SimpleHashMapCache<W>> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B, M> FstIterator<'a, W> for TrMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+    M: TrMapper<W> + 'a,
+{
+    type FstIter =
+        <LazyFst<W, TrMapFstOp<W, F, B, M>, SimpleHashMapCache<W>> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B, M> Fst<W> for TrMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + 'static,
+    M: TrMapper<W> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B, M> Debug for TrMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: TrMapper<W>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B, M> TrMapFst<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: TrMapper<W>,
+{
+    pub fn new(fst: B, mapper: M) -> Self {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = TrMapFstOp::new(fst, mapper);
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        TrMapFst(lazy_fst)
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
+        self.0.compute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::compose::ComposeFst;
+    use crate::algorithms::tr_mappers::QuantizeMapper;
+    use crate::algorithms::{invert, isomorphic, project, relabel_pairs, tr_map, ProjectType};
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Label;
+
+    fn build_fst() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(1, 2, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(3, 4, 2.0, s2))?;
+        fst.add_tr(s1, Tr::new(5, 0, 1.5, s2))?;
+        fst.set_final(s1, 0.5)?;
+        fst.set_final(s2, 1.5)?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_tr_map_fst_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<TrMapFst<TropicalWeight, VectorFst<_>, VectorFst<_>, InvertMapper>>();
+    }
+
+    #[test]
+    fn test_project_invert_relabel_fst() -> Result<()> {
+        let fst = build_fst()?;
+
+        for project_type in &[ProjectType::ProjectInput, ProjectType::ProjectOutput] {
+            let mut ref_fst = fst.clone();
+            project(&mut ref_fst, *project_type);
+            let lazy: ProjectFst<_, VectorFst<_>, _> =
+                ProjectFst::new(&fst, ProjectMapper::new(*project_type));
+            let computed: VectorFst<_> = lazy.compute()?;
+            assert_eq!(computed, ref_fst);
+        }
+
+        let mut ref_fst = fst.clone();
+        invert(&mut ref_fst);
+        let lazy: InvertFst<_, VectorFst<_>, _> = InvertFst::new(&fst, InvertMapper {});
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+
+        let mut ref_fst = fst.clone();
+        relabel_pairs(&mut ref_fst, vec![(1, 10), (5, 50)], vec![(4, 40)])?;
+        let lazy: RelabelFst<_, VectorFst<_>, _> = RelabelFst::new(
+            &fst,
+            RelabelMapper::new(vec![(1, 10), (5, 50)], vec![(4, 40)])?,
+        );
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+        Ok(())
+    }
+
+    /// Sets `label` as input label of the final weights.
+    struct FinalLabelMapper {
+        label: Label,
+        final_action: MapFinalAction,
+    }
+
+    impl TrMapper<TropicalWeight> for FinalLabelMapper {
+        fn tr_map(&self, _tr: &mut Tr<TropicalWeight>) -> Result<()> {
+            Ok(())
+        }
+
+        fn final_tr_map(&self, final_tr: &mut FinalTr<TropicalWeight>) -> Result<()> {
+            final_tr.ilabel = self.label;
+            Ok(())
+        }
+
+        fn final_action(&self) -> MapFinalAction {
+            self.final_action
+        }
+
+        fn properties(&self, _inprops: FstProperties) -> FstProperties {
+            FstProperties::empty()
+        }
+    }
+
+    #[test]
+    fn test_tr_map_fst_superfinal() -> Result<()> {
+        let fst = build_fst()?;
+        let mapper = || FinalLabelMapper {
+            label: 7,
+            final_action: MapFinalAction::MapRequireSuperfinal,
+        };
+        let mut ref_fst = fst.clone();
+        tr_map(&mut ref_fst, &mapper())?;
+        let lazy: TrMapFst<_, VectorFst<_>, _, _> = TrMapFst::new(&fst, mapper());
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed.num_states(), 4);
+        assert!(isomorphic(&computed, &ref_fst)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tr_map_fst_allow_superfinal() -> Result<()> {
+        let fst = build_fst()?;
+
+        // The final weights have labels : a superfinal state is needed.
+        let mapper = || FinalLabelMapper {
+            label: 7,
+            final_action: MapFinalAction::MapAllowSuperfinal,
+        };
+        let mut ref_fst = fst.clone();
+        tr_map(&mut ref_fst, &mapper())?;
+        let lazy: TrMapFst<_, VectorFst<_>, _, _> = TrMapFst::new(&fst, mapper());
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed.num_states(), 4);
+        assert!(isomorphic(&computed, &ref_fst)?);
+
+        // No superfinal state is added when the final weights don't need it.
+        let mapper = || FinalLabelMapper {
+            label: EPS_LABEL,
+            final_action: MapFinalAction::MapAllowSuperfinal,
+        };
+        let mut ref_fst = fst.clone();
+        tr_map(&mut ref_fst, &mapper())?;
+        let lazy: TrMapFst<_, VectorFst<_>, _, _> = TrMapFst::new(&fst, mapper());
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+        assert_eq!(computed, fst);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tr_map_fst_over_compose_fst() -> Result<()> {
+        let fst = build_fst()?;
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        let s0 = fst2.add_state();
+        fst2.set_start(s0)?;
+        fst2.set_final(s0, TropicalWeight::one())?;
+        for label in 0..5 {
+            fst2.add_tr(s0, Tr::new(label, label, 0.25, s0))?;
+        }
+
+        let compose = ComposeFst::<_, VectorFst<_>, VectorFst<_>, _, _, _, _, _>::new_auto(
+            Arc::new(fst),
+            Arc::new(fst2),
+        )?;
+        let mut ref_fst: VectorFst<_> = compose.compute()?;
+        tr_map(&mut ref_fst, &QuantizeMapper::default())?;
+        let lazy: TrMapFst<_, _, _, _> = TrMapFst::new(compose, QuantizeMapper::default());
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+
+use crate::algorithms::{FinalTr, MapFinalAction, TrMapper, WeightConverter};
+use crate::fst_properties::mutable_properties::invert_properties;
+use crate::fst_properties::FstProperties;
+use crate::semirings::Semiring;
+use crate::Tr;
+
+/// Mapper that swaps the input and output labels of the transitions.
+pub struct InvertMapper {}
+
+impl<S: Semiring> TrMapper<S> for InvertMapper {
+    fn tr_map(&self, tr: &mut Tr<S>) -> Result<()> {
+        std::mem::swap(&mut tr.ilabel, &mut tr.olabel);
+        Ok(())
+    }
+
+    fn final_tr_map(&self, _final_tr: &mut FinalTr<S>) -> Result<()> {
+        Ok(())
+    }
+
+    fn final_action(&self) -> MapFinalAction {
+        MapFinalAction::MapNoSuperfinal
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        invert_properties(inprops)
+    }
+}
+
+tr_mapper_to_weight_convert_mapper!(InvertMapper);
@@ -0,0 +1,42 @@
+use anyhow::Result;
+
+use crate::algorithms::{FinalTr, MapFinalAction, ProjectType, TrMapper, WeightConverter};
+use crate::fst_properties::mutable_properties::project_properties;
+use crate::fst_properties::FstProperties;
+use crate::semirings::Semiring;
+use crate::Tr;
+
+/// Mapper that projects the transitions onto their input or output labels.
+pub struct ProjectMapper {
+    project_type: ProjectType,
+}
+
+impl ProjectMapper {
+    pub fn new(project_type: ProjectType) -> Self {
+        Self { project_type }
+    }
+}
+
+impl<S: Semiring> TrMapper<S> for ProjectMapper {
+    fn tr_map(&self, tr: &mut Tr<S>) -> Result<()> {
+        match self.project_type {
+            ProjectType::ProjectInput => tr.olabel = tr.ilabel,
+            ProjectType::ProjectOutput => tr.ilabel = tr.olabel,
+        };
+        Ok(())
+    }
+
+    fn final_tr_map(&self, _final_tr: &mut FinalTr<S>) -> Result<()> {
+        Ok(())
+    }
+
+    fn final_action(&self) -> MapFinalAction {
+        MapFinalAction::MapNoSuperfinal
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        project_properties(inprops, self.project_type)
+    }
+}
+
+tr_mapper_to_weight_convert_mapper!(ProjectMapper);
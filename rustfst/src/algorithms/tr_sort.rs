@@ -8,6 +8,11 @@ use crate::Tr;
 pub trait TrCompare {
     fn compare<W: Semiring>(a: &Tr<W>, b: &Tr<W>) -> Ordering;
     fn properties(inprops: FstProperties) -> FstProperties;
+    /// Returns true if the trs of an FST with the properties `props` are known to be
+    /// already sorted according to `compare`, in which case sorting them is skipped.
+    fn is_sorted(_props: FstProperties) -> bool {
+        false
+    }
 }
 
 /// Compare only input labels.
@@ -26,6 +31,10 @@ impl TrCompare for ILabelCompare {
         }
         outprops
     }
+
+    fn is_sorted(props: FstProperties) -> bool {
+        props.contains(FstProperties::I_LABEL_SORTED)
+    }
 }
 
 /// Compare only output labels.
@@ -44,6 +53,10 @@ impl TrCompare for OLabelCompare {
         }
         outprops
     }
+
+    fn is_sorted(props: FstProperties) -> bool {
+        props.contains(FstProperties::O_LABEL_SORTED)
+    }
 }
 
 /// Sorts trs leaving each state of the FST using a compare function
@@ -55,8 +68,47 @@ where
     C: TrCompare,
 {
     let props = fst.properties();
+    if C::is_sorted(props) {
+        return;
+    }
     for state in 0..fst.num_states() {
         fst.sort_trs_unchecked(state, C::compare);
     }
     fst.set_properties_with_mask(C::properties(props), FstProperties::all_properties());
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_tr_sort_ilabel_properties_proptest(
+            mut fst in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            let mut fst_unknown_props = fst.clone();
+            fst_unknown_props.set_properties(FstProperties::empty());
+            tr_sort(&mut fst, ILabelCompare {});
+            tr_sort(&mut fst_unknown_props, ILabelCompare {});
+            prop_assert!(properties_compatible(&fst));
+            prop_assert!(fst.properties().contains(FstProperties::I_LABEL_SORTED));
+            prop_assert_eq!(fst, fst_unknown_props);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_tr_sort_olabel_properties_proptest(
+            mut fst in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            tr_sort(&mut fst, OLabelCompare {});
+            prop_assert!(properties_compatible(&fst));
+            prop_assert!(fst.properties().contains(FstProperties::O_LABEL_SORTED));
+        }
+    }
+}
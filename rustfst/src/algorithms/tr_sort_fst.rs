@@ -0,0 +1,262 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::{FstOp, LazyFst, SimpleHashMapCache};
+use crate::algorithms::tr_compares::TrCompare;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::Semiring;
+use crate::{Error, StateId, SymbolTable, Trs, TrsVec};
+
+pub struct TrSortFstOp<W: Semiring, F: Fst<W>, B: Borrow<F>, C: TrCompare> {
+    fst: B,
+    properties: FstProperties,
+    w: PhantomData<W>,
+    f: PhantomData<F>,
+    c: PhantomData<C>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, C: TrCompare> Debug for TrSortFstOp<W, F, B, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TrSortFstOp {{ fst : {:?} }}", self.fst.borrow())
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, C: TrCompare> TrSortFstOp<W, F, B, C> {
+    pub fn new(fst: B) -> Self {
+        let properties = C::properties(fst.borrow().properties());
+        Self {
+            fst,
+            properties,
+            w: PhantomData,
+            f: PhantomData,
+            c: PhantomData,
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>, C: TrCompare> FstOp<W> for TrSortFstOp<W, F, B, C> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start())
+    }
+
+    fn compute_trs(&self, state: usize) -> Result<TrsVec<W>> {
+        let mut trs = self.fst.borrow().get_trs(state)?.trs().to_vec();
+        trs.sort_by(C::compare);
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, state: StateId) -> Result<Option<W>> {
+        Ok(self.fst.borrow().final_weight(state)?)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
+
+/// Delayed version of `tr_sort`: the transitions leaving a state are sorted when the state is
+/// expanded.
+pub struct TrSortFst<W: Semiring, F: Fst<W>, B: Borrow<F>, C: TrCompare>(
+    LazyFst<W, TrSortFstOp<W, F, B, C>, SimpleHashMapCache<W>>,
+);
+
+impl<W, F, B, C> CoreFst<W> for TrSortFst<W, F, B, C>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    C: TrCompare,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<usize> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: usize) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: usize) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: usize) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B, C> StateIterator<'a> for TrSortFst<W, F, B, C>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+    C: TrCompare + 'a,
+{
+    type Iter =
+        <LazyFst<W, TrSortFstOp<W, F, B, C>, SimpleHashMapCache<W>> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B, C> FstIterator<'a, W> for TrSortFst<W, F, B, C>
+where
+    W: Semiring,
+    F: Fst<W> + 'a,
+    B: Borrow<F> + 'a,
+    C: TrCompare + 'a,
+{
+    type FstIter =
+        <LazyFst<W, TrSortFstOp<W, F, B, C>, SimpleHashMapCache<W>> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B, C> Fst<W> for TrSortFst<W, F, B, C>
+where
+    W: Semiring,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + 'static,
+    C: TrCompare + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B, C> Debug for TrSortFst<W, F, B, C>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    C: TrCompare,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<W, F, B, C> TrSortFst<W, F, B, C>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    C: TrCompare,
+{
+    // The compare function is only passed to infer the generic parameter, as in `tr_sort`.
+    pub fn new(fst: B, _comp: C) -> Self {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = TrSortFstOp::new(fst);
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        TrSortFst(lazy_fst)
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2, Error> {
+        self.0.compute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
+    use crate::algorithms::tr_mappers::InvertMapper;
+    use crate::algorithms::{invert, tr_sort, InvertFst};
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_tr_sort_fst() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.add_tr(s0, Tr::new(3, 1, 1.0, s1))?;
+        fst.add_tr(s0, Tr::new(1, 3, 2.0, s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, 3.0, s1))?;
+        fst.add_tr(s1, Tr::new(2, 1, 3.0, s0))?;
+        fst.set_final(s1, 0.5)?;
+
+        let mut ref_fst = fst.clone();
+        tr_sort(&mut ref_fst, ILabelCompare {});
+        let lazy: TrSortFst<_, VectorFst<_>, _, _> = TrSortFst::new(&fst, ILabelCompare {});
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+        assert!(lazy.properties().contains(FstProperties::I_LABEL_SORTED));
+
+        // Sorting the inverted FST on its input labels without materializing the inversion.
+        let mut ref_fst = fst.clone();
+        invert(&mut ref_fst);
+        tr_sort(&mut ref_fst, ILabelCompare {});
+        let inverted: InvertFst<_, VectorFst<_>, _> =
+            InvertFst::new(Arc::new(fst.clone()), InvertMapper {});
+        let lazy = TrSortFst::new(inverted, ILabelCompare {});
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+
+        let mut ref_fst = fst.clone();
+        tr_sort(&mut ref_fst, OLabelCompare {});
+        let lazy: TrSortFst<_, VectorFst<_>, _, _> = TrSortFst::new(&fst, OLabelCompare {});
+        let computed: VectorFst<_> = lazy.compute()?;
+        assert_eq!(computed, ref_fst);
+        Ok(())
+    }
+}
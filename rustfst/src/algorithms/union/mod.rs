@@ -1,5 +1,5 @@
 mod union;
 mod union_fst;
 
-pub use union::union;
+pub use union::{union, union_with_config, UnionConfig};
 pub use union_fst::UnionFst;
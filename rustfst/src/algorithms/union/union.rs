@@ -1,12 +1,18 @@
-use anyhow::Result;
 use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::fst_properties::mutable_properties::union_properties;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
 use crate::semirings::Semiring;
+use crate::symbol_table::compat_symbols;
 use crate::tr::Tr;
-use crate::{Trs, EPS_LABEL};
+use crate::{Error, Trs, EPS_LABEL};
+
+#[derive(PartialOrd, PartialEq, Debug, Clone, Copy, Default)]
+pub struct UnionConfig {
+    /// If true, an error is returned when the symbol tables of the two FSTs are not compatible.
+    pub compat_symbols: bool,
+}
 
 /// Performs the union of two wFSTs. If A transduces string `x` to `y` with weight `a`
 /// and `B` transduces string `w` to `v` with weight `b`, then their union transduces `x` to `y`
@@ -53,12 +59,38 @@ use crate::{Trs, EPS_LABEL};
 ///
 /// ![union_out](https://raw.githubusercontent.com/Garvys/rustfst-images-doc/master/images/union_out.svg?sanitize=true)
 ///
-pub fn union<W, F1, F2>(fst_1: &mut F1, fst_2: &F2) -> Result<()>
+pub fn union<W, F1, F2>(fst_1: &mut F1, fst_2: &F2) -> Result<(), Error>
+where
+    W: Semiring,
+    F1: AllocableFst<W> + MutableFst<W>,
+    F2: ExpandedFst<W>,
+{
+    union_with_config(fst_1, fst_2, UnionConfig::default())
+}
+
+/// Same as `union` but the behaviour can be customized with a `UnionConfig`.
+pub fn union_with_config<W, F1, F2>(
+    fst_1: &mut F1,
+    fst_2: &F2,
+    config: UnionConfig,
+) -> Result<(), Error>
 where
     W: Semiring,
     F1: AllocableFst<W> + MutableFst<W>,
     F2: ExpandedFst<W>,
 {
+    if config.compat_symbols
+        && (!compat_symbols(
+            fst_1.input_symbols().map(|s| s.as_ref()),
+            fst_2.input_symbols().map(|s| s.as_ref()),
+        ) || !compat_symbols(
+            fst_1.output_symbols().map(|s| s.as_ref()),
+            fst_2.output_symbols().map(|s| s.as_ref()),
+        ))
+    {
+        return Err(Error::IncompatibleSymbolTables { algorithm: "union" });
+    }
+
     let initial_acyclic_1 = fst_1
         .compute_and_update_properties(FstProperties::INITIAL_ACYCLIC)?
         .contains(FstProperties::INITIAL_ACYCLIC);
@@ -87,8 +119,11 @@ where
 
     let start1 = fst_1.start();
     if start1.is_none() {
-        unsafe { fst_1.set_start_unchecked(start2) };
-        fst_1.set_properties_with_mask(props2, FstProperties::copy_properties());
+        unsafe { fst_1.set_start_unchecked(start2 + numstates1) };
+        // The states of the first FST, if any, are not accessible anymore.
+        if numstates1 == 0 {
+            fst_1.set_properties_with_mask(props2, FstProperties::copy_properties());
+        }
         return Ok(());
     }
     let start1 = unsafe { start1.unsafe_unwrap() };
@@ -117,3 +152,45 @@ where
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use proptest::prelude::*;
+
+    use crate::prelude::*;
+    use crate::proptest_fst::{any_fst_with_properties, properties_compatible};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_union_properties_proptest(
+            mut fst_1 in any_fst_with_properties::<TropicalWeight>(),
+            fst_2 in any_fst_with_properties::<TropicalWeight>()
+        ) {
+            union(&mut fst_1, &fst_2).unwrap();
+            prop_assert!(properties_compatible(&fst_1));
+            prop_assert!(fst_1.properties().contains(FstProperties::INITIAL_ACYCLIC));
+        }
+    }
+
+    #[test]
+    fn test_union_without_start() -> Result<()> {
+        let mut fst_1 = VectorFst::<TropicalWeight>::new();
+        fst_1.add_state();
+        let mut fst_2 = VectorFst::<TropicalWeight>::new();
+        fst_2.add_states(2);
+        fst_2.set_start(0)?;
+        fst_2.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst_2.set_final(1, 2.0)?;
+
+        union(&mut fst_1, &fst_2)?;
+
+        assert_eq!(fst_1.start(), Some(1));
+        assert_eq!(fst_1.get_trs(1)?.trs()[0].nextstate, 2);
+        assert_eq!(fst_1.final_weight(2)?, Some(TropicalWeight::new(2.0)));
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use crate::algorithms::{FinalTr, MapFinalAction};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
 use crate::semirings::Semiring;
-use crate::{Tr, Trs, EPS_LABEL};
+use crate::{Error, Tr, Trs, EPS_LABEL};
 use unsafe_unwrap::UnsafeUnwrap;
 
 /// The WeightConverter interfaces defines how a weight should be turned into another one.
@@ -18,7 +18,7 @@ pub trait WeightConverter<SI: Semiring, SO: Semiring> {
 
 /// Convert an FST in a given Semiring to another Semiring using a WeightConverter
 /// to specify how the conversion should be performed.
-pub fn weight_convert<W1, W2, F1, F2, M>(fst_in: &F1, mapper: &mut M) -> Result<F2>
+pub fn weight_convert<W1, W2, F1, F2, M>(fst_in: &F1, mapper: &mut M) -> Result<F2, Error>
 where
     W1: Semiring,
     W2: Semiring,
@@ -74,7 +74,9 @@ where
             match final_action {
                 MapFinalAction::MapNoSuperfinal => {
                     if mapped_final_tr.ilabel != EPS_LABEL || mapped_final_tr.olabel != EPS_LABEL {
-                        bail!("TrMap: Non-zero tr labels for superfinal tr")
+                        return Err(Error::Other(format_err!(
+                            "TrMap: Non-zero tr labels for superfinal tr"
+                        )));
                     }
 
                     fst_out.set_final(state, mapped_final_tr.weight).unwrap();
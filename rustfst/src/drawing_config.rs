@@ -1,3 +1,12 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{FstPath, StateId, SymbolTable, Trs, EPS_LABEL};
+
 /// Struct to configure how the FST should be drawn.
 #[derive(Debug, Clone, PartialEq)]
 pub struct DrawingConfig {
@@ -21,6 +30,14 @@ pub struct DrawingConfig {
     pub show_weight_one: bool,
     /// Print/draw transition weights and final weights.
     pub print_weight: bool,
+    /// Symbol table used to label the states. States missing from it are labeled with their id.
+    pub state_symbols: Option<Arc<SymbolTable>>,
+    /// States to highlight.
+    pub highlighted_states: HashSet<StateId>,
+    /// Transitions to highlight, identified by their source state and their index in it.
+    pub highlighted_trs: HashSet<(StateId, usize)>,
+    /// Color used to draw the highlighted states and transitions.
+    pub highlight_color: String,
 }
 
 impl Default for DrawingConfig {
@@ -36,6 +53,128 @@ impl Default for DrawingConfig {
             acceptor: false,
             show_weight_one: true,
             print_weight: true,
+            state_symbols: None,
+            highlighted_states: HashSet::new(),
+            highlighted_trs: HashSet::new(),
+            highlight_color: "red".to_string(),
         }
     }
 }
+
+impl DrawingConfig {
+    /// Highlights the states and transitions of a path of the FST going from the start state
+    /// to a final state and accepting the labels of `path`. Weights are not compared.
+    ///
+    /// If several paths of the FST match, only the first one found is highlighted.
+    /// An error is returned if no path matches.
+    pub fn highlight_path<W: Semiring, F: ExpandedFst<W>>(
+        &mut self,
+        fst: &F,
+        path: &FstPath<W>,
+    ) -> Result<()> {
+        let start = fst
+            .start()
+            .ok_or_else(|| format_err!("Can't highlight a path in an FST without start state"))?;
+
+        // Breadth first search on (state, next_ilabel_idx, next_olabel_idx). The transition
+        // used to reach each search state is stored to rebuild the path.
+        let start_search_state = (start, 0, 0);
+        let mut parents = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(start_search_state);
+
+        while let Some(search_state) = queue.pop_front() {
+            let (state, ilabel_idx, olabel_idx) = search_state;
+            if ilabel_idx == path.ilabels.len()
+                && olabel_idx == path.olabels.len()
+                && fst.is_final(state)?
+            {
+                self.highlighted_states.insert(state);
+                let mut current = search_state;
+                while let Some(&(parent, tr_idx)) = parents.get(&current) {
+                    let (parent_state, _, _) = parent;
+                    self.highlighted_states.insert(parent_state);
+                    self.highlighted_trs.insert((parent_state, tr_idx));
+                    current = parent;
+                }
+                return Ok(());
+            }
+
+            let trs = fst.get_trs(state)?;
+            for (tr_idx, tr) in trs.trs().iter().enumerate() {
+                let next_ilabel_idx = if tr.ilabel == EPS_LABEL {
+                    ilabel_idx
+                } else if path.ilabels.get(ilabel_idx) == Some(&tr.ilabel) {
+                    ilabel_idx + 1
+                } else {
+                    continue;
+                };
+                let next_olabel_idx = if tr.olabel == EPS_LABEL {
+                    olabel_idx
+                } else if path.olabels.get(olabel_idx) == Some(&tr.olabel) {
+                    olabel_idx + 1
+                } else {
+                    continue;
+                };
+                let next_search_state = (tr.nextstate, next_ilabel_idx, next_olabel_idx);
+                if next_search_state != start_search_state
+                    && !parents.contains_key(&next_search_state)
+                {
+                    parents.insert(next_search_state, (search_state, tr_idx));
+                    queue.push_back(next_search_state);
+                }
+            }
+        }
+
+        bail!("The path to highlight is not present in the FST")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{MutableFst, SerializableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_draw_highlighted_path() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s0 = fst.add_state();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s2, TropicalWeight::one())?;
+        fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+        fst.add_tr(s0, Tr::new(2, 2, TropicalWeight::one(), s1))?;
+        fst.add_tr(s1, Tr::new(0, 0, TropicalWeight::one(), s2))?;
+
+        let mut state_symbols = SymbolTable::empty();
+        state_symbols.add_symbols(vec!["start", "middle", "end"]);
+
+        let mut config = DrawingConfig::default();
+        config.state_symbols = Some(Arc::new(state_symbols));
+        config.highlight_path(&fst, &FstPath::new(vec![2], vec![2], TropicalWeight::one()))?;
+
+        assert_eq!(
+            config.highlighted_states,
+            vec![s0, s1, s2].into_iter().collect()
+        );
+        assert_eq!(
+            config.highlighted_trs,
+            vec![(s0, 1), (s1, 0)].into_iter().collect()
+        );
+
+        let dot = fst.dot(&config)?;
+        assert!(dot.contains("1 [label = \"middle\""));
+        assert!(dot.contains("0 -> 1 [label = \"1:1/0\", fontsize = 14];"));
+        assert!(dot.contains("0 -> 1 [label = \"2:2/0\", color = \"red\", fontcolor = \"red\","));
+
+        let res =
+            config.highlight_path(&fst, &FstPath::new(vec![3], vec![3], TropicalWeight::one()));
+        assert!(res.is_err());
+
+        Ok(())
+    }
+}
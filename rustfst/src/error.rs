@@ -0,0 +1,270 @@
+use std::fmt;
+use std::path::Path;
+
+use nom::error::ErrorKind;
+use nom::InputLength;
+
+use crate::algorithms::PreconditionError;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::StateId;
+
+/// Errors raised by the crate.
+///
+/// The state accessors of the FST traits, the readers and the algorithms return a
+/// `Result<T, Error>` which can be matched on. The errors raised outside of this crate
+/// (e.g by a `TrMapper`) end in `Error::Other`. As `Error` implements `std::error::Error`,
+/// `?` turns it into an `anyhow::Error`, and `Error::from` converts an `anyhow::Error` back.
+///
+/// # Example
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::prelude::*;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// match fst.set_start(3).unwrap_err() {
+///     Error::InvalidStateId(state) => assert_eq!(state, 3),
+///     e => panic!("Unexpected error : {}", e),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// The state doesn't exist in the FST.
+    InvalidStateId(StateId),
+    /// An algorithm requires properties that the FST or the semiring doesn't have.
+    PropertyViolation(PreconditionError),
+    /// The semiring can't perform an operation, e.g a division by zero.
+    SemiringCapability(String),
+    /// The symbol tables of the FSTs passed to an algorithm are not compatible.
+    IncompatibleSymbolTables { algorithm: &'static str },
+    /// An FST has no symbol table, or a label or a symbol is missing from a symbol table.
+    SymbolTable(String),
+    /// A text or binary input couldn't be parsed.
+    Parse {
+        /// What was being parsed, e.g `binary VectorFst`.
+        context: &'static str,
+        /// Line (starting at 1) at which the parsing of a text input failed. `None` for a
+        /// binary input.
+        line: Option<usize>,
+        /// Error returned by the parser, the remaining input being replaced by the offset
+        /// in bytes at which the parsing failed. `None` if the input is truncated.
+        error: Option<NomCustomError<usize>>,
+    },
+    /// Reading or writing failed.
+    Io(std::io::Error),
+    /// The output of an algorithm would exceed what can be computed, e.g the expansion of a
+    /// grammar with cyclic dependencies.
+    ResourceLimit(String),
+    /// Any other error.
+    Other(anyhow::Error),
+}
+
+impl Error {
+    /// Builds an `Error::Parse` from the error returned by a parser on an input of
+    /// `input_len` bytes.
+    pub(crate) fn from_nom<I: InputLength>(
+        context: &'static str,
+        input_len: usize,
+        e: nom::Err<NomCustomError<I>>,
+    ) -> Self {
+        Error::Parse {
+            context,
+            line: None,
+            error: nom_error_offset(input_len, e),
+        }
+    }
+
+    /// Builds an `Error::Parse` from the error returned by a parser on the text `input`.
+    pub(crate) fn from_nom_text(
+        context: &'static str,
+        input: &str,
+        e: nom::Err<NomCustomError<&str>>,
+    ) -> Self {
+        let error = nom_error_offset(input.len(), e);
+        let line = match &error {
+            Some(NomCustomError::Nom(offset, _)) => Some(line_at(input, *offset)),
+            _ => None,
+        };
+        Error::Parse {
+            context,
+            line,
+            error,
+        }
+    }
+
+    /// Builds an `Error::Parse` for the text `input` of which the parser didn't consume
+    /// anything after `offset`.
+    pub(crate) fn unparsed_text(context: &'static str, input: &str, offset: usize) -> Self {
+        Error::Parse {
+            context,
+            line: Some(line_at(input, offset)),
+            error: Some(NomCustomError::Nom(offset, ErrorKind::Eof)),
+        }
+    }
+
+    /// Builds an `Error::Io` naming the file that couldn't be read.
+    pub(crate) fn read_file(what: &str, path: &Path, e: std::io::Error) -> Self {
+        Error::Io(std::io::Error::new(
+            e.kind(),
+            format!("Can't open {} : {:?} ({})", what, path, e),
+        ))
+    }
+}
+
+fn nom_error_offset<I: InputLength>(
+    input_len: usize,
+    e: nom::Err<NomCustomError<I>>,
+) -> Option<NomCustomError<usize>> {
+    match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => Some(match e {
+            NomCustomError::SymbolTableError(s) => NomCustomError::SymbolTableError(s),
+            NomCustomError::Nom(i, kind) => NomCustomError::Nom(input_len - i.input_len(), kind),
+        }),
+        nom::Err::Incomplete(_) => None,
+    }
+}
+
+/// Line (starting at 1) of the byte at `offset` in `input`.
+fn line_at(input: &str, offset: usize) -> usize {
+    input.as_bytes()[..offset]
+        .iter()
+        .filter(|c| **c == b'\n')
+        .count()
+        + 1
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidStateId(state) => write!(f, "State {:?} doesn't exist", state),
+            Error::PropertyViolation(e) => write!(f, "{}", e),
+            Error::SemiringCapability(msg) => write!(f, "{}", msg),
+            Error::IncompatibleSymbolTables { algorithm } => write!(
+                f,
+                "{} : the symbol tables of the FSTs are not compatible",
+                algorithm
+            ),
+            Error::SymbolTable(msg) => write!(f, "{}", msg),
+            Error::Parse {
+                context,
+                line,
+                error,
+            } => {
+                write!(f, "Error while parsing {}", context)?;
+                if let Some(line) = line {
+                    write!(f, " : parse error at line {}", line)?;
+                }
+                match error {
+                    Some(e) => write!(f, " : {:?}", e),
+                    None if line.is_none() => write!(f, " : truncated input"),
+                    None => Ok(()),
+                }
+            }
+            Error::Io(e) => write!(f, "{}", e),
+            Error::ResourceLimit(msg) => write!(f, "{}", msg),
+            Error::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::PropertyViolation(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Other(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+impl From<PreconditionError> for Error {
+    fn from(e: PreconditionError) -> Self {
+        Error::PropertyViolation(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<Error>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        let e = match e.downcast::<PreconditionError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        match e.downcast::<std::io::Error>() {
+            Ok(e) => Error::Io(e),
+            Err(e) => Error::Other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::algorithms::shortest_path;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, MutableFst, SerializableFst};
+    use crate::semirings::{LogWeight, Semiring, SemiringProperties, TropicalWeight};
+    use crate::Tr;
+
+    use super::*;
+
+    #[test]
+    fn test_error_invalid_state_id() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_state();
+        match fst.add_tr(2, Tr::new(1, 1, 1.0, 0)).unwrap_err() {
+            Error::InvalidStateId(2) => {}
+            e => panic!("Unexpected error : {}", e),
+        }
+        let err = fst.final_weight(3).unwrap_err();
+        assert_eq!(err.to_string(), "State 3 doesn't exist");
+        // The error converts into an `anyhow::Error`, e.g with `?`.
+        let res: Result<()> = fst.set_start(3).map_err(anyhow::Error::from);
+        assert!(res.unwrap_err().downcast_ref::<Error>().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_property_violation() -> Result<()> {
+        let mut fst = VectorFst::<LogWeight>::new();
+        let s0 = fst.add_state();
+        fst.set_start(s0)?;
+        fst.set_final(s0, LogWeight::one())?;
+        match shortest_path::<_, _, VectorFst<_>>(&fst).unwrap_err() {
+            Error::PropertyViolation(PreconditionError::MissingSemiringProperties {
+                algorithm,
+                missing,
+            }) => {
+                assert_eq!(algorithm, "shortest_path");
+                assert_eq!(missing, SemiringProperties::PATH);
+            }
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_parse() -> Result<()> {
+        let data = [0u8, 1, 2, 3, 4, 5, 6, 7, 8];
+        match VectorFst::<TropicalWeight>::load(&data).unwrap_err() {
+            Error::Parse {
+                context: "binary VectorFst",
+                ..
+            } => {}
+            e => panic!("Unexpected error : {:?}", e),
+        }
+        Ok(())
+    }
+}
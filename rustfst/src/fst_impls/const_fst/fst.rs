@@ -1,12 +1,10 @@
 use std::sync::Arc;
 
-use anyhow::{format_err, Result};
-
 use crate::fst_impls::ConstFst;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, Fst};
 use crate::semirings::Semiring;
-use crate::{SymbolTable, TrsConst};
+use crate::{Error, SymbolTable, TrsConst};
 
 impl<W: Semiring> Fst<W> for ConstFst<W> {
     fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
@@ -41,11 +39,11 @@ impl<W: Semiring> CoreFst<W> for ConstFst<W> {
         self.start
     }
 
-    fn final_weight(&self, state_id: usize) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: usize) -> Result<Option<W>, Error> {
         let s = self
             .states
             .get(state_id)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+            .ok_or_else(|| Error::InvalidStateId(state_id))?;
         Ok(s.final_weight.clone())
     }
 
@@ -53,11 +51,11 @@ impl<W: Semiring> CoreFst<W> for ConstFst<W> {
         self.states.get_unchecked(state_id).final_weight.clone()
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         Ok(self
             .states
             .get(s)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", s))?
+            .ok_or_else(|| Error::InvalidStateId(s))?
             .ntrs)
     }
 
@@ -65,11 +63,11 @@ impl<W: Semiring> CoreFst<W> for ConstFst<W> {
         self.states.get_unchecked(s).ntrs
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         let state = self
             .states
             .get(state_id)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+            .ok_or_else(|| Error::InvalidStateId(state_id))?;
         Ok(TrsConst {
             trs: Arc::clone(&self.trs),
             pos: state.pos,
@@ -90,19 +88,19 @@ impl<W: Semiring> CoreFst<W> for ConstFst<W> {
         self.properties
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         Ok(self
             .states
             .get(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))?
+            .ok_or_else(|| Error::InvalidStateId(state))?
             .niepsilons)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         Ok(self
             .states
             .get(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))?
+            .ok_or_else(|| Error::InvalidStateId(state))?
             .noepsilons)
     }
 }
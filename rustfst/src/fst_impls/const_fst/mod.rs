@@ -1,4 +1,5 @@
 pub use self::data_structure::ConstFst;
+pub(crate) use self::serializable_fst::parse_const_fst;
 
 mod converters;
 mod data_structure;
@@ -1,9 +1,6 @@
-use std::fs::{read, File};
-use std::io::BufWriter;
-use std::path::Path;
+use std::io::Write;
 use std::sync::Arc;
 
-use anyhow::Context;
 use anyhow::Result;
 use itertools::Itertools;
 use nom::bytes::complete::take;
@@ -24,30 +21,21 @@ use crate::parsers::bin_fst::utils_serialization::write_bin_i32;
 use crate::parsers::nom_utils::NomCustomError;
 use crate::parsers::text_fst::ParsedTextFst;
 use crate::semirings::SerializableSemiring;
-use crate::{Tr, EPS_LABEL};
+use crate::{Error, Tr, EPS_LABEL};
 
 impl<W: SerializableSemiring> SerializableFst<W> for ConstFst<W> {
     fn fst_type() -> String {
         "const".to_string()
     }
 
-    fn read<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self> {
-        let data = read(path_bin_fst.as_ref()).with_context(|| {
-            format!(
-                "Can't open ConstFst binary file : {:?}",
-                path_bin_fst.as_ref()
-            )
-        })?;
-
-        let (_, parsed_fst) = parse_const_fst(&data)
-            .map_err(|_| format_err!("Error while parsing binary ConstFst"))?;
+    fn load(data: &[u8]) -> Result<Self, Error> {
+        let (_, parsed_fst) =
+            parse_const_fst(data).map_err(|e| Error::from_nom("binary ConstFst", data.len(), e))?;
 
         Ok(parsed_fst)
     }
 
-    fn write<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
-        let mut file = BufWriter::new(File::create(path_bin_fst)?);
-
+    fn store<O: Write>(&self, mut file: O) -> Result<()> {
         let mut flags = FstFlags::empty();
         if self.input_symbols().is_some() {
             flags |= FstFlags::HAS_ISYMBOLS;
@@ -90,10 +78,11 @@ impl<W: SerializableSemiring> SerializableFst<W> for ConstFst<W> {
             write_bin_i32(&mut file, tr.nextstate as i32)?;
         }
 
+        file.flush()?;
         Ok(())
     }
 
-    fn from_parsed_fst_text(mut parsed_fst_text: ParsedTextFst<W>) -> Result<Self> {
+    fn from_parsed_fst_text(mut parsed_fst_text: ParsedTextFst<W>) -> Result<Self, Error> {
         let start_state = parsed_fst_text.start();
         let num_states = parsed_fst_text.num_states();
         let num_trs = parsed_fst_text.transitions.len();
@@ -206,7 +195,7 @@ fn parse_const_state<W: SerializableSemiring>(
     ))
 }
 
-fn parse_const_fst<W: SerializableSemiring>(
+pub(crate) fn parse_const_fst<W: SerializableSemiring>(
     i: &[u8],
 ) -> IResult<&[u8], ConstFst<W>, NomCustomError<&[u8]>> {
     let stream_len = i.len();
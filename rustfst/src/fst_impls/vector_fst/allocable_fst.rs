@@ -1,16 +1,15 @@
 use crate::fst_impls::vector_fst::VectorFst;
 use crate::fst_traits::AllocableFst;
 use crate::semirings::Semiring;
-use crate::StateId;
-use anyhow::Result;
+use crate::{Error, StateId};
 use std::sync::Arc;
 
 impl<W: 'static + Semiring> AllocableFst<W> for VectorFst<W> {
-    fn reserve_trs(&mut self, source: usize, additional: usize) -> Result<()> {
+    fn reserve_trs(&mut self, source: usize, additional: usize) -> Result<(), Error> {
         let trs = &mut self
             .states
             .get_mut(source)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", source))?
+            .ok_or_else(|| Error::InvalidStateId(source))?
             .trs;
 
         Arc::make_mut(&mut trs.0).reserve(additional);
@@ -40,11 +39,11 @@ impl<W: 'static + Semiring> AllocableFst<W> for VectorFst<W> {
         self.states.shrink_to_fit()
     }
 
-    fn shrink_to_fit_trs(&mut self, source: StateId) -> Result<()> {
+    fn shrink_to_fit_trs(&mut self, source: StateId) -> Result<(), Error> {
         let trs = &mut self
             .states
             .get_mut(source)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", source))?
+            .ok_or_else(|| Error::InvalidStateId(source))?
             .trs;
         Arc::make_mut(&mut trs.0).shrink_to_fit();
         Ok(())
@@ -60,11 +59,11 @@ impl<W: 'static + Semiring> AllocableFst<W> for VectorFst<W> {
         self.states.capacity()
     }
 
-    fn trs_capacity(&self, source: StateId) -> Result<usize> {
+    fn trs_capacity(&self, source: StateId) -> Result<usize, Error> {
         Ok(self
             .states
             .get(source)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", source))?
+            .ok_or_else(|| Error::InvalidStateId(source))?
             .trs
             .0
             .capacity())
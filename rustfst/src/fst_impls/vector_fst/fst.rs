@@ -1,12 +1,10 @@
 use std::sync::Arc;
 
-use anyhow::Result;
-
 use crate::fst_impls::VectorFst;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{CoreFst, Fst};
 use crate::semirings::Semiring;
-use crate::{StateId, SymbolTable, Trs, TrsVec};
+use crate::{Error, StateId, SymbolTable, Trs, TrsVec};
 
 impl<W: Semiring> Fst<W> for VectorFst<W> {
     fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
@@ -41,11 +39,11 @@ impl<W: Semiring> CoreFst<W> for VectorFst<W> {
         self.start_state
     }
 
-    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>, Error> {
         let s = self
             .states
             .get(state_id)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+            .ok_or_else(|| Error::InvalidStateId(state_id))?;
         Ok(s.final_weight.clone())
     }
 
@@ -54,11 +52,11 @@ impl<W: Semiring> CoreFst<W> for VectorFst<W> {
         self.states.get_unchecked(state_id).final_weight.clone()
     }
 
-    fn num_trs(&self, s: usize) -> Result<usize> {
+    fn num_trs(&self, s: usize) -> Result<usize, Error> {
         Ok(self
             .states
             .get(s)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", s))?
+            .ok_or_else(|| Error::InvalidStateId(s))?
             .trs
             .len())
     }
@@ -67,11 +65,11 @@ impl<W: Semiring> CoreFst<W> for VectorFst<W> {
         self.states.get_unchecked(s).trs.len()
     }
 
-    fn get_trs(&self, state_id: usize) -> Result<Self::TRS> {
+    fn get_trs(&self, state_id: usize) -> Result<Self::TRS, Error> {
         let state = self
             .states
             .get(state_id)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+            .ok_or_else(|| Error::InvalidStateId(state_id))?;
         // Data is not copied, only Arc
         Ok(state.trs.shallow_clone())
     }
@@ -86,19 +84,19 @@ impl<W: Semiring> CoreFst<W> for VectorFst<W> {
         self.properties
     }
 
-    fn num_input_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_input_epsilons(&self, state: usize) -> Result<usize, Error> {
         Ok(self
             .states
             .get(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))?
+            .ok_or_else(|| Error::InvalidStateId(state))?
             .niepsilons)
     }
 
-    fn num_output_epsilons(&self, state: usize) -> Result<usize> {
+    fn num_output_epsilons(&self, state: usize) -> Result<usize, Error> {
         Ok(self
             .states
             .get(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))?
+            .ok_or_else(|| Error::InvalidStateId(state))?
             .noepsilons)
     }
 }
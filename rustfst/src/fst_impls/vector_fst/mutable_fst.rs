@@ -1,8 +1,6 @@
 use std::cmp::Ordering;
 use std::sync::Arc;
 
-use anyhow::Result;
-
 use crate::algorithms::tr_unique::tr_compare;
 use crate::fst_impls::vector_fst::{VectorFst, VectorFstState};
 use crate::fst_properties::mutable_properties::{
@@ -14,7 +12,7 @@ use crate::fst_traits::CoreFst;
 use crate::fst_traits::MutableFst;
 use crate::semirings::Semiring;
 use crate::trs_iter_mut::TrsIterMut;
-use crate::{StateId, Tr, Trs, EPS_LABEL};
+use crate::{Error, StateId, Tr, Trs, EPS_LABEL};
 
 #[inline]
 fn equal_tr<W: Semiring>(tr_1: &Tr<W>, tr_2: &Tr<W>) -> bool {
@@ -32,12 +30,10 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         }
     }
 
-    fn set_start(&mut self, state_id: StateId) -> Result<()> {
-        ensure!(
-            self.states.get(state_id).is_some(),
-            "The state {:?} doesn't exist",
-            state_id
-        );
+    fn set_start(&mut self, state_id: StateId) -> Result<(), Error> {
+        if self.states.get(state_id).is_none() {
+            return Err(Error::InvalidStateId(state_id));
+        }
         self.start_state = Some(state_id);
         self.properties = set_start_properties(self.properties);
         Ok(())
@@ -48,7 +44,7 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         self.properties = set_start_properties(self.properties);
     }
 
-    fn set_final<S: Into<W>>(&mut self, state_id: StateId, final_weight: S) -> Result<()> {
+    fn set_final<S: Into<W>>(&mut self, state_id: StateId, final_weight: S) -> Result<(), Error> {
         if let Some(state) = self.states.get_mut(state_id) {
             let new_final_weight = final_weight.into();
             self.properties = set_final_properties(
@@ -59,7 +55,7 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
             state.final_weight = Some(new_final_weight);
             Ok(())
         } else {
-            bail!("Stateid {:?} doesn't exist", state_id);
+            Err(Error::InvalidStateId(state_id))
         }
     }
 
@@ -89,11 +85,11 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         self.properties = add_state_properties(self.properties);
     }
 
-    fn tr_iter_mut(&mut self, state_id: StateId) -> Result<TrsIterMut<W>> {
+    fn tr_iter_mut(&mut self, state_id: StateId) -> Result<TrsIterMut<W>, Error> {
         let state = self
             .states
             .get_mut(state_id)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+            .ok_or_else(|| Error::InvalidStateId(state_id))?;
         let trs = Arc::make_mut(&mut state.trs.0);
         Ok(TrsIterMut::new(
             trs,
@@ -114,21 +110,19 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         )
     }
 
-    fn del_state(&mut self, state_to_remove: StateId) -> Result<()> {
+    fn del_state(&mut self, state_to_remove: StateId) -> Result<(), Error> {
         // Remove the state from the vector
         // Check the trs for trs going to this state
 
-        ensure!(
-            state_to_remove < self.states.len(),
-            "State id {:?} doesn't exist",
-            state_to_remove
-        );
+        if state_to_remove >= self.states.len() {
+            return Err(Error::InvalidStateId(state_to_remove));
+        }
         self.properties = delete_states_properties(self.properties);
         let v = vec![state_to_remove];
         self.del_states(v.into_iter())
     }
 
-    fn del_states<T: IntoIterator<Item = StateId>>(&mut self, dstates: T) -> Result<()> {
+    fn del_states<T: IntoIterator<Item = StateId>>(&mut self, dstates: T) -> Result<(), Error> {
         let mut new_id = vec![0 as i32; self.states.len()];
 
         for s in dstates {
@@ -231,11 +225,11 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         }
     }
 
-    fn add_tr(&mut self, source: StateId, tr: Tr<W>) -> Result<()> {
+    fn add_tr(&mut self, source: StateId, tr: Tr<W>) -> Result<(), Error> {
         let state = self
             .states
             .get_mut(source)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", source))?;
+            .ok_or_else(|| Error::InvalidStateId(source))?;
         state.increment_num_epsilons(&tr);
         state.trs.push(tr);
         self.update_properties_after_add_tr(source);
@@ -278,12 +272,12 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         self.set_properties(properties)
     }
 
-    fn delete_final_weight(&mut self, source: usize) -> Result<()> {
+    fn delete_final_weight(&mut self, source: usize) -> Result<(), Error> {
         if let Some(s) = self.states.get_mut(source) {
             self.properties = set_final_properties(self.properties, s.final_weight.as_ref(), None);
             s.final_weight = None;
         } else {
-            bail!("State {:?} doesn't exist", source)
+            return Err(Error::InvalidStateId(source));
         }
         Ok(())
     }
@@ -294,11 +288,11 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         s.final_weight = None;
     }
 
-    fn delete_trs(&mut self, source: usize) -> Result<()> {
+    fn delete_trs(&mut self, source: usize) -> Result<(), Error> {
         let state = self
             .states
             .get_mut(source)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", source))?;
+            .ok_or_else(|| Error::InvalidStateId(source))?;
 
         state.trs.clear();
         state.niepsilons = 0;
@@ -307,11 +301,11 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         Ok(())
     }
 
-    fn pop_trs(&mut self, source: usize) -> Result<Vec<Tr<W>>> {
+    fn pop_trs(&mut self, source: usize) -> Result<Vec<Tr<W>>, Error> {
         let state = &mut self
             .states
             .get_mut(source)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", source))?;
+            .ok_or_else(|| Error::InvalidStateId(source))?;
 
         let v = Arc::make_mut(&mut state.trs.0).drain(..).collect();
         state.niepsilons = 0;
@@ -328,11 +322,11 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         Arc::make_mut(&mut state.trs.0).drain(..).collect()
     }
 
-    fn take_final_weight(&mut self, state_id: usize) -> Result<Option<W>> {
+    fn take_final_weight(&mut self, state_id: usize) -> Result<Option<W>, Error> {
         let s = self
             .states
             .get_mut(state_id)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))?;
+            .ok_or_else(|| Error::InvalidStateId(state_id))?;
 
         self.properties = set_final_properties(self.properties, s.final_weight.as_ref(), None);
         Ok(s.final_weight.take())
@@ -2,7 +2,6 @@ use std::fs::read;
 use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
 use nom::bytes::complete::take;
 use nom::multi::count;
 use nom::number::complete::le_i32;
@@ -19,20 +18,16 @@ use crate::parsers::bin_fst::fst_header::FstHeader;
 use crate::parsers::bin_fst::utils_parsing::{parse_final_weight, parse_fst_tr, parse_start_state};
 use crate::parsers::nom_utils::NomCustomError;
 use crate::semirings::SerializableSemiring;
-use crate::{Tr, TrsVec};
+use crate::{Error, Tr, TrsVec};
 
 impl<W: SerializableSemiring> VectorFst<W> {
     /// Load a VectorFst directly from a ConstFst file.
-    pub fn read_from_const<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self> {
-        let data = read(path_bin_fst.as_ref()).with_context(|| {
-            format!(
-                "Can't open ConstFst binary file : {:?}",
-                path_bin_fst.as_ref()
-            )
-        })?;
+    pub fn read_from_const<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self, Error> {
+        let data = read(path_bin_fst.as_ref())
+            .map_err(|e| Error::read_file("ConstFst binary file", path_bin_fst.as_ref(), e))?;
 
         let (_, parsed_fst) = parse_const_fst(&data)
-            .map_err(|_| format_err!("Error while parsing binary ConstFst file as a VectorFst"))?;
+            .map_err(|e| Error::from_nom("binary ConstFst file as a VectorFst", data.len(), e))?;
 
         Ok(parsed_fst)
     }
@@ -1,9 +1,6 @@
-use std::fs::{read, File};
-use std::io::BufWriter;
-use std::path::Path;
+use std::io::Write;
 use std::sync::Arc;
 
-use anyhow::Context;
 use anyhow::Result;
 use nom::multi::count;
 use nom::number::complete::le_i64;
@@ -19,30 +16,21 @@ use crate::parsers::bin_fst::utils_serialization::{write_bin_i32, write_bin_i64}
 use crate::parsers::nom_utils::NomCustomError;
 use crate::parsers::text_fst::ParsedTextFst;
 use crate::semirings::SerializableSemiring;
-use crate::{Tr, Trs, TrsVec, EPS_LABEL};
+use crate::{Error, Tr, Trs, TrsVec, EPS_LABEL};
 
 impl<W: SerializableSemiring> SerializableFst<W> for VectorFst<W> {
     fn fst_type() -> String {
         "vector".to_string()
     }
 
-    fn read<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self> {
-        let data = read(path_bin_fst.as_ref()).with_context(|| {
-            format!(
-                "Can't open VectorFst binary file : {:?}",
-                path_bin_fst.as_ref()
-            )
-        })?;
-
-        let (_, parsed_fst) = parse_vector_fst(&data)
-            .map_err(|e| format_err!("Error while parsing binary VectorFst : {:?}", e))?;
+    fn load(data: &[u8]) -> Result<Self, Error> {
+        let (_, parsed_fst) = parse_vector_fst(data)
+            .map_err(|e| Error::from_nom("binary VectorFst", data.len(), e))?;
 
         Ok(parsed_fst)
     }
 
-    fn write<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
-        let mut file = BufWriter::new(File::create(path_bin_fst)?);
-
+    fn store<O: Write>(&self, mut file: O) -> Result<()> {
         let num_trs: usize = (0..self.num_states())
             .map(|s: usize| unsafe { self.num_trs_unchecked(s) })
             .sum();
@@ -86,10 +74,11 @@ impl<W: SerializableSemiring> SerializableFst<W> for VectorFst<W> {
             }
         }
 
+        file.flush()?;
         Ok(())
     }
 
-    fn from_parsed_fst_text(parsed_fst_text: ParsedTextFst<W>) -> Result<Self> {
+    fn from_parsed_fst_text(parsed_fst_text: ParsedTextFst<W>) -> Result<Self, Error> {
         let start_state = parsed_fst_text.start();
         let num_states = parsed_fst_text.num_states();
 
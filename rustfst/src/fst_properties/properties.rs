@@ -125,6 +125,22 @@ impl FstProperties {
             | FstProperties::UNWEIGHTED_CYCLES
     }
 
+    /// Properties verified by any FST without transitions, whatever its final weights.
+    pub(crate) fn no_trs_properties() -> FstProperties {
+        FstProperties::ACCEPTOR
+            | FstProperties::I_DETERMINISTIC
+            | FstProperties::O_DETERMINISTIC
+            | FstProperties::NO_EPSILONS
+            | FstProperties::NO_I_EPSILONS
+            | FstProperties::NO_O_EPSILONS
+            | FstProperties::I_LABEL_SORTED
+            | FstProperties::O_LABEL_SORTED
+            | FstProperties::ACYCLIC
+            | FstProperties::INITIAL_ACYCLIC
+            | FstProperties::TOP_SORTED
+            | FstProperties::UNWEIGHTED_CYCLES
+    }
+
     /// Properties that are preserved when an FST is copied.
     pub(crate) fn copy_properties() -> FstProperties {
         FstProperties::ACCEPTOR
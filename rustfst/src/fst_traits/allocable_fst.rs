@@ -1,7 +1,6 @@
 use crate::fst_traits::Fst;
 use crate::semirings::Semiring;
-use crate::StateId;
-use anyhow::Result;
+use crate::{Error, StateId};
 
 /// Trait defining the methods to control allocation for a wFST
 pub trait AllocableFst<W: Semiring>: Fst<W> {
@@ -9,7 +8,7 @@ pub trait AllocableFst<W: Semiring>: Fst<W> {
     /// The FST may reserve more space to avoid frequent allocation.
     /// After calling `reserve_trs`, the capacity will be greater or equal to `num_trs` + `additionnal`
     /// This method has no effects if the capacity is already sufficient
-    fn reserve_trs(&mut self, source: StateId, additional: usize) -> Result<()>;
+    fn reserve_trs(&mut self, source: StateId, additional: usize) -> Result<(), Error>;
 
     /// # Safety
     ///
@@ -33,7 +32,7 @@ pub trait AllocableFst<W: Semiring>: Fst<W> {
 
     /// Shrinks the capacity of the leaving trs for the given state as much as possible.
     /// It will drop down as close as possible to theleaving trs.
-    fn shrink_to_fit_trs(&mut self, source: StateId) -> Result<()>;
+    fn shrink_to_fit_trs(&mut self, source: StateId) -> Result<(), Error>;
 
     /// Shrinks the capacity of the leaving trs for the given state as much as possible.
     /// It will drop down as close as possible to theleaving trs.
@@ -47,7 +46,7 @@ pub trait AllocableFst<W: Semiring>: Fst<W> {
     /// Returns the number of states the FST can hold without reallocating.
     fn states_capacity(&self) -> usize;
     /// Returns the number of trs for a given state the FST can hold without reallocating.
-    fn trs_capacity(&self, source: StateId) -> Result<usize>;
+    fn trs_capacity(&self, source: StateId) -> Result<usize, Error>;
 
     /// Returns the number of trs for a given state the FST can hold without reallocating.
     ///
@@ -2,14 +2,13 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use anyhow::Result;
-
-use crate::fst_properties::FstProperties;
+use crate::algorithms::PreconditionError;
+use crate::fst_properties::{known_properties, FstProperties};
 use crate::fst_traits::iterators::StateIterator;
 use crate::fst_traits::FstIterator;
 use crate::semirings::Semiring;
 use crate::trs::Trs;
-use crate::{StateId, SymbolTable};
+use crate::{Error, StateId, SymbolTable};
 
 /// Trait defining necessary methods for a wFST to access start states and final states.
 pub trait CoreFst<W: Semiring> {
@@ -54,7 +53,7 @@ pub trait CoreFst<W: Semiring> {
     /// assert_eq!(fst.final_weight(s2).unwrap(), Some(BooleanWeight::one()));
     /// assert!(fst.final_weight(s2 + 1).is_err());
     /// ```
-    fn final_weight(&self, state: StateId) -> Result<Option<W>>;
+    fn final_weight(&self, state: StateId) -> Result<Option<W>, Error>;
 
     /// Retrieves the final weight of a state (if the state is a final one).
     ///
@@ -81,7 +80,7 @@ pub trait CoreFst<W: Semiring> {
     /// fst.add_tr(s1, Tr::new(3, 5, BooleanWeight::new(true), s2));
     /// assert_eq!(fst.num_trs(s1).unwrap(), 1);
     /// ```
-    fn num_trs(&self, s: StateId) -> Result<usize>;
+    fn num_trs(&self, s: StateId) -> Result<usize, Error>;
 
     /// Number of trs leaving a specific state in the wFST.
     ///
@@ -111,7 +110,7 @@ pub trait CoreFst<W: Semiring> {
     /// assert!(fst.is_final(s2 + 1).is_err());
     /// ```
     #[inline]
-    fn is_final(&self, state_id: StateId) -> Result<bool> {
+    fn is_final(&self, state_id: StateId) -> Result<bool, Error> {
         let w = self.final_weight(state_id)?;
         Ok(w.is_some())
     }
@@ -134,7 +133,7 @@ pub trait CoreFst<W: Semiring> {
     }
 
     /// Get an iterator on the transitions leaving state `state`.
-    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS>;
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS, Error>;
 
     /// Get an iterator on the transitions leaving state `state`.
     ///
@@ -158,14 +157,14 @@ pub trait CoreFst<W: Semiring> {
     /// properties in `props_known` are known (not the same as true). If not an error is returned.
     ///
     /// A property is known if we known for sure if it is true of false.
-    fn properties_check(&self, props_known: FstProperties) -> Result<FstProperties> {
+    fn properties_check(&self, props_known: FstProperties) -> Result<FstProperties, Error> {
         let props = self.properties();
         if !props.knows(props_known) {
-            bail!(
-                "Properties are not known : {:?}. Properties of the Fst : {:?}",
-                props_known,
-                props
-            )
+            return Err(PreconditionError::UnknownFstProperties {
+                algorithm: "properties_check",
+                unknown: props_known & !known_properties(props),
+            }
+            .into());
         }
         Ok(props)
     }
@@ -192,7 +191,7 @@ pub trait CoreFst<W: Semiring> {
     /// assert_eq!(fst.num_input_epsilons(s0).unwrap(), 2);
     /// assert_eq!(fst.num_input_epsilons(s1).unwrap(), 0);
     /// ```
-    fn num_input_epsilons(&self, state: StateId) -> Result<usize>;
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize, Error>;
 
     /// Returns the number of trs with epsilon output labels leaving a state.
     ///
@@ -216,7 +215,7 @@ pub trait CoreFst<W: Semiring> {
     /// assert_eq!(fst.num_output_epsilons(s0).unwrap(), 1);
     /// assert_eq!(fst.num_output_epsilons(s1).unwrap(), 0);
     /// ```
-    fn num_output_epsilons(&self, state: StateId) -> Result<usize>;
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize, Error>;
 }
 
 /// Trait defining the minimum interface necessary for a wFST.
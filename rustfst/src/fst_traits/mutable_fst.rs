@@ -1,7 +1,5 @@
 use std::cmp::Ordering;
 
-use anyhow::Result;
-
 use crate::algorithms::closure::ClosureType;
 use crate::algorithms::TrMapper;
 use crate::fst_properties::FstProperties;
@@ -9,7 +7,7 @@ use crate::fst_traits::ExpandedFst;
 use crate::semirings::Semiring;
 use crate::tr::Tr;
 use crate::trs_iter_mut::TrsIterMut;
-use crate::{Label, StateId};
+use crate::{Error, Label, StateId};
 
 /// Trait defining the methods to modify a wFST.
 pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
@@ -38,7 +36,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     /// fst.set_start(s2);
     /// assert_eq!(fst.start(), Some(s2));
     /// ```
-    fn set_start(&mut self, state_id: StateId) -> Result<()>;
+    fn set_start(&mut self, state_id: StateId) -> Result<(), Error>;
 
     /// The state with identifier `state_id` is now the start state.
     ///
@@ -71,7 +69,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     /// assert_eq!(fst.final_weight(s1).unwrap(), Some(BooleanWeight::one()));
     /// assert_eq!(fst.final_weight(s2).unwrap(), Some(BooleanWeight::one()));
     /// ```
-    fn set_final<S: Into<W>>(&mut self, state_id: StateId, final_weight: S) -> Result<()>;
+    fn set_final<S: Into<W>>(&mut self, state_id: StateId, final_weight: S) -> Result<(), Error>;
 
     /// Set the final weight of the state with state if `state_id`.
     ///
@@ -106,7 +104,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     fn add_states(&mut self, n: usize);
 
     /// Return a mutable iterator on the `Tr`s of the state `state`.
-    fn tr_iter_mut(&mut self, state: StateId) -> Result<TrsIterMut<W>>;
+    fn tr_iter_mut(&mut self, state: StateId) -> Result<TrsIterMut<W>, Error>;
     /// Return a mutable iterator on the `Tr`s of the state `state`.
     ///
     /// # Safety
@@ -137,7 +135,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     /// assert_eq!(fst.states_iter().count(), 0);
     ///
     /// ```
-    fn del_state(&mut self, state_id: StateId) -> Result<()>;
+    fn del_state(&mut self, state_id: StateId) -> Result<(), Error>;
 
     // TODO: Need to define a correct behaviour is the same state is present multiple times in the iterator
     /// Removes multiple states from an FST. If one of the states doesn't exist, an error is raised.
@@ -168,7 +166,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     /// assert_eq!(fst.states_iter().count(), 0);
     ///
     /// ```
-    fn del_states<T: IntoIterator<Item = StateId>>(&mut self, states: T) -> Result<()>;
+    fn del_states<T: IntoIterator<Item = StateId>>(&mut self, states: T) -> Result<(), Error>;
 
     /// Remove all the states in the FST. As a result, all the trs are also removed,
     /// as well as the start state and all the fina states.
@@ -229,7 +227,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     /// # Ok(())
     /// # }
     /// ```
-    fn add_tr(&mut self, source: StateId, tr: Tr<W>) -> Result<()>;
+    fn add_tr(&mut self, source: StateId, tr: Tr<W>) -> Result<(), Error>;
 
     /// Adds a transition to the FST. The transition will start in the state `state`.
     ///
@@ -271,7 +269,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
         olabel: Label,
         weight: S,
         nextstate: StateId,
-    ) -> Result<()> {
+    ) -> Result<(), Error> {
         self.add_tr(source, Tr::new(ilabel, olabel, weight, nextstate))
     }
 
@@ -304,7 +302,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     unsafe fn set_trs_unchecked(&mut self, source: StateId, trs: Vec<Tr<W>>);
 
     /// Remove the final weight of a specific state.
-    fn delete_final_weight(&mut self, source: StateId) -> Result<()>;
+    fn delete_final_weight(&mut self, source: StateId) -> Result<(), Error>;
 
     /// Remove the final weight of state `state`.
     ///
@@ -315,10 +313,10 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     unsafe fn delete_final_weight_unchecked(&mut self, source: StateId);
 
     /// Deletes all the trs leaving a state.
-    fn delete_trs(&mut self, source: StateId) -> Result<()>;
+    fn delete_trs(&mut self, source: StateId) -> Result<(), Error>;
 
     /// Remove all trs leaving a state and return them.
-    fn pop_trs(&mut self, source: StateId) -> Result<Vec<Tr<W>>>;
+    fn pop_trs(&mut self, source: StateId) -> Result<Vec<Tr<W>>, Error>;
 
     /// Remove all the `Tr` leaving the state `state` and return them.
     ///
@@ -354,7 +352,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     /// # Ok(())
     /// # }
     /// ```
-    fn take_final_weight(&mut self, state_id: StateId) -> Result<Option<W>>;
+    fn take_final_weight(&mut self, state_id: StateId) -> Result<Option<W>, Error>;
 
     /// Takes the final weight out of the fst, leaving a None in its place.
     /// This version leads to `undefined behaviour` if the state doesn't exist.
@@ -420,8 +418,8 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     }
 
     /// Maps a transition using a `TrMapper` object.
-    fn tr_map<M: TrMapper<W>>(&mut self, mapper: &mut M) -> Result<()> {
-        crate::algorithms::tr_map(self, mapper)
+    fn tr_map<M: TrMapper<W>>(&mut self, mapper: &mut M) -> Result<(), Error> {
+        Ok(crate::algorithms::tr_map(self, mapper)?)
     }
 
     /// Set the internal properties of the Fst. All the set properties must be verified by the Fst!
@@ -432,7 +430,10 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
 
     /// Compute the properties verified by the Fst (with a mask) and update
     /// the internal property bits.
-    fn compute_and_update_properties(&mut self, mask: FstProperties) -> Result<FstProperties> {
+    fn compute_and_update_properties(
+        &mut self,
+        mask: FstProperties,
+    ) -> Result<FstProperties, Error> {
         let mut knownprops = FstProperties::empty();
         let testprops =
             crate::fst_properties::compute_fst_properties(self, mask, &mut knownprops, true)?;
@@ -441,7 +442,7 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
     }
 
     /// Compute all the properties verified by the Fst and update the internal property bits.
-    fn compute_and_update_properties_all(&mut self) -> Result<FstProperties> {
+    fn compute_and_update_properties_all(&mut self) -> Result<FstProperties, Error> {
         self.compute_and_update_properties(FstProperties::all_properties())
     }
 }
@@ -1,4 +1,4 @@
-use std::fs::File;
+use std::fs::{read, File};
 use std::io::{BufWriter, LineWriter, Write};
 use std::path::Path;
 
@@ -9,7 +9,7 @@ use crate::fst_traits::ExpandedFst;
 use crate::parsers::text_fst::ParsedTextFst;
 use crate::semirings::SerializableSemiring;
 use crate::Trs;
-use crate::{DrawingConfig, StateId};
+use crate::{DrawingConfig, Error, StateId};
 
 /// Trait definining the methods an Fst must implement to be serialized and deserialized.
 pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
@@ -20,23 +20,39 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
     // BINARY
 
     /// Loads an FST from a file in binary format.
-    fn read<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self>;
+    fn read<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self, Error> {
+        let data = read(path_bin_fst.as_ref()).map_err(|e| {
+            Error::read_file(
+                &format!("{} binary file", Self::fst_type()),
+                path_bin_fst.as_ref(),
+                e,
+            )
+        })?;
+        Self::load(&data)
+    }
     /// Writes the FST to a file in binary format.
-    fn write<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()>;
+    fn write<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
+        self.store(BufWriter::new(File::create(path_bin_fst)?))
+    }
+
+    /// Loads an FST from bytes in binary format.
+    fn load(data: &[u8]) -> Result<Self, Error>;
+    /// Writes the FST in binary format to any writer. For instance stdout.
+    fn store<O: Write>(&self, output: O) -> Result<()>;
 
     // TEXT
 
     /// Turns a generic wFST format into the one of the wFST.
-    fn from_parsed_fst_text(parsed_fst_text: ParsedTextFst<W>) -> Result<Self>;
+    fn from_parsed_fst_text(parsed_fst_text: ParsedTextFst<W>) -> Result<Self, Error>;
 
     /// Deserializes a wFST in text from a path and returns a loaded wFST.
-    fn from_text_string(fst_string: &str) -> Result<Self> {
+    fn from_text_string(fst_string: &str) -> Result<Self, Error> {
         let parsed_text_fst = ParsedTextFst::from_string(fst_string)?;
         Self::from_parsed_fst_text(parsed_text_fst)
     }
 
     /// Deserializes a wFST in text from a path and returns a loaded wFST.
-    fn read_text<P: AsRef<Path>>(path_text_fst: P) -> Result<Self> {
+    fn read_text<P: AsRef<Path>>(path_text_fst: P) -> Result<Self, Error> {
         let parsed_text_fst = ParsedTextFst::from_path(path_text_fst)?;
         Self::from_parsed_fst_text(parsed_text_fst)
     }
@@ -61,7 +77,28 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
     fn draw<P: AsRef<Path>>(&self, path_output: P, config: &DrawingConfig) -> Result<()> {
         let buffer = File::create(path_output.as_ref())?;
         let mut f = BufWriter::new(LineWriter::new(buffer));
+        self.draw_to(&mut f, config)
+    }
+
+    /// Writes the DOT representation of the FST into a String.
+    fn dot(&self, config: &DrawingConfig) -> Result<String> {
+        let mut buffer = Vec::<u8>::new();
+        self.draw_to(&mut buffer, config)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    /// Renders the FST as an SVG file. The layout is computed in pure Rust, no GraphViz
+    /// binary is needed.
+    #[cfg(feature = "svg")]
+    fn draw_svg<P: AsRef<Path>>(&self, path_output: P, config: &DrawingConfig) -> Result<()> {
+        let svg = dot_to_svg(&self.dot(config)?)?;
+        let mut f = BufWriter::new(File::create(path_output.as_ref())?);
+        f.write_all(svg.as_bytes())?;
+        Ok(())
+    }
 
+    /// Writes the DOT representation of the FST to any writer.
+    fn draw_to<O: Write>(&self, f: &mut O, config: &DrawingConfig) -> Result<()> {
         if let Some(start_state) = self.start() {
             writeln!(f, "digraph FST {{")?;
 
@@ -93,11 +130,11 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
             }
 
             // Start state first
-            draw_single_fst_state(self, &mut f, start_state, config)?;
+            draw_single_fst_state(self, f, start_state, config)?;
 
             for state in self.states_iter() {
                 if state != start_state {
-                    draw_single_fst_state(self, &mut f, state, config)?;
+                    draw_single_fst_state(self, f, state, config)?;
                 }
             }
 
@@ -107,6 +144,27 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
     }
 }
 
+#[cfg(feature = "svg")]
+fn dot_to_svg(dot: &str) -> Result<String> {
+    use layout::backends::svg::SVGWriter;
+    use layout::gv::{DotParser, GraphBuilder};
+
+    let graph = DotParser::new(dot)
+        .process()
+        .map_err(|e| format_err!("Can't parse the DOT representation of the FST : {}", e))?;
+    let mut builder = GraphBuilder::new();
+    builder.visit_graph(&graph);
+    let mut visual_graph = builder.get();
+    let mut svg = SVGWriter::new();
+    visual_graph.do_it(false, false, false, &mut svg);
+    Ok(svg.finalize())
+}
+
+/// Escapes the double quotes of a label written in a DOT file.
+fn escape_dot_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 fn draw_single_fst_state<S: SerializableSemiring, F: SerializableFst<S>, W: Write>(
     fst: &F,
     writer: &mut W,
@@ -116,8 +174,14 @@ fn draw_single_fst_state<S: SerializableSemiring, F: SerializableFst<S>, W: Writ
     let opt_isymt = fst.input_symbols();
     let opt_osymt = fst.output_symbols();
 
+    let state_label = config
+        .state_symbols
+        .as_ref()
+        .and_then(|symt| symt.get_symbol(state_id))
+        .map_or_else(|| format!("{}", state_id), escape_dot_label);
+
     write!(writer, "{}", state_id)?;
-    write!(writer, " [label = \"{}", state_id)?;
+    write!(writer, " [label = \"{}", state_label)?;
     if let Some(final_weight) = fst.final_weight(state_id)? {
         if config.print_weight && (config.show_weight_one || !final_weight.is_one()) {
             write!(writer, "/{}", final_weight)?;
@@ -133,17 +197,23 @@ fn draw_single_fst_state<S: SerializableSemiring, F: SerializableFst<S>, W: Writ
         write!(writer, " style = solid,")?;
     }
 
+    if config.highlighted_states.contains(&state_id) {
+        write!(writer, " color = \"{}\",", config.highlight_color)?;
+    }
+
     writeln!(writer, " fontsize = {}]", config.fontsize)?;
 
-    for tr in fst.get_trs(state_id).unwrap().trs() {
+    for (tr_idx, tr) in fst.get_trs(state_id)?.trs().iter().enumerate() {
         write!(writer, "\t{} -> {}", state_id, tr.nextstate)?;
 
         let ilabel = opt_isymt.clone().map_or_else(
             || Ok(format!("{}", tr.ilabel)),
             |symt| {
                 symt.get_symbol(tr.ilabel)
-                    .map(|v| v.to_string())
-                    .ok_or_else(|| format_err!("Missing {} in input SymbolTable", tr.ilabel))
+                    .map(escape_dot_label)
+                    .ok_or_else(|| {
+                        Error::SymbolTable(format!("Missing {} in input SymbolTable", tr.ilabel))
+                    })
             },
         )?;
 
@@ -151,8 +221,10 @@ fn draw_single_fst_state<S: SerializableSemiring, F: SerializableFst<S>, W: Writ
             || Ok(format!("{}", tr.olabel)),
             |symt| {
                 symt.get_symbol(tr.olabel)
-                    .map(|v| v.to_string())
-                    .ok_or_else(|| format_err!("Missing {} in output SymbolTable", tr.olabel))
+                    .map(escape_dot_label)
+                    .ok_or_else(|| {
+                        Error::SymbolTable(format!("Missing {} in output SymbolTable", tr.olabel))
+                    })
             },
         )?;
 
@@ -164,7 +236,15 @@ fn draw_single_fst_state<S: SerializableSemiring, F: SerializableFst<S>, W: Writ
         if config.print_weight && (config.show_weight_one || !tr.weight.is_one()) {
             write!(writer, "/{}", tr.weight)?;
         }
-        writeln!(writer, "\", fontsize = {}];", config.fontsize)?;
+        write!(writer, "\",")?;
+        if config.highlighted_trs.contains(&(state_id, tr_idx)) {
+            write!(
+                writer,
+                " color = \"{}\", fontcolor = \"{}\",",
+                config.highlight_color, config.highlight_color
+            )?;
+        }
+        writeln!(writer, " fontsize = {}];", config.fontsize)?;
     }
 
     Ok(())
@@ -103,7 +103,7 @@ extern crate serde_json;
 
 pub use crate::drawing_config::DrawingConfig;
 pub use crate::fst_path::{check_path_in_fst, FstPath};
-pub use crate::symbol_table::SymbolTable;
+pub use crate::symbol_table::{compat_symbols, SymbolTable};
 
 pub use self::tr::Tr;
 pub use self::trs::{Trs, TrsConst, TrsVec};
@@ -155,10 +155,14 @@ pub mod fst_impls;
 pub mod semirings;
 
 mod drawing_config;
+/// The errors raised by the crate.
+mod error;
 /// Implementation of a successful path inside a wFST.
 mod fst_path;
 mod parsers;
 
+pub use crate::parsers::bin_fst::fst_header::BinaryFstTypes;
+pub use crate::error::Error;
 pub use crate::parsers::nom_utils::NomCustomError;
 
 /// A representable float near .001. (Used in Quantize)
@@ -1,8 +1,11 @@
-use std::io::Write;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
 
 use anyhow::Result;
 use nom::bytes::complete::take;
 use nom::combinator::{map_res, verify};
+use nom::error::ErrorKind;
 use nom::number::complete::{le_i32, le_i64, le_u32, le_u64};
 use nom::IResult;
 
@@ -13,7 +16,7 @@ use crate::parsers::bin_fst::utils_serialization::{
 };
 use crate::parsers::bin_symt::nom_parser::{parse_symbol_table_bin, write_bin_symt};
 use crate::parsers::nom_utils::NomCustomError;
-use crate::SymbolTable;
+use crate::{Error, SymbolTable};
 use std::sync::Arc;
 
 // Identifies stream data as an FST (and its endianity).
@@ -42,13 +45,94 @@ pub(crate) struct FstHeader {
     pub(crate) osymt: Option<Arc<SymbolTable>>,
 }
 
+/// Type of an FST and of its transitions, as stored in the header of a binary FST file.
+///
+/// Useful to find out which `SerializableFst` and which `SerializableSemiring` must be used
+/// to load a binary file without having to parse it completely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryFstTypes {
+    /// Type of the FST. For instance `vector` or `const`.
+    pub fst_type: String,
+    /// Type of the transitions. For instance `standard`, `log` or `log64`.
+    pub tr_type: String,
+}
+
+impl BinaryFstTypes {
+    /// Parses the types from the beginning of a binary FST.
+    pub fn parse(i: &[u8]) -> Result<Self, Error> {
+        let (_, types) = parse_binary_fst_types(i)
+            .map_err(|e| Error::from_nom("binary FST header", i.len(), e))?;
+        Ok(types)
+    }
+
+    /// Reads the types from the header of a binary FST file.
+    /// Only the beginning of the file is read.
+    pub fn read<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self, Error> {
+        let file = File::open(path_bin_fst.as_ref())
+            .map_err(|e| Error::read_file("binary FST file", path_bin_fst.as_ref(), e))?;
+        let mut reader = file.take(0);
+        let mut data = vec![];
+
+        // Magic number followed by the size of the fst type.
+        reader.set_limit(8);
+        reader.read_to_end(&mut data)?;
+        let n_fst_type = read_size_openfst_string(&data)?;
+
+        // Fst type followed by the size of the tr type.
+        reader.set_limit(n_fst_type + 4);
+        reader.read_to_end(&mut data)?;
+        let n_tr_type = read_size_openfst_string(&data)?;
+
+        reader.set_limit(n_tr_type);
+        reader.read_to_end(&mut data)?;
+
+        Self::parse(&data)
+    }
+}
+
+/// Reads the size of the OpenFst string whose size is stored in the last four bytes read.
+fn read_size_openfst_string(data: &[u8]) -> Result<u64, Error> {
+    if data.len() < 8 {
+        return Err(Error::Parse {
+            context: "binary FST header",
+            line: None,
+            error: None,
+        });
+    }
+    let mut buffer = [0u8; 4];
+    buffer.copy_from_slice(&data[data.len() - 4..]);
+    let n = i32::from_le_bytes(buffer);
+    if n < 0 {
+        // Negative string size.
+        return Err(Error::Parse {
+            context: "binary FST header",
+            line: None,
+            error: Some(NomCustomError::Nom(data.len() - 4, ErrorKind::Verify)),
+        });
+    }
+    Ok(n as u64)
+}
+
+fn parse_binary_fst_types(i: &[u8]) -> IResult<&[u8], BinaryFstTypes, NomCustomError<&[u8]>> {
+    let (i, _magic_number) = verify(le_i32, |v: &i32| *v == FST_MAGIC_NUMBER)(i)?;
+    let (i, fst_type) = OpenFstString::parse(i)?;
+    let (i, tr_type) = OpenFstString::parse(i)?;
+    Ok((
+        i,
+        BinaryFstTypes {
+            fst_type: fst_type.into(),
+            tr_type: tr_type.into(),
+        },
+    ))
+}
+
 #[derive(Debug)]
 pub(crate) struct OpenFstString {
     n: i32,
     s: String,
 }
 
-fn optionally_parse_symt(
+pub(crate) fn optionally_parse_symt(
     i: &[u8],
     parse_symt: bool,
 ) -> IResult<&[u8], Option<SymbolTable>, NomCustomError<&[u8]>> {
@@ -60,7 +144,10 @@ fn optionally_parse_symt(
     }
 }
 
-fn optionally_write_symt<W: Write>(file: &mut W, symt: &Option<Arc<SymbolTable>>) -> Result<()> {
+pub(crate) fn optionally_write_symt<W: Write>(
+    file: &mut W,
+    symt: &Option<Arc<SymbolTable>>,
+) -> Result<()> {
     if let Some(symt) = symt {
         write_bin_symt(file, symt)
     } else {
@@ -157,6 +244,10 @@ impl OpenFstString {
         ))
     }
 
+    pub(crate) fn as_str(&self) -> &str {
+        self.s.as_str()
+    }
+
     pub(crate) fn write<W: Write>(&self, file: &mut W) -> Result<()> {
         write_bin_i32(file, self.n)?;
         file.write_all(self.s.as_bytes()).map_err(|e| e.into())
@@ -23,12 +23,13 @@ pub(crate) fn parse_symbol_table_bin(
     i: &[u8],
 ) -> IResult<&[u8], SymbolTable, NomCustomError<&[u8]>> {
     let (i, _magic_number) = verify(le_i32, |v| *v == SYMBOL_TABLE_MAGIC_NUMBER)(i)?;
-    let (i, _name) = OpenFstString::parse(i)?;
+    let (i, name) = OpenFstString::parse(i)?;
     let (i, _available_key) = le_i64(i)?;
     let (i, num_symbols) = le_i64(i)?;
     let (i, pairs_idx_symbols) = count(parse_row_symt, num_symbols as usize)(i)?;
 
     let mut symt = SymbolTable::empty();
+    symt.set_name(name);
     for (key, symbol) in pairs_idx_symbols.into_iter() {
         let inserted_label = symt.add_symbol(symbol);
         if inserted_label != key as usize {
@@ -46,7 +47,7 @@ pub(crate) fn write_bin_symt<W: Write, H: BuildHasher>(
     symt: &SymbolTable<H>,
 ) -> Result<()> {
     write_bin_i32(file, SYMBOL_TABLE_MAGIC_NUMBER)?;
-    OpenFstString::new("rustfst_symboltable").write(file)?;
+    OpenFstString::new(symt.name()).write(file)?;
     // TODO: Might not be available
     write_bin_i64(file, symt.len() as i64)?;
     write_bin_i64(file, symt.len() as i64)?;
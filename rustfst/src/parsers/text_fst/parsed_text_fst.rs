@@ -1,11 +1,10 @@
 use std::fs::read_to_string;
 use std::path::Path;
 
-use anyhow::Result;
-
+use crate::parsers::nom_utils::NomCustomError;
 use crate::parsers::text_fst::nom_parser::vec_rows_parsed;
 use crate::semirings::SerializableSemiring;
-use crate::{Label, StateId};
+use crate::{Error, Label, StateId};
 
 #[derive(Debug, PartialEq)]
 pub enum RowParsed<W: SerializableSemiring> {
@@ -84,9 +83,18 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
     /// 4   5   5   5   0.31
     /// 3   0.67
     /// ```
-    pub fn from_string(fst_string: &str) -> Result<Self> {
-        let (_, vec_rows_parsed) =
-            vec_rows_parsed(fst_string).map_err(|_| format_err!("Error while parsing text fst"))?;
+    ///
+    /// The error returned when a row can't be parsed gives its line.
+    pub fn from_string(fst_string: &str) -> Result<Self, Error> {
+        let (rest, vec_rows_parsed) = vec_rows_parsed(fst_string).map_err(|e| {
+            let e = e.map(|(i, kind)| NomCustomError::Nom(i, kind));
+            Error::from_nom_text("text FST", fst_string, e)
+        })?;
+        if !rest.trim().is_empty() {
+            // The row that couldn't be parsed follows the last separator, which is not consumed.
+            let offset = fst_string.len() - rest.len() + rest.starts_with('\n') as usize;
+            return Err(Error::unparsed_text("text FST", fst_string, offset));
+        }
 
         Ok(Self::from_vec_rows_parsed(vec_rows_parsed))
     }
@@ -133,7 +141,7 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
     /// 4   5   5   5   0.31
     /// 3   0.67
     /// ```
-    pub fn from_path<P: AsRef<Path>>(path_fst_text: P) -> Result<Self> {
+    pub fn from_path<P: AsRef<Path>>(path_fst_text: P) -> Result<Self, Error> {
         let fst_string = read_to_string(path_fst_text)?;
         Self::from_string(&fst_string)
     }
@@ -182,6 +190,8 @@ impl<W: SerializableSemiring> FinalState<W> {
 
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
+
     use super::*;
     use crate::semirings::{Semiring, TropicalWeight};
 
@@ -258,4 +268,19 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_text_fst_error_line() {
+        // The second row has too many fields for a final state and too few for a transition.
+        let err =
+            ParsedTextFst::<TropicalWeight>::from_string("0\t1\t12\t25\n1\t2\t3\n1\n").unwrap_err();
+        match err {
+            Error::Parse {
+                context: "text FST",
+                line: Some(2),
+                ..
+            } => {}
+            e => panic!("Unexpected error : {}", e),
+        }
+    }
 }
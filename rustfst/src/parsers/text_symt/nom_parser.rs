@@ -1,5 +1,7 @@
+use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::tab;
+use nom::error::ErrorKind;
 use nom::multi::many0;
 use nom::sequence::terminated;
 use nom::IResult;
@@ -15,8 +17,17 @@ fn row(i: &str) -> IResult<&str, (Symbol, Label)> {
     Ok((i, (symbol, label)))
 }
 
+/// Matches the end of the input, the last row not being followed by a newline.
+fn end_of_input(i: &str) -> IResult<&str, &str> {
+    if i.is_empty() {
+        Ok((i, i))
+    } else {
+        Err(nom::Err::Error((i, ErrorKind::Eof)))
+    }
+}
+
 fn vec_rows(i: &str) -> IResult<&str, Vec<(Symbol, Label)>> {
-    many0(terminated(row, tag("\n")))(i)
+    many0(terminated(row, alt((tag("\n"), end_of_input))))(i)
 }
 
 pub(crate) fn parse_text_symt(i: &str) -> IResult<&str, ParsedTextSymt> {
@@ -1,10 +1,9 @@
 use std::fs::read_to_string;
 use std::path::Path;
 
-use anyhow::Result;
-
+use crate::parsers::nom_utils::NomCustomError;
 use crate::parsers::text_symt::nom_parser::parse_text_symt;
-use crate::{Label, Symbol};
+use crate::{Error, Label, Symbol};
 
 #[derive(Debug, PartialEq, Default)]
 pub(crate) struct ParsedTextSymt {
@@ -12,13 +11,23 @@ pub(crate) struct ParsedTextSymt {
 }
 
 impl ParsedTextSymt {
-    pub(crate) fn from_string(symt_string: &str) -> Result<Self> {
-        let (_, parsed_symt) = parse_text_symt(symt_string)
-            .map_err(|_| format_err!("Error while parsing text symt"))?;
+    pub(crate) fn from_string(symt_string: &str) -> Result<Self, Error> {
+        let (rest, parsed_symt) = parse_text_symt(symt_string).map_err(|e| {
+            let e = e.map(|(i, kind)| NomCustomError::Nom(i, kind));
+            Error::from_nom_text("text SymbolTable", symt_string, e)
+        })?;
+        if !rest.trim().is_empty() {
+            let offset = symt_string.len() - rest.len();
+            return Err(Error::unparsed_text(
+                "text SymbolTable",
+                symt_string,
+                offset,
+            ));
+        }
         Ok(parsed_symt)
     }
 
-    pub(crate) fn from_path<P: AsRef<Path>>(path_symt_text: P) -> Result<Self> {
+    pub(crate) fn from_path<P: AsRef<Path>>(path_symt_text: P) -> Result<Self, Error> {
         let symt_string = read_to_string(path_symt_text)?;
         Self::from_string(&symt_string)
     }
@@ -3,7 +3,8 @@
 use proptest::prelude::*;
 
 use crate::fst_impls::VectorFst;
-use crate::fst_traits::MutableFst;
+use crate::fst_properties::{compat_properties, compute_fst_properties, FstProperties};
+use crate::fst_traits::{ExpandedFst, MutableFst};
 use crate::semirings::{Semiring, TropicalWeight};
 use crate::Tr;
 
@@ -131,3 +132,22 @@ impl<W: Arbitrary + Semiring> Arbitrary for VectorFst<W> {
             .boxed()
     }
 }
+
+/// Strategy generating FSTs whose properties are all known, so that the properties set by an
+/// algorithm can be checked against the ones computed on its output.
+pub fn any_fst_with_properties<W: Arbitrary + Semiring>() -> BoxedStrategy<VectorFst<W>> {
+    any::<VectorFst<W>>()
+        .prop_map(|mut fst| {
+            fst.compute_and_update_properties_all().unwrap();
+            fst
+        })
+        .boxed()
+}
+
+/// Whether the properties stored in the FST agree with the ones computed from scratch.
+pub fn properties_compatible<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> bool {
+    let mut known = FstProperties::empty();
+    let computed =
+        compute_fst_properties(fst, FstProperties::all_properties(), &mut known, false).unwrap();
+    compat_properties(fst.properties(), computed)
+}
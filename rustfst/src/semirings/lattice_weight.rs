@@ -0,0 +1,240 @@
+use std::borrow::Borrow;
+use std::f32;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use anyhow::Result;
+use nom::bytes::complete::tag;
+use nom::number::complete::{float, le_f32};
+use nom::sequence::separated_pair;
+use nom::IResult;
+
+use crate::parsers::bin_fst::utils_serialization::write_bin_f32;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::semirings::utils_float::float_approx_equal;
+use crate::semirings::{
+    CompleteSemiring, CostWeight, DivideType, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::{Error, KDELTA};
+
+/// Lattice semiring of speech recognition decoders : a pair of costs (graph cost, acoustic cost)
+/// added by `times`. `plus` keeps the weight with the lowest total cost, and the lowest graph
+/// cost in case of tie.
+#[derive(Clone, Debug, PartialOrd, Default, Copy)]
+pub struct LatticeWeight {
+    value: (f32, f32),
+}
+
+impl LatticeWeight {
+    /// Graph cost, the cost of the decoding graph.
+    pub fn value1(&self) -> f32 {
+        self.value.0
+    }
+
+    /// Acoustic cost.
+    pub fn value2(&self) -> f32 {
+        self.value.1
+    }
+
+    fn is_better_than(&self, other: &Self) -> bool {
+        let (s1, s2) = (self.value.0 + self.value.1, other.value.0 + other.value.1);
+        s1 < s2 || (s1 == s2 && self.value.0 < other.value.0)
+    }
+}
+
+impl Semiring for LatticeWeight {
+    type Type = (f32, f32);
+    type ReverseWeight = LatticeWeight;
+
+    fn zero() -> Self {
+        Self {
+            value: (f32::INFINITY, f32::INFINITY),
+        }
+    }
+
+    fn one() -> Self {
+        Self { value: (0.0, 0.0) }
+    }
+
+    fn new(value: <Self as Semiring>::Type) -> Self {
+        Self { value }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        if rhs.borrow().is_better_than(self) {
+            self.value = rhs.borrow().value;
+        }
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let rhs = rhs.borrow();
+        if self.value.0 == f32::INFINITY || rhs.value.0 == f32::INFINITY {
+            *self = Self::zero();
+        } else {
+            self.value.0 += rhs.value.0;
+            self.value.1 += rhs.value.1;
+        }
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        let rhs = rhs.borrow();
+        float_approx_equal(self.value.0, rhs.value.0, delta)
+            && float_approx_equal(self.value.1, rhs.value.1, delta)
+    }
+
+    fn value(&self) -> &Self::Type {
+        &self.value
+    }
+
+    fn take_value(self) -> Self::Type {
+        self.value
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.value = value
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        Ok(*self)
+    }
+
+    fn properties() -> SemiringProperties {
+        SemiringProperties::LEFT_SEMIRING
+            | SemiringProperties::RIGHT_SEMIRING
+            | SemiringProperties::COMMUTATIVE
+            | SemiringProperties::PATH
+            | SemiringProperties::IDEMPOTENT
+    }
+}
+
+impl ReverseBack<LatticeWeight> for LatticeWeight {
+    fn reverse_back(&self) -> Result<LatticeWeight> {
+        Ok(*self)
+    }
+}
+
+impl AsRef<LatticeWeight> for LatticeWeight {
+    fn as_ref(&self) -> &LatticeWeight {
+        &self
+    }
+}
+
+impl fmt::Display for LatticeWeight {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.value.0, self.value.1)
+    }
+}
+
+impl CompleteSemiring for LatticeWeight {}
+
+impl StarSemiring for LatticeWeight {
+    fn closure(&self) -> Self {
+        if self.value.0 + self.value.1 >= 0.0 && self.value.0.is_finite() {
+            Self::one()
+        } else {
+            Self::new((f32::NEG_INFINITY, f32::NEG_INFINITY))
+        }
+    }
+}
+
+impl WeaklyDivisibleSemiring for LatticeWeight {
+    fn divide_assign(&mut self, rhs: &Self, _divide_type: DivideType) -> Result<()> {
+        if rhs.value.0 == f32::INFINITY {
+            bail!(Error::SemiringCapability(
+                "LatticeWeight: Division by zero".to_string()
+            ))
+        }
+        if self.value.0 != f32::INFINITY {
+            self.value.0 -= rhs.value.0;
+            self.value.1 -= rhs.value.1;
+        }
+        Ok(())
+    }
+}
+
+impl WeightQuantize for LatticeWeight {
+    fn quantize_assign(&mut self, delta: f32) -> Result<()> {
+        if self.value.0.is_infinite() || self.value.1.is_infinite() {
+            return Ok(());
+        }
+        self.value.0 = ((self.value.0 / delta) + 0.5).floor() * delta;
+        self.value.1 = ((self.value.1 / delta) + 0.5).floor() * delta;
+        Ok(())
+    }
+}
+
+impl CostWeight for LatticeWeight {
+    fn to_cost(&self) -> f64 {
+        f64::from(self.value.0) + f64::from(self.value.1)
+    }
+
+    fn from_cost(cost: f64) -> Self {
+        Self::new((cost as f32, 0.0))
+    }
+}
+
+impl PartialEq for LatticeWeight {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_equal(other, KDELTA)
+    }
+}
+
+impl Eq for LatticeWeight {}
+
+impl Hash for LatticeWeight {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.0.to_bits().hash(state);
+        self.value.1.to_bits().hash(state);
+    }
+}
+
+impl SerializableSemiring for LatticeWeight {
+    fn weight_type() -> String {
+        "lattice4".to_string()
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, value1) = le_f32(i)?;
+        let (i, value2) = le_f32(i)?;
+        Ok((i, Self::new((value1, value2))))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        write_bin_f32(file, self.value.0)?;
+        write_bin_f32(file, self.value.1)
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let (i, value) = separated_pair(float, tag(","), float)(i)?;
+        Ok((i, Self::new(value)))
+    }
+}
+
+test_semiring_serializable!(
+    tests_lattice_weight_serializable,
+    LatticeWeight,
+    LatticeWeight::one() LatticeWeight::new((0.3, 1.5)) LatticeWeight::new((-1.2, 0.5))
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lattice_weight_plus_total_cost() -> Result<()> {
+        let w1 = LatticeWeight::new((1.0, 2.0));
+        let w2 = LatticeWeight::new((2.5, 0.0));
+        assert_eq!(w1.plus(&w2)?, w2);
+        // Same total cost : the lowest graph cost wins.
+        let w3 = LatticeWeight::new((0.5, 2.5));
+        assert_eq!(w1.plus(&w3)?, w3);
+        assert_eq!(w1.times(&w2)?, LatticeWeight::new((3.5, 2.0)));
+        assert_eq!(w1.times(LatticeWeight::zero())?, LatticeWeight::zero());
+        assert_eq!(w1.plus(LatticeWeight::zero())?, w1);
+        Ok(())
+    }
+}
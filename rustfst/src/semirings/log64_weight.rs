@@ -0,0 +1,182 @@
+use std::borrow::Borrow;
+use std::f64;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use anyhow::Result;
+use nom::number::complete::{double, le_f64};
+use nom::IResult;
+use ordered_float::OrderedFloat;
+
+use crate::parsers::bin_fst::utils_serialization::write_bin_f64;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::semirings::utils_float::float64_approx_equal;
+use crate::semirings::{
+    CompleteSemiring, CostWeight, DivideType, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::KDELTA;
+
+/// Log semiring with double precision: (log(e^-x + e^-y), +, inf, 0).
+#[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
+pub struct Log64Weight {
+    value: OrderedFloat<f64>,
+}
+
+fn ln_pos_exp(x: f64) -> f64 {
+    ((-x).exp()).ln_1p()
+}
+
+impl Semiring for Log64Weight {
+    type Type = f64;
+    type ReverseWeight = Log64Weight;
+
+    fn zero() -> Self {
+        Self {
+            value: OrderedFloat(f64::INFINITY),
+        }
+    }
+    fn one() -> Self {
+        Self {
+            value: OrderedFloat(0.0),
+        }
+    }
+
+    fn new(value: <Self as Semiring>::Type) -> Self {
+        Log64Weight {
+            value: OrderedFloat(value),
+        }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let f1 = self.value();
+        let f2 = rhs.borrow().value();
+        self.value.0 = if f1.eq(&f64::INFINITY) {
+            *f2
+        } else if f2.eq(&f64::INFINITY) {
+            *f1
+        } else if f1 > f2 {
+            f2 - ln_pos_exp(f1 - f2)
+        } else {
+            f1 - ln_pos_exp(f2 - f1)
+        };
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let f1 = self.value();
+        let f2 = rhs.borrow().value();
+        if f1.eq(&f64::INFINITY) {
+        } else if f2.eq(&f64::INFINITY) {
+            self.value.0 = *f2;
+        } else {
+            self.value.0 += f2;
+        }
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        float64_approx_equal(self.value.0, rhs.borrow().value.0, delta)
+    }
+
+    fn value(&self) -> &Self::Type {
+        self.value.as_ref()
+    }
+
+    fn take_value(self) -> Self::Type {
+        self.value.into_inner()
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.value.0 = value
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        Ok(*self)
+    }
+
+    fn properties() -> SemiringProperties {
+        SemiringProperties::LEFT_SEMIRING
+            | SemiringProperties::RIGHT_SEMIRING
+            | SemiringProperties::COMMUTATIVE
+    }
+}
+
+impl ReverseBack<Log64Weight> for Log64Weight {
+    fn reverse_back(&self) -> Result<Log64Weight> {
+        Ok(*self)
+    }
+}
+
+impl AsRef<Log64Weight> for Log64Weight {
+    fn as_ref(&self) -> &Log64Weight {
+        &self
+    }
+}
+
+display_semiring!(Log64Weight);
+
+impl CompleteSemiring for Log64Weight {}
+
+impl StarSemiring for Log64Weight {
+    fn closure(&self) -> Self {
+        if self.value.0 >= 0.0 && self.value.0 < 1.0 {
+            Self::new((1.0 - self.value.0).ln())
+        } else {
+            Self::new(f64::NEG_INFINITY)
+        }
+    }
+}
+
+impl WeaklyDivisibleSemiring for Log64Weight {
+    fn divide_assign(&mut self, rhs: &Self, _divide_type: DivideType) -> Result<()> {
+        self.value.0 -= rhs.value.0;
+        Ok(())
+    }
+}
+
+impl_quantize_f64!(Log64Weight);
+
+impl CostWeight for Log64Weight {
+    fn to_cost(&self) -> f64 {
+        self.value.0
+    }
+
+    fn from_cost(cost: f64) -> Self {
+        Self::new(cost)
+    }
+}
+
+partial_eq_and_hash_f64!(Log64Weight);
+
+impl SerializableSemiring for Log64Weight {
+    fn weight_type() -> String {
+        "log64".to_string()
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, weight) = le_f64(i)?;
+        Ok((i, Self::new(weight)))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        write_bin_f64(file, *self.value())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let (i, f) = double(i)?;
+        Ok((i, Self::new(f)))
+    }
+}
+
+test_semiring_serializable!(
+    tests_log64_weight_serializable,
+    Log64Weight,
+    Log64Weight::new(0.3) Log64Weight::new(0.5) Log64Weight::new(0.0) Log64Weight::new(-1.2)
+);
+
+impl Into<Log64Weight> for f64 {
+    fn into(self) -> Log64Weight {
+        Log64Weight::new(self)
+    }
+}
@@ -12,8 +12,8 @@ use crate::parsers::bin_fst::utils_serialization::write_bin_f32;
 use crate::parsers::nom_utils::NomCustomError;
 use crate::semirings::utils_float::float_approx_equal;
 use crate::semirings::{
-    CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
-    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+    CompleteSemiring, CostWeight, DivideType, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
 };
 use crate::KDELTA;
 
@@ -137,6 +137,16 @@ impl WeaklyDivisibleSemiring for LogWeight {
 
 impl_quantize_f32!(LogWeight);
 
+impl CostWeight for LogWeight {
+    fn to_cost(&self) -> f64 {
+        f64::from(self.value.0)
+    }
+
+    fn from_cost(cost: f64) -> Self {
+        Self::new(cost as f32)
+    }
+}
+
 partial_eq_and_hash_f32!(LogWeight);
 
 impl SerializableSemiring for LogWeight {
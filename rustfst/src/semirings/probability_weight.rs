@@ -12,10 +12,10 @@ use crate::parsers::bin_fst::utils_serialization::write_bin_f32;
 use crate::parsers::nom_utils::NomCustomError;
 use crate::semirings::utils_float::float_approx_equal;
 use crate::semirings::{
-    CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
-    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+    CompleteSemiring, CostWeight, DivideType, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::KDELTA;
+use crate::{Error, KDELTA};
 
 /// Probability semiring: (x, +, 0.0, 1.0).
 #[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
@@ -127,7 +127,7 @@ impl WeaklyDivisibleSemiring for ProbabilityWeight {
     fn divide_assign(&mut self, rhs: &Self, _divide_type: DivideType) -> Result<()> {
         // May panic if rhs.value == 0.0
         if rhs.value.0 == 0.0 {
-            bail!("Division by 0")
+            bail!(Error::SemiringCapability("Division by 0".to_string()))
         }
         self.value.0 /= rhs.value.0;
         Ok(())
@@ -136,6 +136,16 @@ impl WeaklyDivisibleSemiring for ProbabilityWeight {
 
 impl_quantize_f32!(ProbabilityWeight);
 
+impl CostWeight for ProbabilityWeight {
+    fn to_cost(&self) -> f64 {
+        -f64::from(self.value.0).ln()
+    }
+
+    fn from_cost(cost: f64) -> Self {
+        Self::new((-cost).exp() as f32)
+    }
+}
+
 partial_eq_and_hash_f32!(ProbabilityWeight);
 
 test_semiring_serializable!(
@@ -128,6 +128,14 @@ pub trait WeightQuantize: Semiring {
     }
 }
 
+/// Semiring whose weights can be represented by a cost, a negative log probability : `times`
+/// adds the costs and a lower cost is a better weight. This gives an order on the paths of the
+/// semirings that don't have the path property, like the log semiring.
+pub trait CostWeight: Semiring {
+    fn to_cost(&self) -> f64;
+    fn from_cost(cost: f64) -> Self;
+}
+
 macro_rules! impl_quantize_f32 {
     ($semiring: ident) => {
         impl WeightQuantize for $semiring {
@@ -143,6 +151,22 @@ macro_rules! impl_quantize_f32 {
     };
 }
 
+macro_rules! impl_quantize_f64 {
+    ($semiring: ident) => {
+        impl WeightQuantize for $semiring {
+            fn quantize_assign(&mut self, delta: f32) -> Result<()> {
+                let v = *self.value();
+                if v == f64::INFINITY || v == f64::NEG_INFINITY {
+                    return Ok(());
+                }
+                let delta = f64::from(delta);
+                self.set_value(((v / delta) + 0.5).floor() * delta);
+                Ok(())
+            }
+        }
+    };
+}
+
 macro_rules! display_semiring {
     ($semiring:tt) => {
         use std::fmt;
@@ -174,6 +198,25 @@ macro_rules! partial_eq_and_hash_f32 {
     };
 }
 
+macro_rules! partial_eq_and_hash_f64 {
+    ($semiring:tt) => {
+        impl PartialEq for $semiring {
+            fn eq(&self, other: &Self) -> bool {
+                let w1 = *self.value();
+                let w2 = *other.value();
+                let delta = f64::from(KDELTA);
+                w1 <= (w2 + delta) && w2 <= (w1 + delta)
+            }
+        }
+
+        impl Hash for $semiring {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.value.hash(state)
+            }
+        }
+    };
+}
+
 pub trait SerializableSemiring: Semiring + Display {
     fn weight_type() -> String;
     fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>>;
@@ -16,7 +16,7 @@ use crate::semirings::{
     DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
     WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::Label;
+use crate::{Error, Label};
 
 /// String semiring: (identity, ., Infinity, Epsilon)
 #[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Hash)]
@@ -93,11 +93,11 @@ macro_rules! string_semiring {
                     match $string_type {
                         StringType::StringRestrict => {
                             if self != rhs.borrow() {
-                                bail!(
+                                bail!(Error::SemiringCapability(format!(
                                     "Unequal arguments : non-functional FST ? w1 = {:?} w2 = {:?}",
                                     &self,
                                     &rhs.borrow()
-                                );
+                                )));
                             }
                         }
                         StringType::StringLeft => {
@@ -342,7 +342,9 @@ fn divide_right(w1: &StringWeightVariant, w2: &StringWeightVariant) -> StringWei
 impl WeaklyDivisibleSemiring for StringWeightLeft {
     fn divide_assign(&mut self, rhs: &Self, divide_type: DivideType) -> Result<()> {
         if divide_type != DivideType::DivideLeft {
-            bail!("Only left division is defined.");
+            bail!(Error::SemiringCapability(
+                "Only left division is defined.".to_string()
+            ));
         }
         self.value = divide_left(&self.value, &rhs.value);
         Ok(())
@@ -352,7 +354,9 @@ impl WeaklyDivisibleSemiring for StringWeightLeft {
 impl WeaklyDivisibleSemiring for StringWeightRight {
     fn divide_assign(&mut self, rhs: &Self, divide_type: DivideType) -> Result<()> {
         if divide_type != DivideType::DivideRight {
-            bail!("Only right division is defined.");
+            bail!(Error::SemiringCapability(
+                "Only right division is defined.".to_string()
+            ));
         }
         self.value = divide_right(&self.value, &rhs.value);
         Ok(())
@@ -364,7 +368,9 @@ impl WeaklyDivisibleSemiring for StringWeightRestrict {
         self.value = match divide_type {
             DivideType::DivideLeft => divide_left(&self.value, &rhs.value),
             DivideType::DivideRight => divide_right(&self.value, &rhs.value),
-            DivideType::DivideAny => bail!("Only explicit left or right division is defined."),
+            DivideType::DivideAny => bail!(Error::SemiringCapability(
+                "Only explicit left or right division is defined.".to_string()
+            )),
         };
         Ok(())
     }
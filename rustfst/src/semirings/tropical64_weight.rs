@@ -0,0 +1,174 @@
+use std::borrow::Borrow;
+use std::f64;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use anyhow::Result;
+use nom::number::complete::{double, le_f64};
+use nom::IResult;
+use ordered_float::OrderedFloat;
+
+use crate::parsers::bin_fst::utils_serialization::write_bin_f64;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::semirings::semiring::SerializableSemiring;
+use crate::semirings::utils_float::float64_approx_equal;
+use crate::semirings::{
+    CompleteSemiring, CostWeight, DivideType, ReverseBack, Semiring, SemiringProperties,
+    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::KDELTA;
+
+/// Tropical semiring with double precision: (min, +, inf, 0).
+#[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
+pub struct Tropical64Weight {
+    value: OrderedFloat<f64>,
+}
+
+impl Semiring for Tropical64Weight {
+    type Type = f64;
+    type ReverseWeight = Tropical64Weight;
+
+    fn zero() -> Self {
+        Self {
+            value: OrderedFloat(f64::INFINITY),
+        }
+    }
+
+    fn one() -> Self {
+        Self {
+            value: OrderedFloat(0.0),
+        }
+    }
+
+    fn new(value: <Self as Semiring>::Type) -> Self {
+        Tropical64Weight {
+            value: OrderedFloat(value),
+        }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        if rhs.borrow().value < self.value {
+            self.value = rhs.borrow().value;
+        }
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let f1 = self.value();
+        let f2 = rhs.borrow().value();
+        if f1.eq(&f64::INFINITY) {
+        } else if f2.eq(&f64::INFINITY) {
+            self.value.0 = *f2;
+        } else {
+            self.value.0 += f2;
+        }
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        float64_approx_equal(self.value.0, rhs.borrow().value.0, delta)
+    }
+
+    fn value(&self) -> &Self::Type {
+        &self.value.0
+    }
+
+    fn take_value(self) -> Self::Type {
+        self.value.0
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.value.0 = value
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        Ok(*self)
+    }
+
+    fn properties() -> SemiringProperties {
+        SemiringProperties::LEFT_SEMIRING
+            | SemiringProperties::RIGHT_SEMIRING
+            | SemiringProperties::COMMUTATIVE
+            | SemiringProperties::PATH
+            | SemiringProperties::IDEMPOTENT
+    }
+}
+
+impl ReverseBack<Tropical64Weight> for Tropical64Weight {
+    fn reverse_back(&self) -> Result<Tropical64Weight> {
+        Ok(*self)
+    }
+}
+
+impl AsRef<Tropical64Weight> for Tropical64Weight {
+    fn as_ref(&self) -> &Tropical64Weight {
+        &self
+    }
+}
+
+display_semiring!(Tropical64Weight);
+
+impl CompleteSemiring for Tropical64Weight {}
+
+impl StarSemiring for Tropical64Weight {
+    fn closure(&self) -> Self {
+        if self.value.is_sign_positive() && self.value.is_finite() {
+            Self::new(0.0)
+        } else {
+            Self::new(f64::NEG_INFINITY)
+        }
+    }
+}
+
+impl WeaklyDivisibleSemiring for Tropical64Weight {
+    fn divide_assign(&mut self, rhs: &Self, _divide_type: DivideType) -> Result<()> {
+        self.value.0 -= rhs.value.0;
+        Ok(())
+    }
+}
+
+impl_quantize_f64!(Tropical64Weight);
+
+impl CostWeight for Tropical64Weight {
+    fn to_cost(&self) -> f64 {
+        self.value.0
+    }
+
+    fn from_cost(cost: f64) -> Self {
+        Self::new(cost)
+    }
+}
+
+partial_eq_and_hash_f64!(Tropical64Weight);
+
+impl SerializableSemiring for Tropical64Weight {
+    fn weight_type() -> String {
+        "tropical64".to_string()
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, weight) = le_f64(i)?;
+        Ok((i, Self::new(weight)))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        write_bin_f64(file, *self.value())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let (i, f) = double(i)?;
+        Ok((i, Self::new(f)))
+    }
+}
+
+test_semiring_serializable!(
+    tests_tropical64_weight_serializable,
+    Tropical64Weight,
+    Tropical64Weight::one() Tropical64Weight::zero() Tropical64Weight::new(0.3) Tropical64Weight::new(0.5) Tropical64Weight::new(0.0) Tropical64Weight::new(-1.2)
+);
+
+impl Into<Tropical64Weight> for f64 {
+    fn into(self) -> Tropical64Weight {
+        Tropical64Weight::new(self)
+    }
+}
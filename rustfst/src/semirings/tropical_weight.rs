@@ -13,8 +13,8 @@ use crate::parsers::nom_utils::NomCustomError;
 use crate::semirings::semiring::SerializableSemiring;
 use crate::semirings::utils_float::float_approx_equal;
 use crate::semirings::{
-    CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, StarSemiring,
-    WeaklyDivisibleSemiring, WeightQuantize,
+    CompleteSemiring, CostWeight, DivideType, ReverseBack, Semiring, SemiringProperties,
+    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
 };
 use crate::KDELTA;
 
@@ -129,6 +129,16 @@ impl WeaklyDivisibleSemiring for TropicalWeight {
 
 impl_quantize_f32!(TropicalWeight);
 
+impl CostWeight for TropicalWeight {
+    fn to_cost(&self) -> f64 {
+        f64::from(self.value.0)
+    }
+
+    fn from_cost(cost: f64) -> Self {
+        Self::new(cost as f32)
+    }
+}
+
 partial_eq_and_hash_f32!(TropicalWeight);
 
 impl SerializableSemiring for TropicalWeight {
@@ -19,6 +19,7 @@ use crate::semirings::{
     DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
     WeaklyDivisibleSemiring, WeightQuantize,
 };
+use crate::Error;
 
 pub trait UnionWeightOption<W: Semiring>:
     Debug + Hash + Clone + PartialOrd + Eq + Sync + 'static
@@ -235,7 +236,9 @@ where
                 quot.push_back(v.divide(&rhs.list[0], divide_type)?, true)?;
             }
         } else {
-            bail!("Expected at least of the two parameters to have a single element");
+            bail!(Error::SemiringCapability(
+                "Expected at least of the two parameters to have a single element".to_string()
+            ));
         }
         self.set_value(quot.take_value());
         Ok(())
@@ -3,12 +3,13 @@ use std::fs::{read, File};
 use std::io::{BufWriter, LineWriter, Write};
 use std::path::Path;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use itertools::Itertools;
 
 use crate::parsers::bin_symt::nom_parser::{parse_symbol_table_bin, write_bin_symt};
+use crate::parsers::nom_utils::NomCustomError;
 use crate::parsers::text_symt::parsed_text_symt::ParsedTextSymt;
-use crate::{Label, EPS_SYMBOL};
+use crate::{Error, Label, EPS_SYMBOL};
 use std::collections::hash_map::{Entry, RandomState};
 use std::collections::HashMap;
 use std::hash::BuildHasher;
@@ -16,9 +17,42 @@ use std::hash::BuildHasher;
 /// A symbol table stores a bidirectional mapping between transition labels and "symbols" (strings).
 #[derive(Debug, Clone)]
 pub struct SymbolTable<H: BuildHasher = RandomState> {
+    name: String,
     bimap: BiHashMapString<H>,
 }
 
+/// Name given to the `SymbolTable`s created without one.
+pub(crate) static DEFAULT_SYMT_NAME: &str = "rustfst_symboltable";
+
+/// Length in bytes of the checksums computed on a `SymbolTable`.
+const CHECKSUM_LENGTH: usize = 32;
+
+/// Checksum computed the same way as in OpenFst : bytes are XORed into a fixed size buffer.
+struct CheckSummer {
+    count: usize,
+    checksum: [u8; CHECKSUM_LENGTH],
+}
+
+impl CheckSummer {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            checksum: [0; CHECKSUM_LENGTH],
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.checksum[self.count % CHECKSUM_LENGTH] ^= *b;
+            self.count += 1;
+        }
+    }
+
+    fn digest(self) -> Vec<u8> {
+        self.checksum.to_vec()
+    }
+}
+
 impl Default for SymbolTable {
     fn default() -> Self {
         Self::new()
@@ -43,42 +77,53 @@ impl SymbolTable {
 
     pub fn empty() -> Self {
         SymbolTable {
+            name: DEFAULT_SYMT_NAME.to_string(),
             bimap: BiHashMapString::new(),
         }
     }
 
-    fn from_parsed_symt_text(parsed_symt_text: ParsedTextSymt) -> Result<Self> {
+    fn from_parsed_symt_text(parsed_symt_text: ParsedTextSymt) -> Result<Self, Error> {
         let mut bimap = BiHashMapString::new();
-        for (symbol, label) in parsed_symt_text.pairs.into_iter() {
+        // Each pair is parsed from a line.
+        for (line, (symbol, label)) in parsed_symt_text.pairs.into_iter().enumerate() {
             let inserted_label = bimap.get_id_or_insert(symbol);
             if inserted_label != label {
-                bail!("The SymbolTable should contain labels with increasing ids and no hole. Expected {} and got {}", inserted_label, label)
+                return Err(Error::Parse {
+                    context: "text SymbolTable",
+                    line: Some(line + 1),
+                    error: Some(NomCustomError::SymbolTableError(format!(
+                        "The SymbolTable should contain labels with increasing ids and no hole. Expected {} and got {}",
+                        inserted_label, label
+                    ))),
+                });
             }
         }
 
-        Ok(SymbolTable { bimap })
+        Ok(SymbolTable {
+            name: DEFAULT_SYMT_NAME.to_string(),
+            bimap,
+        })
     }
 
-    pub fn from_text_string(symt_string: &str) -> Result<Self> {
+    pub fn from_text_string(symt_string: &str) -> Result<Self, Error> {
         let parsed_symt = ParsedTextSymt::from_string(symt_string)?;
         Self::from_parsed_symt_text(parsed_symt)
     }
 
-    pub fn read_text<P: AsRef<Path>>(path_text_symt: P) -> Result<Self> {
+    pub fn read_text<P: AsRef<Path>>(path_text_symt: P) -> Result<Self, Error> {
+        let name = path_text_symt.as_ref().to_string_lossy().to_string();
         let parsed_symt = ParsedTextSymt::from_path(path_text_symt)?;
-        Self::from_parsed_symt_text(parsed_symt)
+        let mut symt = Self::from_parsed_symt_text(parsed_symt)?;
+        symt.set_name(name);
+        Ok(symt)
     }
 
-    pub fn read<P: AsRef<Path>>(path_bin_symt: P) -> Result<Self> {
-        let data = read(path_bin_symt.as_ref()).with_context(|| {
-            format!(
-                "Can't open SymbolTable binary file : {:?}",
-                path_bin_symt.as_ref()
-            )
-        })?;
+    pub fn read<P: AsRef<Path>>(path_bin_symt: P) -> Result<Self, Error> {
+        let data = read(path_bin_symt.as_ref())
+            .map_err(|e| Error::read_file("SymbolTable binary file", path_bin_symt.as_ref(), e))?;
 
         let (_, symt) = parse_symbol_table_bin(&data)
-            .map_err(|e| format_err!("Error while parsing binary SymbolTable : {:?}", e))?;
+            .map_err(|e| Error::from_nom("binary SymbolTable", data.len(), e))?;
 
         Ok(symt)
     }
@@ -88,7 +133,44 @@ impl<H: BuildHasher> SymbolTable<H> {
     pub fn with_hasher(hasher_builder: H) -> Self {
         let mut bimap = BiHashMapString::with_hasher(hasher_builder);
         bimap.get_id_or_insert(EPS_SYMBOL);
-        Self { bimap }
+        Self {
+            name: DEFAULT_SYMT_NAME.to_string(),
+            bimap,
+        }
+    }
+
+    /// Name of the `SymbolTable`. Tables read from a text file are named after the path of the file.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Checksum of the symbols stored in the table, irrespective of their labels.
+    /// Same value as the one returned by `CheckSum` in OpenFst.
+    pub fn checksum(&self) -> Vec<u8> {
+        let mut checksummer = CheckSummer::new();
+        for label in 0..self.len() {
+            if let Some(symbol) = self.get_symbol(label) {
+                checksummer.update(symbol.as_bytes());
+                checksummer.update(&[0]);
+            }
+        }
+        checksummer.digest()
+    }
+
+    /// Checksum of the (symbol, label) pairs stored in the table.
+    /// Same value as the one returned by `LabeledCheckSum` in OpenFst.
+    pub fn labeled_checksum(&self) -> Vec<u8> {
+        let mut checksummer = CheckSummer::new();
+        for label in 0..self.len() {
+            if let Some(symbol) = self.get_symbol(label) {
+                checksummer.update(format!("{}\t{}", symbol, label).as_bytes());
+            }
+        }
+        checksummer.digest()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -281,6 +363,30 @@ impl<H: BuildHasher> SymbolTable<H> {
     }
 }
 
+/// Returns true if the two symbol tables are compatible, i.e they map the same labels to the same
+/// symbols. A missing symbol table is compatible with any other one.
+///
+/// The comparison is done on the labeled checksums, like `CompatSymbols` in OpenFst.
+///
+/// # Examples
+/// ```rust
+/// # #[macro_use] extern crate rustfst; fn main() {
+/// # use rustfst::{compat_symbols, SymbolTable};
+/// let symt_1 = symt!["a", "b"];
+/// let symt_2 = symt!["b", "a"];
+///
+/// assert!(compat_symbols(Some(&symt_1), Some(&symt_1.clone())));
+/// assert!(!compat_symbols(Some(&symt_1), Some(&symt_2)));
+/// assert!(compat_symbols(Some(&symt_1), None));
+/// # }
+/// ```
+pub fn compat_symbols(symt_1: Option<&SymbolTable>, symt_2: Option<&SymbolTable>) -> bool {
+    match (symt_1, symt_2) {
+        (Some(symt_1), Some(symt_2)) => symt_1.labeled_checksum() == symt_2.labeled_checksum(),
+        _ => true,
+    }
+}
+
 impl<H: BuildHasher> fmt::Display for SymbolTable<H> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (label, symbol) in self.iter().sorted_by_key(|k| k.0) {
@@ -452,4 +558,72 @@ mod tests {
         assert_eq!(symt1.get_label("b"), Some(2));
         assert_eq!(symt1.get_label("c"), Some(3));
     }
+
+    #[test]
+    fn test_symt_name() {
+        let mut symt = SymbolTable::new();
+        assert_eq!(symt.name(), DEFAULT_SYMT_NAME);
+
+        symt.set_name("words");
+        assert_eq!(symt.name(), "words");
+    }
+
+    #[test]
+    fn test_symt_checksums() {
+        let symt1 = symt!["a", "b"];
+        let symt2 = symt!["b", "a"];
+
+        let mut checksum_ref = vec![0; CHECKSUM_LENGTH];
+        for (i, b) in b"<eps>\0a\0b\0".iter().enumerate() {
+            checksum_ref[i] = *b;
+        }
+        assert_eq!(symt1.checksum(), checksum_ref);
+
+        let mut labeled_checksum_ref = vec![0; CHECKSUM_LENGTH];
+        for (i, b) in b"<eps>\t0a\t1b\t2".iter().enumerate() {
+            labeled_checksum_ref[i] = *b;
+        }
+        assert_eq!(symt1.labeled_checksum(), labeled_checksum_ref);
+
+        assert_ne!(symt1.labeled_checksum(), symt2.labeled_checksum());
+        assert!(compat_symbols(Some(&symt1), Some(&symt1.clone())));
+        assert!(!compat_symbols(Some(&symt1), Some(&symt2)));
+        assert!(compat_symbols(None, Some(&symt2)));
+    }
+
+    #[test]
+    fn test_symt_checksum_wraps_around() {
+        let mut symt = SymbolTable::empty();
+        symt.add_symbol("a".repeat(CHECKSUM_LENGTH + 1));
+
+        let mut checksum_ref = vec![b'a'; CHECKSUM_LENGTH];
+        // The 33rd `a` cancels the first one and the trailing `\0` leaves the second one unchanged.
+        checksum_ref[0] = 0;
+        assert_eq!(symt.checksum(), checksum_ref);
+    }
+
+    #[test]
+    fn test_symt_from_text_string() -> Result<()> {
+        let symt = SymbolTable::from_text_string("<eps>\t0\na\t1\nb\t2")?;
+        assert_eq!(symt.len(), 3);
+        assert_eq!(symt.get_label("b"), Some(2));
+
+        match SymbolTable::from_text_string("<eps>\t0\na\t1\nb\t3\n").unwrap_err() {
+            Error::Parse {
+                context: "text SymbolTable",
+                line: Some(3),
+                ..
+            } => {}
+            e => panic!("Unexpected error : {}", e),
+        }
+        match SymbolTable::from_text_string("<eps>\t0\na\t1\nb 2\n").unwrap_err() {
+            Error::Parse {
+                context: "text SymbolTable",
+                line: Some(3),
+                ..
+            } => {}
+            e => panic!("Unexpected error : {}", e),
+        }
+        Ok(())
+    }
 }
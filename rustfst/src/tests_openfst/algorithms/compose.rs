@@ -105,15 +105,6 @@ impl MatcherFlagsTrait for DefaultLabelLookAheadMatcherFlags {
     }
 }
 
-#[derive(Debug, Clone, PartialOrd, PartialEq)]
-pub struct OLabelLookAheadFlags {}
-
-impl MatcherFlagsTrait for OLabelLookAheadFlags {
-    fn flags() -> MatcherFlags {
-        MatcherFlags::OLABEL_LOOKAHEAD_FLAGS
-    }
-}
-
 fn do_test_compose_lookahead<W>(
     fst_raw: &VectorFst<W>,
     compose_test_data: &ComposeTestData<W, VectorFst<W>>,
@@ -192,7 +183,14 @@ where
     let fst1: VectorFst<_> = fst_raw.clone().into();
     let mut fst2: VectorFst<_> = compose_test_data.fst_2.clone();
 
-    let graph1look = Arc::new(TLaFst::new_with_relabeling(fst1, &mut fst2, true)?);
+    let graph1look = TLaFst::new_with_relabeling(fst1, &mut fst2, true)?;
+
+    // The MatcherFst must be usable once serialized and deserialized.
+    let mut serialized_graph1look = vec![];
+    graph1look.store(&mut serialized_graph1look)?;
+    let graph1look_loaded = TLaFst::load(&serialized_graph1look)?;
+    assert_eq!(graph1look_loaded.addon(), graph1look.addon());
+    let graph1look = Arc::new(graph1look_loaded);
 
     // LabelLookAheadRelabeler::relabel(&mut fst2, graph1look.addon(), true)?;
 
@@ -13,6 +13,7 @@ use crate::semirings::WeaklyDivisibleSemiring;
 use crate::semirings::WeightQuantize;
 use crate::tests_openfst::utils::test_isomorphic_fst;
 use crate::tests_openfst::FstTestData;
+use crate::Error;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DeterminizeOperationResult {
@@ -46,7 +47,9 @@ impl DeterminizeOperationResult {
             },
             result: match self.result_path.as_str() {
                 "error" => Err(format_err!("lol")),
-                _ => F::read(dir_path.as_ref().join(&self.result_path)),
+                _ => {
+                    F::read(dir_path.as_ref().join(&self.result_path)).map_err(anyhow::Error::from)
+                }
             },
             w: PhantomData,
         }
@@ -60,7 +63,7 @@ where
 {
     for determinize_data in &test_data.determinize {
         let config = DeterminizeConfig::default().with_det_type(determinize_data.det_type);
-        let fst_res: Result<F> = determinize_with_config(&test_data.raw, config);
+        let fst_res: Result<F, Error> = determinize_with_config(&test_data.raw, config);
 
         match (&determinize_data.result, fst_res) {
             (Ok(fst_expected), Ok(ref fst_determinized)) => {
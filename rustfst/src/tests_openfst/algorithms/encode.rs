@@ -1,13 +1,15 @@
 use std::fmt::Display;
+use std::fs::read;
 use std::marker::PhantomData;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{format_err, Context, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::algorithms::encode::{decode, encode, EncodeType};
+use crate::algorithms::encode::{decode, decode_with_table, encode, EncodeTable, EncodeType};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{MutableFst, SerializableFst};
+use crate::parsers::bin_fst::fst_header::FST_MAGIC_NUMBER;
 use crate::semirings::{SerializableSemiring, WeightQuantize};
 use crate::tests_openfst::utils::test_eq_fst;
 use crate::tests_openfst::FstTestData;
@@ -17,6 +19,7 @@ pub struct EncodeOperationResult {
     encode_labels: bool,
     encode_weights: bool,
     result_path: String,
+    encoder_path: Option<String>,
 }
 
 pub struct EncodeTestData<W, F>
@@ -26,6 +29,8 @@ where
 {
     pub encode_type: EncodeType,
     pub result: F,
+    /// Encode table saved by OpenFst, as with `fstencode --save_encoder`.
+    pub encoder_path: Option<PathBuf>,
     w: PhantomData<W>,
 }
 
@@ -39,6 +44,10 @@ impl EncodeOperationResult {
         EncodeTestData {
             encode_type: EncodeType::from_bools(self.encode_weights, self.encode_labels).unwrap(),
             result: F::read(dir_path.as_ref().join(&self.result_path)).unwrap(),
+            encoder_path: self
+                .encoder_path
+                .as_ref()
+                .map(|p| dir_path.as_ref().join(p)),
             w: PhantomData,
         }
     }
@@ -101,3 +110,48 @@ where
     }
     Ok(())
 }
+
+pub fn test_encode_table<W, F>(test_data: &FstTestData<W, F>) -> Result<()>
+where
+    F: SerializableFst<W> + MutableFst<W> + Display,
+    W: SerializableSemiring + WeightQuantize,
+{
+    for (encode_test_data, encode_decode_test_data) in
+        test_data.encode.iter().zip(test_data.encode_decode.iter())
+    {
+        let encoder_path = match &encode_test_data.encoder_path {
+            Some(p) => p,
+            None => continue,
+        };
+        let encode_table = EncodeTable::<W>::read(encoder_path)?;
+        assert_eq!(encode_table.encode_type(), encode_test_data.encode_type);
+
+        // The FST encoded by OpenFst is decoded with the table it saved.
+        let mut fst_decoded = encode_test_data.result.clone();
+        decode_with_table(&mut fst_decoded, &encode_table)?;
+        test_eq_fst(
+            &encode_decode_test_data.result,
+            &fst_decoded,
+            format!(
+                "Decode with an OpenFst encode table encode_type={:?}",
+                encode_test_data.encode_type
+            ),
+        );
+
+        let data = read(encoder_path)?;
+        let mut data_written = vec![];
+        encode_table.store(&mut data_written)?;
+        if data[..4] == FST_MAGIC_NUMBER.to_le_bytes() {
+            assert_eq!(data_written, data);
+        } else {
+            // Tables in the former layout are written back with an FST header.
+            let encode_table_written = EncodeTable::<W>::load(&data_written)?;
+            assert_eq!(encode_table_written.len(), encode_table.len());
+            assert_eq!(
+                encode_table_written.encode_type(),
+                encode_test_data.encode_type
+            );
+        }
+    }
+    Ok(())
+}
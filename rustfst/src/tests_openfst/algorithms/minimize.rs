@@ -11,6 +11,7 @@ use crate::semirings::WeaklyDivisibleSemiring;
 use crate::semirings::WeightQuantize;
 use crate::tests_openfst::utils::test_eq_fst;
 use crate::tests_openfst::FstTestData;
+use crate::Error;
 use std::path::Path;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -43,7 +44,9 @@ impl MinimizeOperationResult {
             allow_nondet: self.allow_nondet,
             result: match self.result_path.as_str() {
                 "error" => Err(format_err!("lol")),
-                _ => F::read(dir_path.as_ref().join(&self.result_path)),
+                _ => {
+                    F::read(dir_path.as_ref().join(&self.result_path)).map_err(anyhow::Error::from)
+                }
             },
             w: PhantomData,
         }
@@ -59,7 +62,7 @@ where
     for minimize_data in &test_data.minimize {
         //        println!("Minimize : allow_nondet = {}", minimize_data.allow_nondet);
         let mut fst_raw = test_data.raw.clone();
-        let fst_res: Result<F> = minimize_with_config(
+        let fst_res: Result<F, Error> = minimize_with_config(
             &mut fst_raw,
             MinimizeConfig::new(minimize_data.delta, minimize_data.allow_nondet),
         )
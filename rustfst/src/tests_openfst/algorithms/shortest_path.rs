@@ -12,7 +12,7 @@ use crate::semirings::WeightQuantize;
 use crate::semirings::{Semiring, SerializableSemiring};
 use crate::tests_openfst::utils::test_correctness_properties;
 use crate::tests_openfst::FstTestData;
-use crate::FstPath;
+use crate::{Error, FstPath};
 use std::path::Path;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -45,7 +45,9 @@ impl ShorestPathOperationResult {
             nshortest: self.nshortest,
             result: match self.result_path.as_str() {
                 "error" => Err(format_err!("lol")),
-                _ => F::read(dir_path.as_ref().join(&self.result_path)),
+                _ => {
+                    F::read(dir_path.as_ref().join(&self.result_path)).map_err(anyhow::Error::from)
+                }
             },
             w: PhantomData,
         }
@@ -63,7 +65,7 @@ where
         let config = ShortestPathConfig::default()
             .with_nshortest(data.nshortest)
             .with_unique(data.unique);
-        let fst_res: Result<F> = shortest_path_with_config(&test_data.raw, config);
+        let fst_res: Result<F, Error> = shortest_path_with_config(&test_data.raw, config);
         match (&data.result, &fst_res) {
             (Ok(fst_expected), Ok(ref fst_shortest)) => {
                 // Comparing directly the fsts doesn't work because there is undefined behaviour
@@ -5,6 +5,7 @@ use crate::fst_traits::{Fst, SerializableFst};
 use crate::semirings::{SerializableSemiring, WeightQuantize};
 use crate::tests_openfst::utils::test_eq_fst;
 use crate::tests_openfst::FstTestData;
+use crate::{BinaryFstTypes, Tr};
 
 pub fn test_vector_fst_bin_deserializer<W>(test_data: &FstTestData<W, VectorFst<W>>) -> Result<()>
 where
@@ -38,3 +39,14 @@ where
     );
     Ok(())
 }
+
+pub fn test_vector_fst_bin_types<W>(test_data: &FstTestData<W, VectorFst<W>>) -> Result<()>
+where
+    W: SerializableSemiring + WeightQuantize,
+{
+    let types = BinaryFstTypes::read(&test_data.raw_vector_bin_path)?;
+
+    assert_eq!(types.fst_type, VectorFst::<W>::fst_type());
+    assert_eq!(types.tr_type, Tr::<W>::tr_type());
+    Ok(())
+}
@@ -28,6 +28,24 @@ where
     Ok(())
 }
 
+pub fn test_vector_fst_bin_store_load<W>(test_data: &FstTestData<W, VectorFst<W>>) -> Result<()>
+where
+    W: SerializableSemiring + WeightQuantize,
+{
+    let mut data = vec![];
+    test_data.raw.store(&mut data)?;
+
+    let deserialized_fst = VectorFst::<W>::load(&data)?;
+
+    test_eq_fst(
+        &test_data.raw,
+        &deserialized_fst,
+        "Store and load VectorFst Bin",
+    );
+
+    Ok(())
+}
+
 pub fn test_vector_fst_bin_serializer_with_symt<W>(
     test_data: &FstTestData<W, VectorFst<W>>,
 ) -> Result<()>
@@ -56,9 +56,11 @@ use crate::tests_openfst::io::const_fst_text_deserialization::test_const_fst_tex
 use crate::tests_openfst::io::const_fst_text_serialization::test_const_fst_text_serialization;
 use crate::tests_openfst::io::const_fst_text_serialization::test_const_fst_text_serialization_with_symt;
 use crate::tests_openfst::io::vector_fst_bin_deserializer::test_vector_fst_bin_deserializer;
+use crate::tests_openfst::io::vector_fst_bin_deserializer::test_vector_fst_bin_types;
 use crate::tests_openfst::io::vector_fst_bin_deserializer::test_vector_fst_bin_with_symt_deserializer;
 use crate::tests_openfst::io::vector_fst_bin_serializer::{
     test_vector_fst_bin_serializer, test_vector_fst_bin_serializer_with_symt,
+    test_vector_fst_bin_store_load,
 };
 use crate::tests_openfst::io::vector_fst_text_deserialization::test_vector_fst_text_deserialization;
 use crate::tests_openfst::io::vector_fst_text_serialization::{
@@ -69,7 +71,9 @@ use self::algorithms::{
     compose::test_compose,
     connect::test_connect,
     determinize::{test_determinize, DeterminizeOperationResult, DeterminizeTestData},
-    encode::{test_encode, test_encode_decode, EncodeOperationResult, EncodeTestData},
+    encode::{
+        test_encode, test_encode_decode, test_encode_table, EncodeOperationResult, EncodeTestData,
+    },
     inverse::test_invert,
     minimize::{test_minimize, MinimizeOperationResult, MinimizeTestData},
     project::{test_project_input, test_project_output},
@@ -542,6 +546,12 @@ macro_rules! test_fst {
                 Ok(())
             }
 
+            #[test]
+            fn test_encode_table_openfst() -> Result<()> {
+                do_run!(test_encode_table, $fst_name);
+                Ok(())
+            }
+
             #[test]
             fn test_factor_weight_gallic_openfst() -> Result<()> {
                 do_run!(test_factor_weight_gallic, $fst_name);
@@ -680,6 +690,12 @@ macro_rules! test_fst {
                 Ok(())
             }
 
+            #[test]
+            fn test_vector_fst_bin_store_load_openfst() -> Result<()> {
+                do_run!(test_vector_fst_bin_store_load, $fst_name);
+                Ok(())
+            }
+
             #[test]
             fn test_vector_fst_bin_serializer_with_symt_openfst() -> Result<()> {
                 do_run!(test_vector_fst_bin_serializer_with_symt, $fst_name);
@@ -698,6 +714,12 @@ macro_rules! test_fst {
                 Ok(())
             }
 
+            #[test]
+            fn test_vector_fst_bin_types_openfst() -> Result<()> {
+                do_run!(test_vector_fst_bin_types, $fst_name);
+                Ok(())
+            }
+
             #[test]
             fn test_weight_pushing_final_openfst() -> Result<()> {
                 do_run!(test_weight_pushing_final, $fst_name);
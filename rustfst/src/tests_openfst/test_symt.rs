@@ -62,9 +62,12 @@ fn run_test_openfst_symt(test_name: &str) -> Result<()> {
         symt.write(&path_symt_serialized)?;
         let symt2 = SymbolTable::read(path_symt_serialized)?;
         assert_eq!(symt_bin, symt2);
+        assert_eq!(symt.name(), symt2.name());
     }
 
     assert_eq!(symt, symt_bin);
+    assert_eq!(symt.checksum(), symt_bin.checksum());
+    assert_eq!(symt.labeled_checksum(), symt_bin.labeled_checksum());
 
     Ok(())
 }